@@ -1,26 +1,69 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::str::FromStr;
 
 use cosmwasm_std::{
-    attr, entry_point, to_json_binary, Addr, Attribute, Binary, Decimal, Deps, DepsMut, Env,
-    MessageInfo, Order, Response, StdError, StdResult, SubMsg, Uint128, Uint64,
+    attr, entry_point, from_json, to_json_binary, wasm_execute, Addr, Attribute, Binary, CosmosMsg,
+    Decimal, Deps, DepsMut, Empty, Env, Event, MessageInfo, Order, ReplyOn, Response, StdError,
+    StdResult, Storage, SubMsg, Uint128, Uint64,
 };
-use cw2::{set_contract_version};
+use cw2::{get_contract_version, set_contract_version};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::{Bound, PrefixBound};
 
-use astroport::asset::{Asset, AssetInfo};
+use astroport::asset::{native_asset_info, token_asset_info, Asset, AssetInfo, PairInfo};
 use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use astroport::factory::PairType;
 use astroport::maker::{
-    AssetWithLimit, BalancesResponse, Config, ConfigResponse, ExecuteMsg, InstantiateMsg,
-    QueryMsg,
+    AssetSimulation, AssetStats, AssetTypeFilter, AssetWithLimit, Auction, AuctionFallback,
+    AuctionResponse, BalancesResponse, BeliefPriceSource, BridgeCall, BridgeCallResponse,
+    BridgeInfo, BridgeProposal, BridgesResponse, BrokenRoute, BurnDestinationResponse,
+    CanOnboardResponse, CollectStatusResponse, CollectorRecord, Config, ConfigChange,
+    ConfigHistoryEntry, ConfigOverrides, ConfigResponse, Cw20HookMsg, DevFundConfig,
+    DirectPairsResponse, DistributeResponseData, DistributionHookMsg, DistributionMode,
+    EffectiveConfigResponse, ExecuteMsg, ExternalDex, FailOrSkip, IbcLifecycleComplete,
+    InstantiateMsg, KeeperCommitment, MaxCollectNowResponse, MigrateMsg, MinReceived,
+    PermissionsResponse, PreUpgradeRewards, PriceOracle, QueryMsg, Role, RouteHop, RouteResponse,
+    RoutesChangedSinceResponse, SecondReceiverConfig, SecondReceiverParams, SeizeProposal,
+    SimulateCollectResponse, StatisticsResponse, SudoMsg, SupplyImpactResponse,
+    SwapPreviewResponse, SwapSimulation, DEFAULT_CONFIG_CHANGE_DELAY_SECS,
+    DEFAULT_SEIZE_DELAY_SECS, MAX_SECOND_RECEIVER_CUT_BPS,
 };
 use astroport::pair::MAX_ALLOWED_SLIPPAGE;
+use astroport::querier::{query_pairs_info, simulate};
+use astroport_circular_buffer::BufferManager;
 
 use crate::error::ContractError;
-use crate::state::{BRIDGES, CONFIG, LAST_COLLECT_TS, OWNERSHIP_PROPOSAL};
+use crate::migration::{migrate_bridges, migrate_from_astroport};
+use crate::reply::{
+    BURN_REPLY_ID, COLLECT_SETTLED_REPLY_ID, DISTRIBUTION_HOOK_REPLY_ID,
+    QUARANTINE_REPLY_ID_OFFSET, SWAP_REPLY_ID,
+};
+use crate::state::{
+    DiscoveredRoute, RouteProgress, ValueWindow, ASSET_STATS, AUCTIONS, BLACKLIST, BRIDGE_CHANGES,
+    BRIDGE_PROPOSALS, BURNING_ROIDS, BURN_RETRIES, COLLECTS_SINCE_BURN, COLLECT_FAILED, COLLECT_ID,
+    COLLECT_MIN_ROIDS_OUT, COLLECT_ROIDS_OUT, COLLECT_VALUE_WINDOW, CONFIG, CONFIG_HISTORY,
+    CONSECUTIVE_FAILED_COLLECTS, COOLDOWN_EXEMPT, COOLDOWN_WINDOW_END, DECIMALS, DISCOVERED_ROUTES,
+    DISTRIBUTION_HOOKS, DONATIONS, DUST_BRIDGE_BALANCES, EXTERNAL_DEXES, FAILED_ASSETS,
+    IBC_TRANSFERS, KEEPER_COMMITMENT, LAST_COLLECT_TS, LAST_FLUSH_TS, MIN_RECEIVED,
+    OWNERSHIP_PROPOSAL, PAUSED, PENDING_BURN, PENDING_COLLECT, PENDING_COLLECTOR,
+    PENDING_CONFIG_CHANGE, PRE_UPGRADE_REWARDS, PRICE_ORACLES, QUARANTINE_BATCH, RECENT_COLLECTORS,
+    RETURNED_TO_CIRCULATION, ROLES, ROUTES, ROUTES_RESET_HEIGHT, ROUTE_PROGRESS, SEIZE_PROPOSAL,
+    SPREADS, STATS, SWAPPED_ROIDS, SWAPS_START_ROIDS, SWAP_HOP, TOTAL_BURNED, UNPAUSED_AT,
+    WORST_HOP,
+};
 use crate::utils::{
-    build_distribute_msg, build_send_msg, build_swap_msg, try_build_swap_msg,
-    validate_bridge, validate_cooldown, BRIDGES_EXECUTION_MAX_DEPTH,
-    BRIDGES_INITIAL_DEPTH,
+    assert_role, auction_price, bridge_payload, build_dev_fund_msg, build_distribute_msg,
+    build_distribution_msg, build_swap_msg, check_bridge_approved, check_bridge_chain,
+    check_pool_liquidity, dust_bridge_amounts, execution_max_depth, get_asset_max_spread,
+    get_collect_amount, get_max_spread, get_next_hop, get_pool, get_swap_executor, is_dust,
+    load_bridges, load_routes, net_of_transfer_tax, record_burn, record_config_change,
+    record_pair_types, resolve_burn_destination, resolve_route, role_holder,
+    simulate_swap_to_roids, snapshot_dust_bridges, snapshot_token_balances, tag_collect_id,
+    validate_auction_prices, validate_bridge, validate_bridge_memo,
+    validate_bridges_execution_depth, validate_cooldown, validate_dev_fund,
+    validate_distribution_mode, validate_external_dex, validate_factory_pair, validate_keeper_fee,
+    validate_min_receive_buffer, validate_swap_deadline, validate_tokenfactory_burn, BridgeMemo,
+    BRIDGES_INITIAL_DEPTH, BRIDGES_MAX_DEPTH, CONCENTRATED_PAIR_TYPE, REBASE_DRIFT_BUFFER_BPS,
 };
 
 /// Contract name that is used for migration.
@@ -29,6 +72,36 @@ const CONTRACT_NAME: &str = "asteroid-maker";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 /// Sets the default maximum spread (as a percentage) used when swapping fee tokens to ASTRO.
 const DEFAULT_MAX_SPREAD: u64 = 5; // 5%
+/// The max number of assets considered by the MaxCollectNow query.
+const MAX_COLLECT_NOW_ASSETS: usize = 50;
+/// Max spreads below this percentage are considered tight by TuneRisk.
+const TIGHT_MAX_SPREAD: u64 = 1; // 1%
+/// Cooldowns below this number of seconds are considered short by TuneRisk.
+const SHORT_COLLECT_COOLDOWN: u64 = 60;
+/// The min number of seconds between two FlushRoids calls.
+const FLUSH_ROIDS_COOLDOWN: u64 = 60;
+/// The length (in seconds) of the collect value window when no collect cooldown is set.
+const DEFAULT_VALUE_WINDOW_SECS: u64 = 86400;
+/// The number of latest collects kept with their callers.
+const RECENT_COLLECTORS_CAPACITY: u32 = 20;
+/// The default number of collects returned by the RecentCollectors query.
+const DEFAULT_RECENT_COLLECTORS_LIMIT: u32 = 10;
+/// The max number of contracts called after each distribution.
+const MAX_DISTRIBUTION_HOOKS: u32 = 10;
+/// The gas each distribution hook can use. A hook running out of it fails on its own.
+const DISTRIBUTION_HOOK_GAS_LIMIT: u64 = 300_000;
+/// The default number of bridges returned by the Bridges query.
+const DEFAULT_BRIDGES_LIMIT: u32 = 10;
+/// The max number of bridges returned by the Bridges query.
+const MAX_BRIDGES_LIMIT: u32 = 30;
+/// The default number of entries returned by the ConfigHistory query.
+const DEFAULT_CONFIG_HISTORY_LIMIT: u32 = 10;
+/// The max number of entries returned by the ConfigHistory query.
+const MAX_CONFIG_HISTORY_LIMIT: u32 = 30;
+/// The default number of balances returned by an `AllBalances` query
+const DEFAULT_BALANCES_LIMIT: u32 = 10;
+/// The max number of balances returned by an `AllBalances` query
+const MAX_BALANCES_LIMIT: u32 = 30;
 
 /// Creates a new contract with the specified parameters in [`InstantiateMsg`].
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -39,7 +112,7 @@ pub fn instantiate(
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    
+
     let max_spread = if let Some(max_spread) = msg.max_spread {
         if max_spread.is_zero() || max_spread.gt(&Decimal::from_str(MAX_ALLOWED_SLIPPAGE)?) {
             return Err(ContractError::IncorrectMaxSpread {});
@@ -57,6 +130,7 @@ pub fn instantiate(
     }
 
     validate_cooldown(msg.collect_cooldown)?;
+    validate_swap_deadline(msg.swap_deadline_secs)?;
     LAST_COLLECT_TS.save(deps.storage, &env.block.time.seconds())?;
 
     let cfg = Config {
@@ -67,6 +141,40 @@ pub fn instantiate(
         factory_contract: deps.api.addr_validate(&msg.factory_contract)?,
         max_spread,
         collect_cooldown: msg.collect_cooldown,
+        swap_deadline_secs: msg.swap_deadline_secs,
+        approved_bridge_assets: None,
+        auto_pause_threshold: None,
+        burn_every_n_collects: None,
+        dust_threshold: None,
+        keeper_commit_window_secs: None,
+        min_receive_buffer_bps: None,
+        lenient_burn: false,
+        max_bridges: None,
+        rebasing_assets: vec![],
+        strict_collect: false,
+        post_unpause_grace_secs: None,
+        burn_retry_limit: None,
+        learn_routes: false,
+        keeper_fee_bps: None,
+        router_contract: None,
+        guardian: None,
+        seize_delay_secs: None,
+        min_collect_amount: None,
+        quarantine_failed_swaps: false,
+        second_receiver_cfg: None,
+        dev_fund_config: None,
+        max_collect_value_per_window: None,
+        distribution_mode: Default::default(),
+        bridge_call: None,
+        bridges_execution_max_depth: None,
+        tokenfactory_burn: false,
+        max_assets_per_tx: None,
+        belief_price_source: Default::default(),
+        on_spread_exceeded: Default::default(),
+        reserve_amount: None,
+        auction_fallback: None,
+        config_change_delay_secs: None,
+        operator: None,
     };
 
     CONFIG.save(deps.storage, &cfg)?;
@@ -81,10 +189,7 @@ pub fn instantiate(
         ),
         attr("roids_token", cfg.roids_token.to_string()),
         attr("factory_contract", msg.factory_contract),
-        attr(
-            "asteroid_contract",
-            msg.asteroid_contract,
-        ),
+        attr("asteroid_contract", msg.asteroid_contract),
         attr("max_spread", max_spread.to_string()),
     ]))
 }
@@ -92,17 +197,119 @@ pub fn instantiate(
 /// Exposes execute functions available in the contract.
 ///
 /// ## Variants
-/// * **ExecuteMsg::Collect { assets }** Swaps collected fee tokens to ROIDS
+/// * **ExecuteMsg::Collect { assets, asset_type_filter, expected_routes, deadline, min_roids_out }**
+/// Swaps collected fee tokens to ROIDS
 /// and transfers the ROIDS to the Hub burn address
 ///
+/// * **ExecuteMsg::CollectAll { limit }** Swaps every held fee token found in the factory pairs to ROIDS.
+///
+/// * **ExecuteMsg::ContinueCollect {}** Swaps the assets the latest collect left over.
+///
+/// * **ExecuteMsg::ForceCollect { assets }** Swaps fee tokens to ROIDS ignoring the collect
+/// cooldown and the collect value budget. Only the owner or the guardian can execute this.
+///
+/// * **ExecuteMsg::Donate {}** Donates the attached native tokens to the burn pool.
+///
+/// * **ExecuteMsg::Receive(msg)** Receives CW20 fee tokens sent with a [`Cw20HookMsg`].
+///
 /// * **ExecuteMsg::UpdateConfig {
-///             factory_contract,
+///             basic_asset,
 ///             max_spread,
 ///         }** Updates general contract settings stores in the [`Config`].
 ///
+/// * **ExecuteMsg::ProposeConfigChange {
+///             factory_contract,
+///             roids_token,
+///             asteroid_contract,
+///             config_change_delay_secs,
+///             router_contract,
+///             second_receiver_params,
+///             dev_fund_config,
+///             distribution_mode,
+///             bridge_call,
+///             external_dexes,
+///         }** Proposes a timelocked change of the critical settings and the fee destinations.
+///
+/// * **ExecuteMsg::ApplyConfigChange {}** Applies the pending config change once its delay passed.
+///
+/// * **ExecuteMsg::DropConfigChange {}** Removes the pending config change.
+///
+/// * **ExecuteMsg::TuneRisk { max_spread, collect_cooldown }** Updates the max spread and
+/// the collect cooldown together.
+///
+/// * **ExecuteMsg::FlushRoids {}** Distributes ROIDS standing in the Maker. Only the owner's
+/// flush bypasses the burn frequency and the hold threshold.
+///
+/// * **ExecuteMsg::CommitCollect {}** Reserves the next collect for the sender.
+///
+/// * **ExecuteMsg::ConsolidateDust { assets }** Swaps dust balances to ROIDS.
+///
+/// * **ExecuteMsg::SwapToIntermediate { asset, intermediate, max_spread }** Swaps a stuck asset
+/// to an intermediate asset left in the Maker.
+///
 /// * **ExecuteMsg::UpdateBridges { add, remove }** Adds or removes bridge assets used to swap fee tokens to ASTRO.
 ///
-/// * **ExecuteMsg::SwapBridgeAssets { assets }** Swap fee tokens (through bridges) to ASTRO.
+/// * **ExecuteMsg::ImportBridges { entries }** Bulk loads routes exported from another deployment.
+///
+/// * **ExecuteMsg::UpdateRoutes { add, remove }** Sets or removes full routes of fee tokens to ROIDS.
+///
+/// * **ExecuteMsg::ProposeBridge { asset, route }** Proposes a route of a fee token to ROIDS.
+///
+/// * **ExecuteMsg::ReviewBridgeProposal { asset, approve }** Approves or rejects a proposed route.
+///
+/// * **ExecuteMsg::UpdateMinReceived { add, remove }** Sets or removes the minimum ROIDS fee tokens
+/// must realize.
+///
+/// * **ExecuteMsg::UpdateSpreads { add, remove }** Sets or removes max spreads of specific fee tokens.
+///
+/// * **ExecuteMsg::UpdatePriceOracles { add, remove }** Sets or removes the TWAP oracles of
+/// specific assets.
+///
+/// * **ExecuteMsg::UpdateExternalDexes { remove }** Removes the external DEXes of specific
+/// fee tokens.
+///
+/// * **ExecuteMsg::UpdateBlacklist { add, remove }** Adds or removes assets which are never swapped.
+///
+/// * **ExecuteMsg::UpdateCooldownExempt { add, remove }** Adds or removes callers which aren't
+/// subject to the collect cooldown.
+///
+/// * **ExecuteMsg::UpdateDistributionHooks { add, remove }** Adds or removes contracts called after
+/// each distribution.
+///
+/// * **ExecuteMsg::SetPaused { paused }** Pauses or unpauses collects.
+///
+/// * **ExecuteMsg::Pause {}** Pauses collects, bridge swaps and distributions.
+///
+/// * **ExecuteMsg::Unpause {}** Unpauses the Maker.
+///
+/// * **ExecuteMsg::ProposeSeize { assets, recipient }** Proposes to send assets without a route
+/// out of the Maker.
+///
+/// * **ExecuteMsg::Seize {}** Executes the pending seize proposal once the seize delay passed.
+///
+/// * **ExecuteMsg::DropSeizeProposal {}** Removes the pending seize proposal.
+///
+/// * **ExecuteMsg::StartAuction { asset, start_price, end_price, duration_secs }** Starts a
+/// declining price auction selling a fee token for ROIDS.
+///
+/// * **ExecuteMsg::CancelAuction { asset_info }** Cancels the auction of a fee token.
+///
+/// * **ExecuteMsg::BidAuction { asset_info }** Buys a fee token from its auction with native ROIDS.
+///
+/// * **ExecuteMsg::RetryFailed {}** Collects the fee tokens whose swaps were quarantined.
+///
+/// * **ExecuteMsg::ReclaimRoids { from, amount }** Pulls mis-sent ROIDS back from the factory or a pair.
+///
+/// * **ExecuteMsg::UpdateApprovedBridgeAssets { assets }** Sets the whitelist of assets that can be used as bridges.
+///
+/// * **ExecuteMsg::UpdateRebasingAssets { assets }** Sets the assets with an elastic supply.
+///
+/// * **ExecuteMsg::RefreshDecimals {}** Caches the decimals of the bridge assets, ROIDS and the
+/// default bridge.
+///
+/// * **ExecuteMsg::AssignRole { role, address }** Hands a role over to another address.
+///
+/// * **ExecuteMsg::SwapBridgeAssets { assets, depth }** Swap fee tokens (through bridges) to ASTRO.
 ///
 /// * **ExecuteMsg::DistributeAstro {}** Private method used by the contract to distribute ASTRO rewards.
 ///
@@ -112,7 +319,8 @@ pub fn instantiate(
 ///
 /// * **ExecuteMsg::ClaimOwnership {}** Claims contract ownership.
 ///
-/// * **ExecuteMsg::EnableRewards** Enables collected ASTRO (pre Maker upgrade) to be distributed to xASTRO stakers.
+/// * **ExecuteMsg::EnableRewards { blocks }** Releases the ROIDS held from before the Maker upgrade
+/// over the specified number of blocks.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -121,25 +329,244 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Collect { assets } => collect(deps, env, assets),
-        ExecuteMsg::UpdateConfig {
+        ExecuteMsg::Collect {
+            assets,
+            asset_type_filter,
+            expected_routes,
+            deadline,
+            min_roids_out,
+        } => {
+            check_collect_deadline(deps.as_ref(), &env, deadline)?;
+
+            collect(
+                deps,
+                env,
+                info,
+                assets,
+                asset_type_filter.unwrap_or_default(),
+                expected_routes,
+                min_roids_out,
+                CollectKind::Regular,
+            )
+        }
+        ExecuteMsg::CollectAll { limit } => collect_all(deps, env, info, limit),
+        ExecuteMsg::Donate {} => {
+            let assets = info
+                .funds
+                .iter()
+                .map(|coin| Asset {
+                    info: native_asset_info(coin.denom.clone()),
+                    amount: coin.amount,
+                })
+                .collect();
+            donate(deps, info.sender, assets)
+        }
+        ExecuteMsg::ContinueCollect {} => {
+            let assets = match PENDING_COLLECT.may_load(deps.storage)? {
+                Some(assets) => assets,
+                None => return Err(ContractError::NoPendingCollect {}),
+            };
+
+            collect(
+                deps,
+                env,
+                info,
+                assets,
+                AssetTypeFilter::Both,
+                None,
+                None,
+                CollectKind::Continuation,
+            )
+        }
+        ExecuteMsg::ForceCollect { assets } => {
+            let cfg = CONFIG.load(deps.storage)?;
+
+            // Permission check
+            if info.sender != cfg.owner && cfg.guardian.as_ref() != Some(&info.sender) {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            collect(
+                deps,
+                env,
+                info,
+                assets,
+                AssetTypeFilter::Both,
+                None,
+                None,
+                CollectKind::Forced,
+            )
+        }
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::ProposeConfigChange {
+            factory_contract,
+            roids_token,
+            asteroid_contract,
+            config_change_delay_secs,
+            router_contract,
+            second_receiver_params,
+            dev_fund_config,
+            distribution_mode,
+            bridge_call,
+            external_dexes,
+        } => propose_config_change(
+            deps,
+            env,
+            info,
             factory_contract,
+            roids_token,
+            asteroid_contract,
+            config_change_delay_secs,
+            router_contract,
+            second_receiver_params,
+            dev_fund_config,
+            distribution_mode,
+            bridge_call,
+            external_dexes,
+        ),
+        ExecuteMsg::ApplyConfigChange {} => apply_config_change(deps, env, info),
+        ExecuteMsg::DropConfigChange {} => drop_config_change(deps, info),
+        ExecuteMsg::TuneRisk {
+            max_spread,
+            collect_cooldown,
+        } => tune_risk(deps, info, max_spread, collect_cooldown),
+        ExecuteMsg::FlushRoids {} => flush_roids(deps, env, info),
+        ExecuteMsg::CommitCollect {} => commit_collect(deps, env, info),
+        ExecuteMsg::UpdateConfig {
             basic_asset,
             max_spread,
             collect_cooldown,
-            roids_token,
-            asteroid_contract,
+            swap_deadline_secs,
+            auto_pause_threshold,
+            burn_every_n_collects,
+            dust_threshold,
+            keeper_commit_window_secs,
+            min_receive_buffer_bps,
+            lenient_burn,
+            max_bridges,
+            strict_collect,
+            post_unpause_grace_secs,
+            burn_retry_limit,
+            learn_routes,
+            keeper_fee_bps,
+            guardian,
+            seize_delay_secs,
+            min_collect_amount,
+            quarantine_failed_swaps,
+            max_collect_value_per_window,
+            bridges_execution_max_depth,
+            tokenfactory_burn,
+            max_assets_per_tx,
+            belief_price_source,
+            on_spread_exceeded,
+            reserve_amount,
+            auction_fallback,
+            operator,
         } => update_config(
             deps,
+            env,
             info,
-            factory_contract,
             basic_asset,
             max_spread,
             collect_cooldown,
-            roids_token,
-            asteroid_contract,
+            swap_deadline_secs,
+            auto_pause_threshold,
+            burn_every_n_collects,
+            dust_threshold,
+            keeper_commit_window_secs,
+            min_receive_buffer_bps,
+            lenient_burn,
+            max_bridges,
+            strict_collect,
+            post_unpause_grace_secs,
+            burn_retry_limit,
+            learn_routes,
+            keeper_fee_bps,
+            guardian,
+            seize_delay_secs,
+            min_collect_amount,
+            quarantine_failed_swaps,
+            max_collect_value_per_window,
+            bridges_execution_max_depth,
+            tokenfactory_burn,
+            max_assets_per_tx,
+            belief_price_source,
+            on_spread_exceeded,
+            reserve_amount,
+            auction_fallback,
+            operator,
+        ),
+        ExecuteMsg::ConsolidateDust { assets } => consolidate_dust(deps, env, info, assets),
+        ExecuteMsg::SwapToIntermediate {
+            asset,
+            intermediate,
+            max_spread,
+        } => swap_to_intermediate(deps.as_ref(), env, info, asset, intermediate, max_spread),
+        ExecuteMsg::SetPaused { paused } => set_paused(deps, env, info, paused),
+        ExecuteMsg::Pause {} => set_paused(deps, env, info, true),
+        ExecuteMsg::Unpause {} => set_paused(deps, env, info, false),
+        ExecuteMsg::ProposeSeize { assets, recipient } => {
+            propose_seize(deps, env, info, assets, recipient)
+        }
+        ExecuteMsg::Seize {} => seize(deps, env, info),
+        ExecuteMsg::DropSeizeProposal {} => drop_seize_proposal(deps, info),
+        ExecuteMsg::StartAuction {
+            asset,
+            start_price,
+            end_price,
+            duration_secs,
+        } => start_auction(
+            deps,
+            env,
+            info,
+            asset,
+            start_price,
+            end_price,
+            duration_secs,
         ),
-        ExecuteMsg::UpdateBridges { add, remove } => update_bridges(deps, info, add, remove),
+        ExecuteMsg::CancelAuction { asset_info } => cancel_auction(deps, info, asset_info),
+        ExecuteMsg::BidAuction { asset_info } => {
+            let cfg = CONFIG.load(deps.storage)?;
+            let paid = match &cfg.roids_token {
+                AssetInfo::NativeToken { denom }
+                    if info.funds.len() == 1 && info.funds[0].denom == *denom =>
+                {
+                    info.funds[0].amount
+                }
+                _ => return Err(ContractError::InvalidAuctionPayment {}),
+            };
+            bid_auction(deps, env, info.sender, asset_info, paid)
+        }
+        ExecuteMsg::RetryFailed {} => retry_failed(deps, env, info),
+        ExecuteMsg::ReclaimRoids { from, amount } => reclaim_roids(deps, env, info, from, amount),
+        ExecuteMsg::UpdateBridges { add, remove } => update_bridges(deps, env, info, add, remove),
+        ExecuteMsg::ImportBridges { entries } => import_bridges(deps, env, info, entries),
+        ExecuteMsg::UpdateRoutes { add, remove } => update_routes(deps, env, info, add, remove),
+        ExecuteMsg::ProposeBridge { asset, route } => propose_bridge(deps, info, asset, route),
+        ExecuteMsg::ReviewBridgeProposal { asset, approve } => {
+            review_bridge_proposal(deps, env, info, asset, approve)
+        }
+        ExecuteMsg::UpdateMinReceived { add, remove } => {
+            update_min_received(deps, info, add, remove)
+        }
+        ExecuteMsg::UpdateSpreads { add, remove } => update_spreads(deps, info, add, remove),
+        ExecuteMsg::UpdatePriceOracles { add, remove } => {
+            update_price_oracles(deps, info, add, remove)
+        }
+        ExecuteMsg::UpdateExternalDexes { remove } => update_external_dexes(deps, info, remove),
+        ExecuteMsg::UpdateBlacklist { add, remove } => update_blacklist(deps, info, add, remove),
+        ExecuteMsg::UpdateCooldownExempt { add, remove } => {
+            update_cooldown_exempt(deps, info, add, remove)
+        }
+        ExecuteMsg::UpdateDistributionHooks { add, remove } => {
+            update_distribution_hooks(deps, info, add, remove)
+        }
+        ExecuteMsg::UpdateApprovedBridgeAssets { assets } => {
+            update_approved_bridge_assets(deps, info, assets)
+        }
+        ExecuteMsg::RefreshDecimals {} => refresh_decimals(deps, info),
+        ExecuteMsg::AssignRole { role, address } => assign_role(deps, info, role, address),
+        ExecuteMsg::UpdateRebasingAssets { assets } => update_rebasing_assets(deps, info, assets),
         ExecuteMsg::SwapBridgeAssets { assets, depth } => {
             swap_bridge_assets(deps, env, info, assets, depth)
         }
@@ -175,483 +602,4585 @@ pub fn execute(
             })
             .map_err(Into::into)
         }
+        ExecuteMsg::EnableRewards { blocks } => enable_rewards(deps, env, info, blocks),
     }
 }
 
-/// Swaps fee tokens to ROIDS and distribute the resulting ROIDS to the Hub burn address.
+/// Exposes sudo functions called by the chain.
 ///
-/// * **assets** array with fee tokens being swapped to ROIDS.
-fn collect(
-    deps: DepsMut,
-    env: Env,
-    assets: Vec<AssetWithLimit>,
-) -> Result<Response, ContractError> {
-    let cfg = CONFIG.load(deps.storage)?;
+/// ## Variants
+/// * **SudoMsg::Collect {}** Collects every held fee token with a route to ROIDS. Meant for chain
+/// cron modules, so a paused Maker or a running cooldown skips the collect instead of failing.
+///
+/// * **SudoMsg::IbcLifecycleComplete(..)** Settles an ICS20 transfer of ROIDS to the Hub.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::Collect {} => {
+            let cfg = CONFIG.load(deps.storage)?;
 
-    // Allowing collect only once per cooldown period
-    LAST_COLLECT_TS.update(deps.storage, |last_ts| match cfg.collect_cooldown {
-        Some(cd_period) if env.block.time.seconds() < last_ts + cd_period => {
-            Err(ContractError::Cooldown {
-                next_collect_ts: last_ts + cd_period,
-            })
+            if PAUSED.may_load(deps.storage)?.unwrap_or_default() {
+                return Ok(Response::new()
+                    .add_attributes([attr("action", "sudo_collect"), attr("skipped", "paused")]));
+            }
+            if let Some(next_collect_ts) = next_collect_ts(deps.as_ref(), &cfg)? {
+                if env.block.time.seconds() < next_collect_ts {
+                    return Ok(Response::new().add_attributes([
+                        attr("action", "sudo_collect"),
+                        attr("skipped", "cooldown"),
+                        attr("next_collect_ts", next_collect_ts.to_string()),
+                    ]));
+                }
+            }
+
+            // The Maker collects on its own behalf, so no keeper fee is paid
+            let info = MessageInfo {
+                sender: env.contract.address.clone(),
+                funds: vec![],
+            };
+            collect_all(deps, env, info, None)
         }
-        _ => Ok(env.block.time.seconds()),
-    })?;
+        SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+            channel,
+            sequence,
+            success,
+            ..
+        }) => settle_ibc_transfer(deps, env, channel, sequence, success),
+        SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcTimeout { channel, sequence }) => {
+            settle_ibc_transfer(deps, env, channel, sequence, false)
+        }
+    }
+}
 
-    let roids = cfg.roids_token.clone();
+/// Accounts an acknowledged ICS20 transfer of ROIDS as burned. ROIDS of a rejected or timed out
+/// transfer is refunded to the Maker and distributed again, so it isn't accounted for here.
+fn settle_ibc_transfer(
+    deps: DepsMut,
+    env: Env,
+    channel: String,
+    sequence: u64,
+    success: bool,
+) -> Result<Response, ContractError> {
+    let amount = match IBC_TRANSFERS.may_load(deps.storage, (&channel, sequence))? {
+        Some(amount) => amount,
+        None => return Ok(Response::new()),
+    };
+    IBC_TRANSFERS.remove(deps.storage, (&channel, sequence));
 
-    // Check for duplicate assets
-    let mut uniq = HashSet::new();
-    if !assets
-        .clone()
-        .into_iter()
-        .all(|a| uniq.insert(a.info.to_string()))
-    {
-        return Err(ContractError::DuplicatedAsset {});
+    if success {
+        record_burn(deps.storage, &env, amount)?;
     }
 
-    // let response = Response::default();
+    Ok(Response::new().add_attributes([
+        attr("action", "settle_ibc_transfer"),
+        attr("ibc_channel", channel),
+        attr("ibc_sequence", sequence.to_string()),
+        attr(
+            if success {
+                "burned_roids"
+            } else {
+                "refunded_roids"
+            },
+            amount,
+        ),
+    ]))
+}
 
-    // Swap all non ROIDS tokens
-    let (mut response, bridge_assets) = swap_assets(
-        deps.as_ref(),
-        &env.contract.address,
-        &cfg,
-        assets.into_iter().filter(|a| a.info.ne(&roids)).collect(),
-    )?;
+/// Collects all fee tokens held by the Maker which appear in the factory pairs and have a route
+/// to ROIDS.
+///
+/// * **limit** number of factory pairs to read.
+fn collect_all(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
 
-    // // If no swap messages - send ROIDS directly to x/vxASTRO stakers
-    // if response.messages.is_empty() {
-    //     let (mut distribute_msg, attributes) = distribute(deps, env, &mut cfg)?;
-    //     if !distribute_msg.is_empty() {
-    //         response.messages.append(&mut distribute_msg);
-    //         response = response.add_attributes(attributes);
-    //     }
-    // } else {
-    //     response.messages.push(build_distribute_msg(
-    //         env,
-    //         bridge_assets,
-    //         BRIDGES_INITIAL_DEPTH,
-    //     )?);
-    // }
+    let pairs = query_pairs_info(&deps.querier, &cfg.factory_contract, None, limit)?.pairs;
 
-    Ok(response.add_attribute("action", "collect"))
-}
+    let mut uniq = HashSet::new();
+    let mut assets = vec![];
+    for asset_info in pairs.into_iter().flat_map(|pair| pair.asset_infos) {
+        if asset_info.eq(&cfg.roids_token) || !uniq.insert(asset_info.to_string()) {
+            continue;
+        }
+        // Fee tokens without a balance or a route are left for a later collect
+        match asset_info.query_pool(&deps.querier, &env.contract.address) {
+            Ok(amount) if !amount.is_zero() => {}
+            _ => continue,
+        }
+        if resolve_route(deps.as_ref(), &cfg, &asset_info).is_err() {
+            continue;
+        }
+        assets.push(AssetWithLimit {
+            info: asset_info,
+            limit: None,
+            limit_semantics: None,
+        });
+    }
 
-/// This enum describes available token types that can be used as a SwapTarget.
-enum SwapTarget {
-    Roids(SubMsg),
-    Bridge { asset: AssetInfo, msg: SubMsg },
+    collect(
+        deps,
+        env,
+        info,
+        assets,
+        AssetTypeFilter::Both,
+        None,
+        None,
+        CollectKind::Regular,
+    )
 }
 
-/// Swap all non ASTRO tokens to ASTRO.
-///
-/// * **contract_addr** maker contract address.
+/// Records CW20 fee tokens pushed to the Maker and collects them if requested.
 ///
-/// * **assets** array with assets to swap to ASTRO.
-///
-/// * **with_validation** whether the swap operation should be validated or not.
-fn swap_assets(
-    deps: Deps,
-    contract_addr: &Addr,
-    cfg: &Config,
-    assets: Vec<AssetWithLimit>,
-) -> Result<(Response, Vec<AssetInfo>), ContractError> {
-    let mut response = Response::default();
-    let mut bridge_assets = HashMap::new();
+/// * **cw20_msg** CW20 message to process.
+fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_json(&cw20_msg.msg)? {
+        Cw20HookMsg::CollectDeposit { swap } => {
+            let token = token_asset_info(info.sender);
+            ASSET_STATS.update(deps.storage, token.to_string(), |stats| {
+                let mut stats: AssetStats = stats.unwrap_or_default();
+                stats.deposited += cw20_msg.amount;
+                StdResult::Ok(stats)
+            })?;
 
-    for a in assets {
-        // Get balance
-        let mut balance = a.info.query_pool(&deps.querier, contract_addr)?;
-        if let Some(limit) = a.limit {
-            if limit < balance && limit > Uint128::zero() {
-                balance = limit;
+            let attributes = [
+                attr("action", "collect_deposit"),
+                attr("asset", token.to_string()),
+                attr("amount", cw20_msg.amount),
+                attr("depositor", &cw20_msg.sender),
+            ];
+
+            if !swap.unwrap_or_default() {
+                return Ok(Response::new().add_attributes(attributes));
             }
-        }
 
-        if !balance.is_zero() {
-            match swap(deps, cfg, a.info, balance)? {
-                SwapTarget::Roids(msg) => {
-                    response.messages.push(msg);
-                }
-                SwapTarget::Bridge { asset, msg } => {
-                    response.messages.push(msg);
-                    bridge_assets.insert(asset.to_string(), asset);
-                }
+            // The depositor is the caller of the collect
+            let depositor = MessageInfo {
+                sender: deps.api.addr_validate(&cw20_msg.sender)?,
+                funds: vec![],
+            };
+            let asset = AssetWithLimit {
+                info: token,
+                limit: None,
+                limit_semantics: None,
+            };
+            Ok(collect(
+                deps,
+                env,
+                depositor,
+                vec![asset],
+                AssetTypeFilter::Both,
+                None,
+                None,
+                CollectKind::Regular,
+            )?
+            .add_attributes(attributes))
+        }
+        Cw20HookMsg::Donate {} => {
+            let donor = deps.api.addr_validate(&cw20_msg.sender)?;
+            let asset = Asset {
+                info: token_asset_info(info.sender),
+                amount: cw20_msg.amount,
+            };
+            donate(deps, donor, vec![asset])
+        }
+        Cw20HookMsg::BidAuction { asset_info } => {
+            let cfg = CONFIG.load(deps.storage)?;
+            if cfg.roids_token != token_asset_info(info.sender) {
+                return Err(ContractError::InvalidAuctionPayment {});
             }
+            let bidder = deps.api.addr_validate(&cw20_msg.sender)?;
+            bid_auction(deps, env, bidder, asset_info, cw20_msg.amount)
         }
     }
-
-    Ok((response, bridge_assets.into_values().collect()))
 }
 
-/// Checks if all required pools and bridges exists and performs a swap operation to ASTRO.
+/// Records donations to the burn pool. The donated tokens are collected like any other fee
+/// token, or distributed right away if they are ROIDS.
 ///
-/// * **from_token** token to swap to ASTRO.
+/// * **donor** address the donation is attributed to.
 ///
-/// * **amount_in** amount of tokens to swap.
-fn swap(
-    deps: Deps,
-    cfg: &Config,
-    from_token: AssetInfo,
-    amount_in: Uint128,
-) -> Result<SwapTarget, ContractError> {
-    // 1. Check if bridge tokens exist
-    let bridge_token = BRIDGES.load(deps.storage, from_token.to_string());
-    if let Ok(bridge_token) = bridge_token {
-        let bridge_pool = validate_bridge(
-            deps,
-            &cfg.factory_contract,
-            &from_token,
-            &bridge_token,
-            &cfg.roids_token,
-            BRIDGES_INITIAL_DEPTH,
-        )?;
+/// * **assets** donated assets.
+fn donate(deps: DepsMut, donor: Addr, assets: Vec<Asset>) -> Result<Response, ContractError> {
+    let assets: Vec<_> = assets.into_iter().filter(|a| !a.amount.is_zero()).collect();
+    if assets.is_empty() {
+        return Err(ContractError::EmptyDonation {});
+    }
 
-        let msg = build_swap_msg(
-            cfg.max_spread,
-            &bridge_pool,
-            &from_token,
-            Some(&bridge_token),
-            amount_in,
+    let mut response =
+        Response::new().add_attributes([attr("action", "donate"), attr("donor", donor.as_str())]);
+    for asset in assets {
+        DONATIONS.update(
+            deps.storage,
+            (&donor, asset.info.to_string()),
+            |total| -> StdResult<_> {
+                let mut total = total.unwrap_or_else(|| Asset {
+                    info: asset.info.clone(),
+                    amount: Uint128::zero(),
+                });
+                total.amount += asset.amount;
+                Ok(total)
+            },
         )?;
 
-        let swap_msg = if bridge_token == cfg.roids_token {
-            SwapTarget::Roids(msg)
-        } else {
-            SwapTarget::Bridge {
-                asset: bridge_token,
-                msg,
-            }
-        };
-        return Ok(swap_msg);
-    }
-
-    // 2. Check for a pair with a default bridge
-    if let Some(default_bridge) = &cfg.default_bridge {
-        if from_token.ne(default_bridge) {
-            let swap_to_default =
-                try_build_swap_msg(&deps.querier, cfg, &from_token, default_bridge, amount_in);
-            if let Ok(msg) = swap_to_default {
-                return Ok(SwapTarget::Bridge {
-                    asset: default_bridge.clone(),
-                    msg,
-                });
-            }
-        }
+        response = response.add_event(
+            Event::new("donation")
+                .add_attribute("donor", donor.as_str())
+                .add_attribute("asset", asset.info.to_string())
+                .add_attribute("amount", asset.amount),
+        );
     }
 
-    // 3. Check for a direct pair with ROIDS
-    let swap_to_astro =
-        try_build_swap_msg(&deps.querier, cfg, &from_token, &cfg.roids_token, amount_in);
-    if let Ok(msg) = swap_to_astro {
-        return Ok(SwapTarget::Roids(msg));
-    }
+    Ok(response)
+}
 
-    Err(ContractError::CannotSwap(from_token))
+/// The kinds of collect the [`collect`] function handles.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CollectKind {
+    /// A keeper collect subject to the cooldown
+    Regular,
+    /// Swaps the assets left over by the previous collect
+    Continuation,
+    /// An owner or guardian collect bypassing the cooldown and the rate limits
+    Forced,
 }
 
-/// Swaps collected fees using bridge assets.
+/// Swaps fee tokens to ROIDS and distribute the resulting ROIDS to the Hub burn address.
 ///
-/// * **assets** array with fee tokens to swap as well as amount of tokens to swap.
+/// * **assets** array with fee tokens being swapped to ROIDS.
 ///
-/// * **depth** maximum route length used to swap a fee token.
+/// * **asset_type_filter** asset types to process, others are left for a later collect.
 ///
-/// ## Executor
-/// Only the Maker contract itself can execute this.
-fn swap_bridge_assets(
-    deps: DepsMut,
+/// * **expected_routes** routes planned by the keeper which must match the resolved ones.
+///
+/// * **min_roids_out** minimum ROIDS the swaps of the collect must realize altogether.
+///
+/// * **kind** whether the collect is a regular one, a continuation or a forced one.
+///
+/// Only the first `max_assets_per_tx` assets are swapped, the rest is saved for `ContinueCollect`.
+#[allow(clippy::too_many_arguments)]
+fn collect(
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    assets: Vec<AssetInfo>,
-    depth: u64,
+    assets: Vec<AssetWithLimit>,
+    asset_type_filter: AssetTypeFilter,
+    expected_routes: Option<Vec<(AssetInfo, Vec<AssetInfo>)>>,
+    min_roids_out: Option<Uint128>,
+    kind: CollectKind,
 ) -> Result<Response, ContractError> {
-    if info.sender != env.contract.address {
-        return Err(ContractError::Unauthorized {});
-    }
-
-    if assets.is_empty() {
-        return Ok(Response::default());
-    }
+    let mut cfg = CONFIG.load(deps.storage)?;
 
-    // Check that the contract doesn't call itself endlessly
-    if depth >= BRIDGES_EXECUTION_MAX_DEPTH {
-        return Err(ContractError::MaxBridgeDepth(depth));
+    if PAUSED.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::Paused {});
     }
 
-    let cfg = CONFIG.load(deps.storage)?;
-
-    let bridges = assets
-        .into_iter()
-        .map(|a| AssetWithLimit {
-            info: a,
-            limit: None,
-        })
-        .collect();
-
-    let (response, bridge_assets) =
-        swap_assets(deps.as_ref(), &env.contract.address, &cfg, bridges)?;
+    check_post_unpause_grace(deps.storage, &env, &cfg)?;
 
-    // There should always be some messages, if there are none - something went wrong
-    if response.messages.is_empty() {
-        return Err(ContractError::Std(StdError::generic_err(
-            "Empty swap messages",
-        )));
+    // Allowing collect only once per cooldown period unless the caller is exempt.
+    // A continuation belongs to the collect which started the cooldown.
+    // Forced collects leave the cooldown of the keepers untouched
+    if kind == CollectKind::Regular {
+        start_cooldown(deps.branch(), &env, &cfg, &info.sender)?;
     }
 
-    Ok(response
-        .add_submessage(build_distribute_msg(env, bridge_assets, depth + 1)?)
-        .add_attribute("action", "swap_bridge_assets"))
-}
+    if kind != CollectKind::Forced {
+        take_keeper_commitment(deps.storage, &env, &info.sender)?;
+    }
 
-/// Distributes ASTRO rewards to x/vxASTRO holders.
-///
-/// ## Executor
-/// Only the Maker contract itself can execute this.
-fn distribute_astro(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
-    if info.sender != env.contract.address {
-        return Err(ContractError::Unauthorized {});
+    if cfg.auto_pause_threshold.is_some() {
+        // A previous collect without failed swaps breaks the failure streak
+        if !COLLECT_FAILED.may_load(deps.storage)?.unwrap_or_default() {
+            CONSECUTIVE_FAILED_COLLECTS.save(deps.storage, &0)?;
+        }
+        COLLECT_FAILED.save(deps.storage, &false)?;
     }
 
-    let mut cfg = CONFIG.load(deps.storage)?;
-    let (distribute_msg, attributes) = distribute(deps, env, &mut cfg)?;
-    if distribute_msg.is_empty() {
-        return Ok(Response::default());
+    if cfg.burn_every_n_collects.is_some() && kind != CollectKind::Continuation {
+        let collects = COLLECTS_SINCE_BURN
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        COLLECTS_SINCE_BURN.save(deps.storage, &collects.saturating_add(1))?;
     }
 
-    Ok(Response::default()
-        .add_submessages(distribute_msg)
-        .add_attributes(attributes))
-}
+    let collect_id = COLLECT_ID.may_load(deps.storage)?.unwrap_or_default() + 1;
+    COLLECT_ID.save(deps.storage, &collect_id)?;
+    PENDING_COLLECTOR.save(deps.storage, &info.sender)?;
+    SWAP_HOP.save(deps.storage, &0)?;
+    WORST_HOP.remove(deps.storage);
+    ROUTE_PROGRESS.remove(deps.storage);
+    DUST_BRIDGE_BALANCES.remove(deps.storage);
 
-type DistributeMsgParts = (Vec<SubMsg>, Vec<Attribute>);
+    // Incident response can't wait for the value budget of the next windows
+    if kind == CollectKind::Forced {
+        cfg.max_collect_value_per_window = None;
+    }
+
+    let roids = cfg.roids_token.clone();
+
+    // Check for duplicate assets
+    let mut uniq = HashSet::new();
+    if !assets
+        .clone()
+        .into_iter()
+        .all(|a| uniq.insert(a.info.to_string()))
+    {
+        return Err(ContractError::DuplicatedAsset {});
+    }
+
+    // Make sure the bridges didn't change since the keeper planned the collect
+    for (asset, expected_route) in expected_routes.unwrap_or_default() {
+        if resolve_route(deps.as_ref(), &cfg, &asset)? != expected_route {
+            return Err(ContractError::RouteChanged(asset));
+        }
+    }
+
+    let mut assets: Vec<_> = assets
+        .into_iter()
+        .filter(|a| a.info.ne(&roids) && asset_type_filter.matches(&a.info))
+        .collect();
+
+    // Assets beyond the per transaction limit are left for ContinueCollect
+    let pending = match cfg.max_assets_per_tx {
+        Some(max) if assets.len() > max as usize => assets.split_off(max as usize),
+        _ => vec![],
+    };
+    if pending.is_empty() {
+        PENDING_COLLECT.remove(deps.storage);
+    } else {
+        PENDING_COLLECT.save(deps.storage, &pending)?;
+    }
+
+    // Swap all non ROIDS tokens
+    // The value budget is shared by every collect of the window
+    let value_window = current_value_window(deps.as_ref(), &env, &cfg)?;
+    let value_budget = cfg
+        .max_collect_value_per_window
+        .map(|max_value| max_value.saturating_sub(value_window.spent));
+    let (mut response, bridge_assets, swept, stranded, spent) = swap_assets(
+        deps.as_ref(),
+        &env.contract.address,
+        &cfg,
+        assets,
+        value_budget,
+    )?;
+    if value_budget.is_some() {
+        COLLECT_VALUE_WINDOW.save(
+            deps.storage,
+            &ValueWindow {
+                spent: value_window.spent + spent,
+                ..value_window
+            },
+        )?;
+    }
+    snapshot_token_balances(deps.branch(), &env.contract.address, &bridge_assets)?;
+    response = auction_stranded(deps.storage, &env, &cfg, response, stranded)?;
+    if !pending.is_empty() {
+        response = response.add_attribute("pending_assets", pending.len().to_string());
+    }
+
+    if cfg.quarantine_failed_swaps && !cfg.strict_collect {
+        response = quarantine_swaps(deps.storage, response, &swept)?;
+    }
+
+    // Discovered routes are learned in the swap replies, only once their swaps succeeded
+    DISCOVERED_ROUTES.remove(deps.storage);
+    if cfg.learn_routes {
+        let mut discovered = vec![];
+        for progress in &swept {
+            if let Some(route) = discover_route(deps.as_ref(), &cfg, &progress.asset)? {
+                discovered.push(route);
+            }
+        }
+        if !discovered.is_empty() {
+            DISCOVERED_ROUTES.save(deps.storage, &discovered)?;
+        }
+    }
+
+    let min_roids_out = min_roids_out.filter(|min| !min.is_zero());
+
+    // If no swap messages - distribute ROIDS directly
+    if response.messages.is_empty() {
+        // Nothing is swapped, so no ROIDS can be realized
+        if let Some(min) = min_roids_out {
+            return Err(ContractError::MinRoidsOutNotMet {
+                min,
+                actual: Uint128::zero(),
+            });
+        }
+
+        let collector = take_pending_collector(deps.storage)?;
+        let (mut distribute_msg, attributes, amount) = distribute(
+            deps.branch(),
+            env.clone(),
+            &mut cfg,
+            collector.as_ref(),
+            false,
+        )?;
+        record_collector(deps.branch(), collector, amount)?;
+        if !distribute_msg.is_empty() {
+            response.messages.append(&mut distribute_msg);
+            response = response.add_attributes(attributes);
+        }
+    } else {
+        // The realized price of each swept asset is tracked in the swap replies
+        ROUTE_PROGRESS.save(deps.storage, &swept)?;
+        SWAPS_START_ROIDS.save(
+            deps.storage,
+            &roids.query_pool(&deps.querier, &env.contract.address)?,
+        )?;
+
+        let mut distribute_msg =
+            build_distribute_msg(env.clone(), bridge_assets, BRIDGES_INITIAL_DEPTH)?;
+
+        // The reply of the last message comes once every swap and the distribution settled
+        if let Some(min) = min_roids_out {
+            COLLECT_MIN_ROIDS_OUT.save(deps.storage, &min)?;
+            distribute_msg = SubMsg {
+                id: COLLECT_SETTLED_REPLY_ID,
+                reply_on: ReplyOn::Success,
+                ..distribute_msg
+            };
+        }
+        response.messages.push(distribute_msg);
+    }
+
+    // Let the keeper know when the next collect is possible
+    response = response.add_attribute("last_collect_ts", env.block.time.seconds().to_string());
+    if let Some(next_collect_ts) = next_collect_ts(deps.as_ref(), &cfg)? {
+        response = response.add_attribute("next_collect_ts", next_collect_ts.to_string());
+    }
+
+    // With the circuit breaker enabled, failed swaps are recorded instead of reverting the collect.
+    // Other failures, e.g. of the distribution, still revert it. Strict collects always revert so
+    // the cooldown and all swaps are rolled back together
+    if cfg.auto_pause_threshold.is_some() && !cfg.strict_collect {
+        response.messages = response
+            .messages
+            .into_iter()
+            .map(|msg| match msg.reply_on {
+                // Swaps keep their slippage record and reply on failure too
+                ReplyOn::Success if msg.id == SWAP_REPLY_ID => SubMsg {
+                    reply_on: ReplyOn::Always,
+                    ..msg
+                },
+                _ => msg,
+            })
+            .collect();
+    }
+
+    Ok(tag_collect_id(
+        deps.as_ref(),
+        response.add_attribute("action", "collect"),
+    )?)
+}
+
+/// Makes the swaps of a collect step reply on failure as well, so a failing pool quarantines the
+/// asset it was offered instead of reverting the collect. Swaps are matched with the swept assets
+/// by their order.
+fn quarantine_swaps(
+    storage: &mut dyn Storage,
+    mut response: Response,
+    swept: &[RouteProgress],
+) -> StdResult<Response> {
+    let mut batch = vec![];
+    response.messages = response
+        .messages
+        .into_iter()
+        .map(|msg| {
+            if msg.id != SWAP_REPLY_ID || batch.len() >= swept.len() {
+                return msg;
+            }
+            let index = batch.len();
+            batch.push(swept[index].asset.clone());
+            SubMsg {
+                id: QUARANTINE_REPLY_ID_OFFSET + index as u64,
+                reply_on: ReplyOn::Always,
+                ..msg
+            }
+        })
+        .collect();
+    QUARANTINE_BATCH.save(storage, &batch)?;
+
+    Ok(response)
+}
+
+/// Collects the fee tokens whose swaps failed and were quarantined. The queue is emptied, so
+/// swaps failing again are quarantined anew.
+fn retry_failed(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let failed = FAILED_ASSETS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    if failed.is_empty() {
+        return Err(ContractError::NoFailedAssets {});
+    }
+
+    let mut assets = vec![];
+    for (key, failed) in failed {
+        FAILED_ASSETS.remove(deps.storage, key);
+        assets.push(AssetWithLimit {
+            info: failed.asset,
+            limit: None,
+            limit_semantics: None,
+        });
+    }
+
+    collect(
+        deps,
+        env,
+        info,
+        assets,
+        AssetTypeFilter::Both,
+        None,
+        None,
+        CollectKind::Regular,
+    )
+}
+
+/// Distributes ROIDS standing in the Maker, e.g. received via direct transfers, without
+/// waiting for a collect. Can be executed once per [`FLUSH_ROIDS_COOLDOWN`].
+fn flush_roids(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+
+    if PAUSED.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::Paused {});
+    }
+
+    if let Some(last_ts) = LAST_FLUSH_TS.may_load(deps.storage)? {
+        if env.block.time.seconds() < last_ts + FLUSH_ROIDS_COOLDOWN {
+            return Err(ContractError::FlushCooldown {
+                next_flush_ts: last_ts + FLUSH_ROIDS_COOLDOWN,
+            });
+        }
+    }
+    LAST_FLUSH_TS.save(deps.storage, &env.block.time.seconds())?;
+
+    // Anyone may flush, but only the owner can override the burn frequency and the hold threshold
+    let force = info.sender == cfg.owner;
+    let (distribute_msg, attributes, _) = distribute(deps, env, &mut cfg, None, force)?;
+
+    Ok(Response::new()
+        .add_submessages(distribute_msg)
+        .add_attribute("action", "flush_roids")
+        .add_attributes(attributes))
+}
+
+/// Reserves the next collect for the sender. The commitment can only be made once the cooldown
+/// is over and lasts for the configured keeper commit window. A keeper whose commitment lapsed
+/// unused can't commit again until the next collect.
+fn commit_collect(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    let window = cfg
+        .keeper_commit_window_secs
+        .ok_or(ContractError::KeeperCommitDisabled {})?;
+
+    if PAUSED.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::Paused {});
+    }
+
+    let now = env.block.time.seconds();
+    if let Some(next_collect_ts) = next_collect_ts(deps.as_ref(), &cfg)? {
+        if now < next_collect_ts {
+            return Err(ContractError::Cooldown { next_collect_ts });
+        }
+    }
+
+    if let Some(commitment) = KEEPER_COMMITMENT.may_load(deps.storage)? {
+        if now < commitment.expires_at {
+            return Err(ContractError::CollectReserved {
+                keeper: commitment.keeper,
+                expires_at: commitment.expires_at,
+            });
+        }
+        if commitment.keeper == info.sender {
+            return Err(ContractError::CommitmentLapsed {});
+        }
+    }
+
+    let commitment = KeeperCommitment {
+        keeper: info.sender,
+        expires_at: now + window,
+    };
+    KEEPER_COMMITMENT.save(deps.storage, &commitment)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "commit_collect"),
+        attr("keeper", commitment.keeper),
+        attr("expires_at", commitment.expires_at.to_string()),
+    ]))
+}
+
+/// Makes sure pools got time to stabilize after an incident before anything is swapped.
+fn check_post_unpause_grace(
+    storage: &dyn Storage,
+    env: &Env,
+    cfg: &Config,
+) -> Result<(), ContractError> {
+    if let Some(grace_secs) = cfg.post_unpause_grace_secs {
+        if let Some(unpaused_at) = UNPAUSED_AT.may_load(storage)? {
+            if env.block.time.seconds() < unpaused_at + grace_secs {
+                return Err(ContractError::PostUnpauseGrace {
+                    resume_ts: unpaused_at + grace_secs,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// A committed keeper swaps exclusively until its commitment expires. The commitment is used
+/// up by the first swap of its keeper or once it expired.
+fn take_keeper_commitment(
+    storage: &mut dyn Storage,
+    env: &Env,
+    sender: &Addr,
+) -> Result<(), ContractError> {
+    if let Some(commitment) = KEEPER_COMMITMENT.may_load(storage)? {
+        if commitment.keeper != *sender && env.block.time.seconds() < commitment.expires_at {
+            return Err(ContractError::CollectReserved {
+                keeper: commitment.keeper,
+                expires_at: commitment.expires_at,
+            });
+        }
+        KEEPER_COMMITMENT.remove(storage);
+    }
+
+    Ok(())
+}
+
+/// Returns the attribute reporting why an asset is left out of a swap, if it is. Blacklisted
+/// assets are skipped even if the keeper passed them, auctioned ones are sold to bidders instead.
+fn skipped_asset_attr(storage: &dyn Storage, asset: &AssetInfo) -> Option<Attribute> {
+    if BLACKLIST.has(storage, asset.to_string()) {
+        Some(attr("skipped_blacklisted", asset.to_string()))
+    } else if AUCTIONS.has(storage, asset.to_string()) {
+        Some(attr("skipped_auctioned", asset.to_string()))
+    } else {
+        None
+    }
+}
+
+/// Swaps dust balances to ROIDS in one pass. Each asset is routed like in a collect and the
+/// following hops only swap what the dust swaps returned. Only balances at or below the dust
+/// threshold are accepted and consolidations are subject to the same guards as collects.
+///
+/// * **assets** array with dust tokens to swap to ROIDS.
+fn consolidate_dust(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    assets: Vec<AssetInfo>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    if PAUSED.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::Paused {});
+    }
+
+    let threshold = cfg
+        .dust_threshold
+        .ok_or(ContractError::DustConsolidationDisabled {})?;
+
+    // Consolidations share the guards and the cooldown of collects as they swap just the same
+    check_post_unpause_grace(deps.storage, &env, &cfg)?;
+    start_cooldown(deps.branch(), &env, &cfg, &info.sender)?;
+    take_keeper_commitment(deps.storage, &env, &info.sender)?;
+
+    let mut uniq = HashSet::new();
+    if !assets.iter().all(|a| uniq.insert(a.to_string())) {
+        return Err(ContractError::DuplicatedAsset {});
+    }
+
+    let mut response = Response::default();
+    let mut bridge_assets = HashMap::new();
+    let mut swapped = HashMap::new();
+
+    for asset in assets.into_iter().filter(|a| a.ne(&cfg.roids_token)) {
+        if let Some(skipped) = skipped_asset_attr(deps.storage, &asset) {
+            response = response.add_attribute(skipped.key, skipped.value);
+            continue;
+        }
+
+        let balance = asset.query_pool(&deps.querier, &env.contract.address)?;
+        if balance > threshold {
+            return Err(ContractError::NotDust {
+                asset,
+                balance,
+                threshold,
+            });
+        }
+        if balance.is_zero() {
+            continue;
+        }
+
+        let (to_token, pool) = get_next_hop(deps.as_ref(), &cfg, &asset)?;
+        let msg = build_swap_msg(
+            get_asset_max_spread(deps.storage, &cfg, &asset, &pool)?,
+            &pool.contract_addr,
+            &asset,
+            Some(&to_token),
+            balance,
+            None,
+            None,
+        )?;
+        response.messages.push(SubMsg {
+            id: SWAP_REPLY_ID,
+            reply_on: ReplyOn::Success,
+            ..msg
+        });
+
+        if to_token != cfg.roids_token {
+            bridge_assets.insert(to_token.to_string(), to_token);
+        }
+        // Dust of different assets doesn't add up, each amount is reported on its own
+        response = response.add_attribute(
+            "consolidated",
+            Asset {
+                info: asset.clone(),
+                amount: balance,
+            }
+            .to_string(),
+        );
+        swapped.insert(asset.to_string(), balance);
+    }
+
+    let consolidated = response.messages.len();
+    if consolidated > 0 {
+        // Dust isn't tracked along its route, the swaps are only recorded in their replies.
+        // Consolidating dust pays no keeper fee
+        ROUTE_PROGRESS.remove(deps.storage);
+        PENDING_COLLECTOR.remove(deps.storage);
+        SWAP_HOP.save(deps.storage, &0)?;
+        SWAPS_START_ROIDS.save(
+            deps.storage,
+            &cfg.roids_token
+                .query_pool(&deps.querier, &env.contract.address)?,
+        )?;
+
+        // Bridge assets may already be held, only the consolidated amounts are swapped further
+        let bridge_assets: Vec<_> = bridge_assets.into_values().collect();
+        snapshot_dust_bridges(
+            deps.branch(),
+            &env.contract.address,
+            &bridge_assets,
+            &swapped,
+        )?;
+
+        response.messages.push(build_distribute_msg(
+            env,
+            bridge_assets,
+            BRIDGES_INITIAL_DEPTH,
+        )?);
+    }
+
+    Ok(response.add_attributes([
+        attr("action", "consolidate_dust"),
+        attr("consolidated_count", consolidated.to_string()),
+    ]))
+}
+
+/// Swaps the whole balance of an asset which can't reach ROIDS to an intermediate asset. The
+/// intermediate stays in the Maker so it can be collected once it is routable.
+///
+/// * **asset** asset to swap.
+///
+/// * **intermediate** asset to receive, must have a pair with `asset`.
+///
+/// * **max_spread** max spread of the swap.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn swap_to_intermediate(
+    deps: Deps,
+    env: Env,
+    info: MessageInfo,
+    asset: AssetInfo,
+    intermediate: AssetInfo,
+    max_spread: Decimal,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if max_spread.is_zero() || max_spread > Decimal::from_str(MAX_ALLOWED_SLIPPAGE)? {
+        return Err(ContractError::IncorrectMaxSpread {});
+    };
+
+    if asset.equal(&intermediate) {
+        return Err(ContractError::InvalidBridge(asset, intermediate));
+    }
+
+    let pool = get_pool(&deps.querier, &cfg.factory_contract, &asset, &intermediate)?;
+
+    let amount = asset.query_pool(&deps.querier, &env.contract.address)?;
+    if amount.is_zero() {
+        return Err(ContractError::NothingToSwap(asset));
+    }
+
+    let simulation = simulate(
+        &deps.querier,
+        &pool.contract_addr,
+        &Asset {
+            info: asset.clone(),
+            amount,
+        },
+    )?;
+
+    let msg = build_swap_msg(
+        max_spread,
+        &pool.contract_addr,
+        &asset,
+        Some(&intermediate),
+        amount,
+        None,
+        None,
+    )?;
+
+    Ok(Response::new().add_submessage(msg).add_attributes([
+        attr("action", "swap_to_intermediate"),
+        attr("asset", asset.to_string()),
+        attr("intermediate", intermediate.to_string()),
+        attr("offer_amount", amount),
+        attr("expected_return_amount", simulation.return_amount),
+    ]))
+}
+
+/// Updates the max spread and the collect cooldown in one action. On top of the checks made by
+/// [`update_config`], a tight max spread can't be combined with a short cooldown as collects
+/// would then be retried often with swaps likely to fail.
+///
+/// * **max_spread** new max spread.
+///
+/// * **collect_cooldown** new collect cooldown.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn tune_risk(
+    mut deps: DepsMut,
+    info: MessageInfo,
+    max_spread: Decimal,
+    collect_cooldown: u64,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if max_spread.is_zero() || max_spread > Decimal::from_str(MAX_ALLOWED_SLIPPAGE)? {
+        return Err(ContractError::IncorrectMaxSpread {});
+    };
+    validate_cooldown(Some(collect_cooldown))?;
+
+    if max_spread < Decimal::percent(TIGHT_MAX_SPREAD) && collect_cooldown < SHORT_COLLECT_COOLDOWN
+    {
+        return Err(ContractError::RiskyParams {
+            max_spread,
+            collect_cooldown,
+        });
+    }
+
+    let event = Event::new("tune_risk")
+        .add_attribute("old_max_spread", config.max_spread.to_string())
+        .add_attribute("max_spread", max_spread.to_string())
+        .add_attribute(
+            "old_collect_cooldown",
+            config
+                .collect_cooldown
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| String::from("none")),
+        )
+        .add_attribute("collect_cooldown", collect_cooldown.to_string());
+
+    freeze_cooldown_window(deps.branch(), &config)?;
+    config.max_spread = max_spread;
+    config.collect_cooldown = Some(collect_cooldown);
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "tune_risk")
+        .add_event(event))
+}
+
+/// Starts a new cooldown window, failing if the one in progress didn't end yet. Callers exempt
+/// from the cooldown start a new window at any time.
+fn start_cooldown(
+    deps: DepsMut,
+    env: &Env,
+    cfg: &Config,
+    sender: &Addr,
+) -> Result<(), ContractError> {
+    if !COOLDOWN_EXEMPT.has(deps.storage, sender) {
+        if let Some(next_collect_ts) = next_collect_ts(deps.as_ref(), cfg)? {
+            if env.block.time.seconds() < next_collect_ts {
+                return Err(ContractError::Cooldown { next_collect_ts });
+            }
+        }
+    }
+    LAST_COLLECT_TS.save(deps.storage, &env.block.time.seconds())?;
+    COOLDOWN_WINDOW_END.remove(deps.storage);
+
+    Ok(())
+}
+
+/// Rejects collects executed past the deadline their caller set, since a collect lingering in the
+/// mempool would swap at stale prices. With `swap_deadline_secs` set every collect must carry a
+/// deadline at most that many seconds ahead.
+fn check_collect_deadline(
+    deps: Deps,
+    env: &Env,
+    deadline: Option<u64>,
+) -> Result<(), ContractError> {
+    let now = env.block.time.seconds();
+    let max_ahead = CONFIG.load(deps.storage)?.swap_deadline_secs;
+
+    match (deadline, max_ahead) {
+        (Some(deadline), _) if now > deadline => {
+            Err(ContractError::CollectDeadlineExceeded { deadline })
+        }
+        (Some(deadline), Some(secs)) if deadline > now + secs => {
+            Err(ContractError::CollectDeadlineTooFar {
+                deadline,
+                max: now + secs,
+            })
+        }
+        (None, Some(_)) => Err(ContractError::MissingCollectDeadline {}),
+        _ => Ok(()),
+    }
+}
+
+/// Returns the collect value window in progress. A new window opens once the previous one lasted
+/// for the collect cooldown, or for a day if no cooldown is set.
+fn current_value_window(deps: Deps, env: &Env, cfg: &Config) -> StdResult<ValueWindow> {
+    let now = env.block.time.seconds();
+    let length = cfg.collect_cooldown.unwrap_or(DEFAULT_VALUE_WINDOW_SECS);
+
+    Ok(match COLLECT_VALUE_WINDOW.may_load(deps.storage)? {
+        Some(window) if now < window.start + length => window,
+        _ => ValueWindow {
+            start: now,
+            spent: Uint128::zero(),
+        },
+    })
+}
+
+/// Keeps the end of the cooldown window in progress before `collect_cooldown` is changed,
+/// so the new cooldown only applies from the next collect on.
+fn freeze_cooldown_window(deps: DepsMut, cfg: &Config) -> StdResult<()> {
+    if COOLDOWN_WINDOW_END.may_load(deps.storage)?.is_none() {
+        let window_end =
+            next_collect_ts(deps.as_ref(), cfg)?.unwrap_or(LAST_COLLECT_TS.load(deps.storage)?);
+        COOLDOWN_WINDOW_END.save(deps.storage, &window_end)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the timestamp from which the next collect is allowed, if a cooldown applies.
+/// A window in progress when `collect_cooldown` was changed ends as originally scheduled.
+fn next_collect_ts(deps: Deps, cfg: &Config) -> StdResult<Option<u64>> {
+    if let Some(window_end) = COOLDOWN_WINDOW_END.may_load(deps.storage)? {
+        return Ok(Some(window_end));
+    }
+
+    cfg.collect_cooldown
+        .map(|cd_period| Ok(LAST_COLLECT_TS.load(deps.storage)? + cd_period))
+        .transpose()
+}
+
+/// Returns the route an asset without a bridge takes if it can be learned as its bridge, so
+/// later collects skip the route discovery. Only routes whose next hop is a valid bridge are
+/// learned, i.e. direct ROIDS pairs and default bridge routes reaching ROIDS.
+fn discover_route(
+    deps: Deps,
+    cfg: &Config,
+    asset: &AssetInfo,
+) -> Result<Option<DiscoveredRoute>, ContractError> {
+    if ROUTES.has(deps.storage, asset.to_string())
+        || EXTERNAL_DEXES.has(deps.storage, asset.to_string())
+    {
+        return Ok(None);
+    }
+
+    let (bridge, _) = get_next_hop(deps, cfg, asset)?;
+    if check_bridge_approved(cfg, &bridge).is_err() {
+        return Ok(None);
+    }
+    match validate_bridge(
+        deps,
+        &cfg.factory_contract,
+        asset,
+        &bridge,
+        &cfg.roids_token,
+        BRIDGES_INITIAL_DEPTH,
+    ) {
+        Ok(pool) => Ok(Some(DiscoveredRoute {
+            asset: asset.clone(),
+            bridge,
+            pool,
+        })),
+        Err(_) => Ok(None),
+    }
+}
+
+type SwapAssetsParts = (
+    Response,
+    Vec<AssetInfo>,
+    Vec<RouteProgress>,
+    Vec<Asset>,
+    Uint128,
+);
+
+/// Swap all non ASTRO tokens to ASTRO.
+///
+/// * **contract_addr** maker contract address.
+///
+/// * **assets** array with assets to swap to ASTRO.
+///
+/// * **value_budget** ROIDS value the swaps may reach, amounts above it are deferred.
+///
+/// Returns the swap messages, the bridge assets to swap next, the swept assets, the assets
+/// left for the fallback auction and the ROIDS value of the swaps.
+fn swap_assets(
+    deps: Deps,
+    contract_addr: &Addr,
+    cfg: &Config,
+    assets: Vec<AssetWithLimit>,
+    mut value_budget: Option<Uint128>,
+) -> Result<SwapAssetsParts, ContractError> {
+    let mut response = Response::default();
+    let mut bridge_assets = HashMap::new();
+    let mut swept = vec![];
+    let mut stranded = vec![];
+    let mut spent = Uint128::zero();
+
+    for a in assets {
+        if let Some(skipped) = skipped_asset_attr(deps.storage, &a.info) {
+            response = response.add_attribute(skipped.key, skipped.value);
+            continue;
+        }
+
+        let mut balance = get_collect_amount(deps, cfg, contract_addr, &a)?;
+
+        // Dust isn't worth the gas and tends to fail the spread checks
+        if !balance.is_zero() && is_dust(cfg, balance) {
+            response = response.add_attribute("skipped_dust", a.info.to_string());
+            continue;
+        }
+
+        // Assets without a route to ROIDS are auctioned instead of failing the collect
+        if cfg.auction_fallback.is_some() && !balance.is_zero() {
+            if let Err(ContractError::CannotSwap(_)) = resolve_route(deps, cfg, &a.info) {
+                stranded.push(Asset {
+                    info: a.info,
+                    amount: balance,
+                });
+                continue;
+            }
+        }
+
+        // Assets which can't be swapped within the max spread wait for better liquidity
+        if cfg.on_spread_exceeded == FailOrSkip::Skip && !balance.is_zero() {
+            let offered = net_of_transfer_tax(deps.storage, &a.info, balance)?;
+            if let Err(ContractError::MaxSpreadExceeded(_)) =
+                simulate_swap_to_roids(deps, cfg, &a.info, offered, 0)
+            {
+                response = response.add_attribute("skipped_spread_exceeded", a.info.to_string());
+                continue;
+            }
+        }
+
+        // The part of the balance exceeding the value budget is deferred to later collects
+        if let Some(budget) = value_budget.as_mut().filter(|_| !balance.is_zero()) {
+            let offered = net_of_transfer_tax(deps.storage, &a.info, balance)?;
+            let value = simulate_swap_to_roids(deps, cfg, &a.info, offered, 0)?;
+            if value > *budget {
+                let capped = balance.multiply_ratio(*budget, value);
+                response =
+                    response.add_attribute("deferred", format!("{}:{}", a.info, balance - capped));
+                balance = capped;
+                spent += *budget;
+                *budget = Uint128::zero();
+            } else {
+                spent += value;
+                *budget -= value;
+            }
+        }
+
+        if !balance.is_zero() {
+            // Assets whose pool price deviates from the oracle are left for a later collect
+            let target = match get_swap_executor(deps, cfg, &a.info)?
+                .build_swap(deps, cfg, &a.info, balance)
+            {
+                Err(ContractError::OracleDeviation { deviation_bps, .. })
+                    if !cfg.strict_collect =>
+                {
+                    response = response.add_attribute(
+                        "skipped_oracle_deviation",
+                        format!("{}:{deviation_bps}", a.info),
+                    );
+                    continue;
+                }
+                target => target?,
+            };
+
+            // Only the part of the balance left after the transfer tax reaches the pair
+            swept.push(RouteProgress {
+                asset: a.info.clone(),
+                input: balance,
+                holding: a.info.clone(),
+                held: net_of_transfer_tax(deps.storage, &a.info, balance)?,
+            });
+
+            // The slippage of each swap is recorded in the reply
+            let msg = match target {
+                SwapTarget::Roids(msg) => msg,
+                SwapTarget::Bridge { asset, msg } => {
+                    bridge_assets.insert(asset.to_string(), asset);
+                    msg
+                }
+            };
+            response.messages.push(SubMsg {
+                id: SWAP_REPLY_ID,
+                reply_on: ReplyOn::Success,
+                ..msg
+            });
+        }
+    }
+
+    Ok((
+        response,
+        bridge_assets.into_values().collect(),
+        swept,
+        stranded,
+        spent,
+    ))
+}
+
+/// Starts the fallback auction of each fee token a collect step found without a route to ROIDS.
+/// Fee tokens which are already auctioned keep their auction.
+fn auction_stranded(
+    storage: &mut dyn Storage,
+    env: &Env,
+    cfg: &Config,
+    mut response: Response,
+    stranded: Vec<Asset>,
+) -> StdResult<Response> {
+    let fallback = match &cfg.auction_fallback {
+        Some(fallback) => fallback,
+        None => return Ok(response),
+    };
+
+    let start_ts = env.block.time.seconds();
+    for asset in stranded {
+        if AUCTIONS.has(storage, asset.info.to_string()) {
+            continue;
+        }
+
+        response = response.add_attribute("auctioned", asset.to_string());
+        let auction = Auction {
+            asset,
+            start_price: fallback.start_price,
+            end_price: fallback.end_price,
+            start_ts,
+            end_ts: start_ts + fallback.duration_secs,
+        };
+        AUCTIONS.save(storage, auction.asset.info.to_string(), &auction)?;
+    }
+
+    Ok(response)
+}
+
+/// Swaps collected fees using bridge assets.
+///
+/// * **assets** array with fee tokens to swap as well as amount of tokens to swap.
+///
+/// * **depth** maximum route length used to swap a fee token.
+///
+/// ## Executor
+/// Only the Maker contract itself can execute this.
+fn swap_bridge_assets(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    assets: Vec<AssetInfo>,
+    depth: u64,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Pausing stops the collects in flight as well
+    if PAUSED.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::Paused {});
+    }
+
+    if assets.is_empty() {
+        return Ok(Response::default());
+    }
+
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Check that the contract doesn't call itself endlessly
+    if depth >= execution_max_depth(&cfg) {
+        return Err(ContractError::MaxBridgeDepth(depth));
+    }
+
+    // Swaps made by the collect itself are hop 0
+    SWAP_HOP.save(deps.storage, &(depth + 1))?;
+
+    // A dust consolidation only swaps what it added to the bridge assets
+    let dust_balances = DUST_BRIDGE_BALANCES.may_load(deps.storage)?;
+    let bridges = match &dust_balances {
+        Some(balances) => {
+            DUST_BRIDGE_BALANCES.remove(deps.storage);
+            dust_bridge_amounts(deps.as_ref(), &env.contract.address, balances, assets)?
+        }
+        None => assets
+            .into_iter()
+            .map(|a| AssetWithLimit {
+                info: a,
+                limit: None,
+                limit_semantics: None,
+            })
+            .collect(),
+    };
+    if bridges.is_empty() {
+        return Ok(Response::new().add_submessage(build_distribute_msg(env, vec![], depth + 1)?));
+    }
+    let swapped: HashMap<_, _> = bridges
+        .iter()
+        .filter_map(|a| a.limit.map(|limit| (a.info.to_string(), limit)))
+        .collect();
+
+    // The value of bridged amounts was counted by the collect which swept them
+    let (mut response, bridge_assets, swept, stranded, _) =
+        swap_assets(deps.as_ref(), &env.contract.address, &cfg, bridges, None)?;
+    snapshot_token_balances(deps.branch(), &env.contract.address, &bridge_assets)?;
+    if dust_balances.is_some() && !bridge_assets.is_empty() {
+        snapshot_dust_bridges(
+            deps.branch(),
+            &env.contract.address,
+            &bridge_assets,
+            &swapped,
+        )?;
+    }
+    let auctioned = !stranded.is_empty();
+    response = auction_stranded(deps.storage, &env, &cfg, response, stranded)?;
+
+    let quarantine = cfg.quarantine_failed_swaps && !cfg.strict_collect;
+    if quarantine {
+        response = quarantine_swaps(deps.storage, response, &swept)?;
+    }
+
+    // There should always be some messages, if there are none - something went wrong.
+    // Quarantined swaps of the previous hop or auctioned bridges may have left nothing to swap
+    // though
+    if response.messages.is_empty() && !quarantine && !auctioned {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Empty swap messages",
+        )));
+    }
+
+    let response = response
+        .add_submessage(build_distribute_msg(env, bridge_assets, depth + 1)?)
+        .add_attribute("action", "swap_bridge_assets");
+
+    Ok(tag_collect_id(deps.as_ref(), response)?)
+}
+
+/// Distributes ASTRO rewards to x/vxASTRO holders.
+///
+/// ## Executor
+/// Only the Maker contract itself can execute this.
+fn distribute_astro(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if PAUSED.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::Paused {});
+    }
+
+    let mut cfg = CONFIG.load(deps.storage)?;
+    let collector = take_pending_collector(deps.storage)?;
+    let (distribute_msg, attributes, amount) =
+        distribute(deps.branch(), env, &mut cfg, collector.as_ref(), false)?;
+    record_collector(deps.branch(), collector, amount)?;
+
+    // Data is set even if nothing was distributed so an orchestrating contract
+    // can tell the distribution ran
+    let data = to_json_binary(&DistributeResponseData {
+        amount,
+        destination: resolve_burn_destination(&cfg).0,
+    })?;
+
+    let response = Response::default()
+        .add_submessages(distribute_msg)
+        .add_attributes(attributes)
+        .set_data(data);
+
+    Ok(tag_collect_id(deps.as_ref(), response)?)
+}
+
+/// Takes the caller of the collect whose ROIDS is about to be distributed. The caller is cleared
+/// before the distribution runs so it can't be paid by a later distribution.
+fn take_pending_collector(storage: &mut dyn Storage) -> StdResult<Option<Addr>> {
+    let collector = PENDING_COLLECTOR.may_load(storage)?;
+    PENDING_COLLECTOR.remove(storage);
+
+    Ok(collector)
+}
+
+/// Records the caller of the collect whose ROIDS was just distributed.
+/// Distributions not started by a collect aren't recorded.
+///
+/// * **roids_realized** amount of ROIDS distributed.
+fn record_collector(
+    deps: DepsMut,
+    caller: Option<Addr>,
+    roids_realized: Uint128,
+) -> Result<(), ContractError> {
+    let caller = match caller {
+        Some(caller) => caller,
+        None => return Ok(()),
+    };
+
+    if RECENT_COLLECTORS.state().may_load(deps.storage)?.is_none() {
+        BufferManager::init(deps.storage, RECENT_COLLECTORS, RECENT_COLLECTORS_CAPACITY)?;
+    }
+
+    let record = CollectorRecord {
+        caller,
+        timestamp: LAST_COLLECT_TS.load(deps.storage)?,
+        roids_realized,
+    };
+    BufferManager::new(deps.storage, RECENT_COLLECTORS)?.instant_push(deps.storage, &record)?;
+
+    Ok(())
+}
+
+type DistributeMsgParts = (Vec<SubMsg>, Vec<Attribute>, Uint128);
+
+/// Adds ROIDS paid out of a distribution to the total put back into circulation.
+fn record_returned(storage: &mut dyn Storage, amount: Uint128) -> StdResult<()> {
+    let returned = RETURNED_TO_CIRCULATION
+        .may_load(storage)?
+        .unwrap_or_default();
+    RETURNED_TO_CIRCULATION.save(storage, &(returned + amount))
+}
 
 /// Private function that performs the ASTRO token distribution to x/vxASTRO.
+///
+/// * **collector** caller of the collect whose swaps are distributed, paid the keeper fee.
+///
+/// * **force** whether ROIDS is distributed regardless of the burn frequency and the hold
+/// threshold.
 fn distribute(
     deps: DepsMut,
     env: Env,
-    cfg: &mut Config,
-) -> Result<DistributeMsgParts, ContractError> {
-    let mut result = vec![];
-    let mut attributes = vec![];
+    cfg: &mut Config,
+    collector: Option<&Addr>,
+    force: bool,
+) -> Result<DistributeMsgParts, ContractError> {
+    let mut result = vec![];
+    let mut attributes = vec![];
+
+    let mut amount = cfg
+        .roids_token
+        .query_pool(&deps.querier, &env.contract.address)?;
+
+    // ROIDS gained since the swaps started was swap-derived, anything else was received directly.
+    // The realized balance is used rather than simulations so a swap returning more than
+    // simulated has its surplus burned as well
+    let mut keeper_reward = vec![];
+    if let Some(start_balance) = SWAPS_START_ROIDS.may_load(deps.storage)? {
+        SWAPS_START_ROIDS.remove(deps.storage);
+        let mut realized = amount.saturating_sub(start_balance);
+        if COLLECT_MIN_ROIDS_OUT.exists(deps.storage) {
+            COLLECT_ROIDS_OUT.save(deps.storage, &realized)?;
+        }
+
+        // The caller of the collect is paid a share of the ROIDS its own swept assets realized,
+        // not of bridge assets held before or ROIDS received in the meantime
+        if let (Some(fee_bps), Some(collector)) = (
+            cfg.keeper_fee_bps,
+            collector.filter(|collector| **collector != env.contract.address),
+        ) {
+            let collected: Uint128 = ROUTE_PROGRESS
+                .may_load(deps.storage)?
+                .unwrap_or_default()
+                .iter()
+                .filter(|entry| entry.holding == cfg.roids_token)
+                .map(|entry| entry.held)
+                .sum();
+            let fee = collected.min(realized).multiply_ratio(fee_bps, 10000u16);
+            if !fee.is_zero() {
+                keeper_reward.push(SubMsg::new(
+                    Asset {
+                        info: cfg.roids_token.clone(),
+                        amount: fee,
+                    }
+                    .into_msg::<Empty>(collector.clone())?,
+                ));
+                attributes.push(attr("keeper_fee", fee));
+                record_returned(deps.storage, fee)?;
+                realized -= fee;
+                amount -= fee;
+            }
+        }
+
+        let swapped = SWAPPED_ROIDS.may_load(deps.storage)?.unwrap_or_default();
+        SWAPPED_ROIDS.save(deps.storage, &(swapped + realized))?;
+    }
+
+    // A blocked burn is re-attempted on the following collects until the retry budget runs out
+    let retry_burn = match (cfg.burn_retry_limit, PENDING_BURN.may_load(deps.storage)?) {
+        (Some(limit), Some(_)) => BURN_RETRIES.may_load(deps.storage)?.unwrap_or_default() <= limit,
+        _ => false,
+    };
+
+    // ROIDS is accumulated until the configured number of collects is reached
+    if let Some(burn_every) = cfg.burn_every_n_collects.filter(|_| !force && !retry_burn) {
+        let collects = COLLECTS_SINCE_BURN
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        if collects < burn_every {
+            attributes.push(attr(
+                "collects_until_burn",
+                (burn_every - collects).to_string(),
+            ));
+            return Ok((keeper_reward, attributes, Uint128::zero()));
+        }
+    }
+
+    // ROIDS held from before the upgrade is only distributed as it's released
+    if let Some(mut rewards) = PRE_UPGRADE_REWARDS.may_load(deps.storage)? {
+        if !rewards.rewards_amount.is_zero() {
+            let blocks_passed = env.block.height - rewards.last_distribution_block;
+            let released = rewards
+                .pre_upgrade_amount
+                .multiply_ratio(blocks_passed, rewards.pre_upgrade_blocks)
+                .min(rewards.rewards_amount);
+            amount = amount.saturating_sub(rewards.rewards_amount) + released;
+
+            rewards.rewards_amount -= released;
+            rewards.last_distribution_block = env.block.height;
+            PRE_UPGRADE_REWARDS.save(deps.storage, &rewards)?;
+            attributes.push(attr("preupgrade_roids_distribution", released));
+        }
+    }
+
+    // The operational reserve is never distributed
+    if let Some(reserve_amount) = cfg.reserve_amount {
+        let reserved = amount.min(reserve_amount);
+        if !reserved.is_zero() {
+            attributes.push(attr("reserved_roids", reserved));
+            amount -= reserved;
+        }
+    }
+
+    if amount.is_zero() {
+        return Ok((keeper_reward, attributes, amount));
+    }
+
+    // Held ROIDS is only distributed once the balance reaches the threshold
+    if let DistributionMode::Hold { threshold } = &cfg.distribution_mode {
+        if amount < *threshold && !force {
+            attributes.push(attr("held_roids", amount));
+            return Ok((keeper_reward, attributes, Uint128::zero()));
+        }
+    }
+
+    // The second receiver is paid its cut before the remainder goes to the burn flow
+    if let Some(second_receiver) = &cfg.second_receiver_cfg {
+        let cut = amount.multiply_ratio(second_receiver.cut_bps, 10000u16);
+        if !cut.is_zero() {
+            keeper_reward.push(SubMsg::new(
+                Asset {
+                    info: cfg.roids_token.clone(),
+                    amount: cut,
+                }
+                .into_msg::<Empty>(second_receiver.address.clone())?,
+            ));
+            attributes.extend([
+                attr("second_receiver", second_receiver.address.as_str()),
+                attr("second_receiver_cut", cut),
+            ]);
+            record_returned(deps.storage, cut)?;
+            amount -= cut;
+        }
+    }
+
+    if let Some(dev_fund) = &cfg.dev_fund_config {
+        let share = amount * dev_fund.share;
+        if !share.is_zero() {
+            keeper_reward.push(build_dev_fund_msg(&deps.querier, cfg, dev_fund, share)?);
+            attributes.extend([
+                attr("dev_fund", &dev_fund.address),
+                attr("dev_fund_share", share),
+            ]);
+            record_returned(deps.storage, share)?;
+            amount -= share;
+        }
+    }
+
+    let swapped = SWAPPED_ROIDS
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .min(amount);
+    SWAPPED_ROIDS.remove(deps.storage);
+
+    if !amount.is_zero() {
+        result.push(SubMsg::new(build_distribution_msg(&env, cfg, amount)?));
+        // The next batch only starts once ROIDS was actually sent
+        if cfg.burn_every_n_collects.is_some() {
+            COLLECTS_SINCE_BURN.save(deps.storage, &0)?;
+        }
+    }
+
+    // The burned amount is accounted for once the burn reply confirms it
+    if !result.is_empty() {
+        BURNING_ROIDS.save(deps.storage, &amount)?;
+    }
+
+    // Burn failures are handled in the reply so a restrictive ROIDS token can't revert the swaps
+    let mut result: Vec<SubMsg> = keeper_reward
+        .into_iter()
+        .chain(result.into_iter().map(|msg| SubMsg {
+            id: BURN_REPLY_ID,
+            reply_on: ReplyOn::Always,
+            ..msg
+        }))
+        .collect();
+
+    // Each hook runs with its own gas limit and its failure is caught in the reply
+    if !amount.is_zero() {
+        for hook in DISTRIBUTION_HOOKS.keys(deps.storage, None, None, Order::Ascending) {
+            result.push(SubMsg {
+                id: DISTRIBUTION_HOOK_REPLY_ID,
+                msg: wasm_execute(
+                    hook?,
+                    &DistributionHookMsg::AfterDistribute { amount },
+                    vec![],
+                )?
+                .into(),
+                gas_limit: Some(DISTRIBUTION_HOOK_GAS_LIMIT),
+                reply_on: ReplyOn::Error,
+            });
+        }
+    }
+
+    attributes.extend([
+        attr("action", "distribute_roids"),
+        attr("roids_amount", amount),
+        attr("roids_from_swaps", swapped),
+        attr("roids_received_directly", amount - swapped),
+        attr("destination", resolve_burn_destination(cfg).0),
+        attr("distribution_mode", cfg.distribution_mode.as_str()),
+    ]);
+    if retry_burn {
+        attributes.push(attr("burn_retry", "true"));
+    }
+
+    Ok((result, attributes, amount))
+}
+
+/// Releases the ROIDS held by the Maker from before its upgrade linearly over a number of blocks,
+/// so it isn't dumped in a single distribution. Each distribution releases the share of the
+/// blocks passed since the previous one.
+///
+/// * **blocks** number of blocks the pre-upgrade ROIDS is released over.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn enable_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    blocks: u64,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if PRE_UPGRADE_REWARDS.exists(deps.storage) {
+        return Err(ContractError::RewardsAlreadyEnabled {});
+    }
+    if blocks == 0 {
+        return Err(ContractError::IncorrectPreUpgradeBlocks {});
+    }
+
+    let pre_upgrade_amount = cfg
+        .roids_token
+        .query_pool(&deps.querier, &env.contract.address)?;
+    PRE_UPGRADE_REWARDS.save(
+        deps.storage,
+        &PreUpgradeRewards {
+            pre_upgrade_blocks: blocks,
+            last_distribution_block: env.block.height,
+            rewards_amount: pre_upgrade_amount,
+            pre_upgrade_amount,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "enable_rewards"),
+        attr("pre_upgrade_amount", pre_upgrade_amount),
+        attr("pre_upgrade_blocks", blocks.to_string()),
+    ]))
+}
+
+/// Updates general contract parameters. The contracts fees are sent to are changed with a
+/// timelock through [`propose_config_change`] instead.
+///
+/// * **staking_contract** address of the xASTRO staking contract.
+///
+/// * **governance_contract** address of the vxASTRO fee distributor contract.
+///
+/// * **governance_percent** percentage of ASTRO that goes to the vxASTRO fee distributor.
+///
+/// * **default_bridge_opt** default bridge asset used for intermediate swaps to ASTRO.
+///
+/// * **max_spread** max spread used when swapping fee tokens to ASTRO.
+///
+/// * **swap_deadline_secs** max number of seconds a collect deadline may lie ahead. 0 lifts the
+/// bound.
+///
+/// * **auto_pause_threshold** number of consecutive failed collects that pauses the Maker. 0
+/// disables the circuit breaker.
+///
+/// * **burn_every_n_collects** number of collects to accumulate ROIDS over before burning.
+///
+/// ## Executor
+/// Only the owner can execute this.
+#[allow(clippy::too_many_arguments)]
+fn update_config(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    default_bridge_opt: Option<AssetInfo>,
+    max_spread: Option<Decimal>,
+    collect_cooldown: Option<u64>,
+    swap_deadline_secs: Option<u64>,
+    auto_pause_threshold: Option<u32>,
+    burn_every_n_collects: Option<u32>,
+    dust_threshold: Option<Uint128>,
+    keeper_commit_window_secs: Option<u64>,
+    min_receive_buffer_bps: Option<u16>,
+    lenient_burn: Option<bool>,
+    max_bridges: Option<u32>,
+    strict_collect: Option<bool>,
+    post_unpause_grace_secs: Option<u64>,
+    burn_retry_limit: Option<u32>,
+    learn_routes: Option<bool>,
+    keeper_fee_bps: Option<u16>,
+    guardian: Option<String>,
+    seize_delay_secs: Option<u64>,
+    min_collect_amount: Option<Uint128>,
+    quarantine_failed_swaps: Option<bool>,
+    max_collect_value_per_window: Option<Uint128>,
+    bridges_execution_max_depth: Option<u64>,
+    tokenfactory_burn: Option<bool>,
+    max_assets_per_tx: Option<u32>,
+    belief_price_source: Option<BeliefPriceSource>,
+    on_spread_exceeded: Option<FailOrSkip>,
+    reserve_amount: Option<Uint128>,
+    auction_fallback: Option<AuctionFallback>,
+    operator: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut attributes = vec![attr("action", "set_config")];
+
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Every route may change along with the default bridge
+    if default_bridge_opt.is_some() {
+        ROUTES_RESET_HEIGHT.save(deps.storage, &env.block.height)?;
+    }
+
+    if let Some(default_bridge) = &default_bridge_opt {
+        default_bridge.check(deps.api)?;
+        check_bridge_approved(&config, default_bridge)?;
+        attributes.push(attr("default_bridge", default_bridge.to_string()));
+        config.default_bridge = default_bridge_opt;
+    }
+
+    if let Some(max_spread) = max_spread {
+        if max_spread.is_zero() || max_spread > Decimal::from_str(MAX_ALLOWED_SLIPPAGE)? {
+            return Err(ContractError::IncorrectMaxSpread {});
+        };
+
+        config.max_spread = max_spread;
+        attributes.push(attr("max_spread", max_spread.to_string()));
+    };
+
+    if let Some(collect_cooldown) = collect_cooldown {
+        validate_cooldown(Some(collect_cooldown))?;
+        freeze_cooldown_window(deps.branch(), &config)?;
+        config.collect_cooldown = Some(collect_cooldown);
+        attributes.push(attr("collect_cooldown", collect_cooldown.to_string()));
+    }
+
+    if let Some(swap_deadline_secs) = swap_deadline_secs {
+        // Zero lifts the bound, collects need no deadline anymore
+        config.swap_deadline_secs = Some(swap_deadline_secs).filter(|v| *v > 0);
+        attributes.push(attr("swap_deadline_secs", swap_deadline_secs.to_string()));
+    }
+
+    if let Some(auto_pause_threshold) = auto_pause_threshold {
+        // Zero disables the circuit breaker, its failure streak starts over once re-enabled
+        config.auto_pause_threshold = Some(auto_pause_threshold).filter(|v| *v > 0);
+        if config.auto_pause_threshold.is_none() {
+            CONSECUTIVE_FAILED_COLLECTS.remove(deps.storage);
+            COLLECT_FAILED.remove(deps.storage);
+        }
+        attributes.push(attr(
+            "auto_pause_threshold",
+            auto_pause_threshold.to_string(),
+        ));
+    }
+
+    if let Some(burn_every_n_collects) = burn_every_n_collects {
+        match burn_every_n_collects {
+            0 => return Err(ContractError::IncorrectBurnFrequency {}),
+            // Burning every collect is the default behaviour
+            1 => config.burn_every_n_collects = None,
+            _ => {
+                // The first batch starts from scratch
+                COLLECTS_SINCE_BURN.save(deps.storage, &0)?;
+                config.burn_every_n_collects = Some(burn_every_n_collects)
+            }
+        }
+        attributes.push(attr(
+            "burn_every_n_collects",
+            burn_every_n_collects.to_string(),
+        ));
+    }
+
+    if let Some(dust_threshold) = dust_threshold {
+        // Zero disables dust consolidation
+        config.dust_threshold = Some(dust_threshold).filter(|v| !v.is_zero());
+        attributes.push(attr("dust_threshold", dust_threshold));
+    }
+
+    if let Some(keeper_commit_window_secs) = keeper_commit_window_secs {
+        // Zero disables keeper commitments
+        config.keeper_commit_window_secs = Some(keeper_commit_window_secs).filter(|v| *v > 0);
+        if config.keeper_commit_window_secs.is_none() {
+            KEEPER_COMMITMENT.remove(deps.storage);
+        }
+        attributes.push(attr(
+            "keeper_commit_window_secs",
+            keeper_commit_window_secs.to_string(),
+        ));
+    }
+
+    if let Some(min_receive_buffer_bps) = min_receive_buffer_bps {
+        validate_min_receive_buffer(min_receive_buffer_bps)?;
+        // Zero disables minimum receive checks
+        config.min_receive_buffer_bps = Some(min_receive_buffer_bps).filter(|v| *v > 0);
+        attributes.push(attr(
+            "min_receive_buffer_bps",
+            min_receive_buffer_bps.to_string(),
+        ));
+    }
+
+    if let Some(lenient_burn) = lenient_burn {
+        config.lenient_burn = lenient_burn;
+        attributes.push(attr("lenient_burn", lenient_burn.to_string()));
+    }
+
+    if let Some(strict_collect) = strict_collect {
+        config.strict_collect = strict_collect;
+        attributes.push(attr("strict_collect", strict_collect.to_string()));
+    }
+
+    if let Some(post_unpause_grace_secs) = post_unpause_grace_secs {
+        config.post_unpause_grace_secs = Some(post_unpause_grace_secs).filter(|secs| *secs > 0);
+        attributes.push(attr(
+            "post_unpause_grace_secs",
+            post_unpause_grace_secs.to_string(),
+        ));
+    }
+
+    if let Some(burn_retry_limit) = burn_retry_limit {
+        config.burn_retry_limit = Some(burn_retry_limit).filter(|limit| *limit > 0);
+        attributes.push(attr("burn_retry_limit", burn_retry_limit.to_string()));
+    }
+
+    if let Some(learn_routes) = learn_routes {
+        config.learn_routes = learn_routes;
+        attributes.push(attr("learn_routes", learn_routes.to_string()));
+    }
+
+    if let Some(keeper_fee_bps) = keeper_fee_bps {
+        validate_keeper_fee(keeper_fee_bps)?;
+        // Zero disables the keeper fee
+        config.keeper_fee_bps = Some(keeper_fee_bps).filter(|v| *v > 0);
+        attributes.push(attr("keeper_fee_bps", keeper_fee_bps.to_string()));
+    }
+
+    if let Some(seize_delay_secs) = seize_delay_secs {
+        if seize_delay_secs == 0 {
+            return Err(ContractError::IncorrectSeizeDelay {});
+        }
+        config.seize_delay_secs = Some(seize_delay_secs);
+        attributes.push(attr("seize_delay_secs", seize_delay_secs.to_string()));
+    }
+
+    if let Some(min_collect_amount) = min_collect_amount {
+        config.min_collect_amount = Some(min_collect_amount).filter(|v| !v.is_zero());
+        attributes.push(attr("min_collect_amount", min_collect_amount));
+    }
+
+    if let Some(quarantine_failed_swaps) = quarantine_failed_swaps {
+        config.quarantine_failed_swaps = quarantine_failed_swaps;
+        attributes.push(attr(
+            "quarantine_failed_swaps",
+            quarantine_failed_swaps.to_string(),
+        ));
+    }
+
+    if let Some(max_value) = max_collect_value_per_window {
+        config.max_collect_value_per_window = Some(max_value).filter(|v| !v.is_zero());
+        attributes.push(attr("max_collect_value_per_window", max_value));
+    }
+
+    if let Some(bridges_execution_max_depth) = bridges_execution_max_depth {
+        validate_bridges_execution_depth(bridges_execution_max_depth)?;
+        config.bridges_execution_max_depth = Some(bridges_execution_max_depth);
+        attributes.push(attr(
+            "bridges_execution_max_depth",
+            bridges_execution_max_depth.to_string(),
+        ));
+    }
+
+    if let Some(tokenfactory_burn) = tokenfactory_burn {
+        config.tokenfactory_burn = tokenfactory_burn;
+        attributes.push(attr("tokenfactory_burn", tokenfactory_burn.to_string()));
+    }
+
+    if let Some(max_assets_per_tx) = max_assets_per_tx {
+        // Zero swaps every asset in a single transaction
+        config.max_assets_per_tx = Some(max_assets_per_tx).filter(|max| *max > 0);
+        attributes.push(attr("max_assets_per_tx", max_assets_per_tx.to_string()));
+    }
+
+    if let Some(belief_price_source) = belief_price_source {
+        attributes.push(attr("belief_price_source", belief_price_source.as_str()));
+        config.belief_price_source = belief_price_source;
+    }
+
+    if let Some(on_spread_exceeded) = on_spread_exceeded {
+        attributes.push(attr("on_spread_exceeded", on_spread_exceeded.as_str()));
+        config.on_spread_exceeded = on_spread_exceeded;
+    }
+
+    if let Some(reserve_amount) = reserve_amount {
+        config.reserve_amount = Some(reserve_amount).filter(|amount| !amount.is_zero());
+        attributes.push(attr("reserve_amount", reserve_amount));
+    }
+
+    if let Some(fallback) = auction_fallback {
+        attributes.push(attr(
+            "auction_fallback_duration",
+            fallback.duration_secs.to_string(),
+        ));
+        // A zero duration removes the fallback
+        config.auction_fallback = if fallback.duration_secs == 0 {
+            None
+        } else {
+            validate_auction_prices(
+                fallback.start_price,
+                fallback.end_price,
+                fallback.duration_secs,
+            )?;
+            Some(fallback)
+        };
+    }
+
+    if config.tokenfactory_burn {
+        validate_tokenfactory_burn(&env, &config.roids_token)?;
+    }
+
+    if let Some(guardian) = guardian {
+        config.guardian = if guardian.is_empty() {
+            None
+        } else {
+            Some(deps.api.addr_validate(&guardian)?)
+        };
+        attributes.push(attr("guardian", guardian));
+    }
+
+    if let Some(operator) = operator {
+        config.operator = if operator.is_empty() {
+            None
+        } else {
+            Some(deps.api.addr_validate(&operator)?)
+        };
+        attributes.push(attr("operator", operator));
+    }
+
+    if let Some(max_bridges) = max_bridges {
+        let max_bridges = Some(max_bridges).filter(|max| *max > 0);
+        if let Some(max) = max_bridges {
+            let count = ROUTES
+                .keys(deps.storage, None, None, Order::Ascending)
+                .count() as u32;
+            if count > max {
+                return Err(ContractError::MaxBridgesTooLow { max, count });
+            }
+        }
+
+        config.max_bridges = max_bridges;
+        attributes.push(attr(
+            "max_bridges",
+            max_bridges
+                .map(|max| max.to_string())
+                .unwrap_or_else(|| String::from("none")),
+        ));
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+    record_config_change(
+        deps.storage,
+        &env,
+        &info.sender,
+        "set_config",
+        attributes[1..].to_vec(),
+    )?;
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Adds or removes bridge tokens used to swap fee tokens to ASTRO.
+///
+/// * **add** array of bridge tokens added to swap fee tokens with.
+///
+/// * **remove** array of bridge tokens removed from being used to swap certain fee tokens.
+///
+/// ## Executor
+/// Only the bridge manager or the operator can execute this.
+fn update_bridges(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    add: Option<Vec<(AssetInfo, AssetInfo)>>,
+    remove: Option<Vec<AssetInfo>>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if cfg.operator.as_ref() != Some(&info.sender) {
+        assert_role(deps.as_ref(), &cfg, &info.sender, &Role::BridgeManager)?;
+    }
+
+    let mut changes = vec![];
+
+    // Remove old bridges
+    if let Some(remove_bridges) = remove {
+        for asset in remove_bridges {
+            changes.push(attr(asset.to_string(), "none"));
+            ROUTES.remove(deps.storage, asset.to_string());
+            BRIDGE_CHANGES.save(
+                deps.storage,
+                (env.block.height, &asset.to_string()),
+                &Empty {},
+            )?;
+        }
+    }
+
+    // Add new bridges
+    let astro = cfg.roids_token.clone();
+    let added = add.clone().unwrap_or_default();
+    let mut memo = BridgeMemo::new();
+    if let Some(add_bridges) = add {
+        for (asset, bridge) in add_bridges {
+            check_bridge_entry(&cfg, &asset, &bridge)?;
+
+            // Check that bridge tokens can be swapped to ASTRO. Bridges sharing a chain are only
+            // validated once
+            let pool = validate_bridge_memo(
+                deps.as_ref(),
+                &cfg.factory_contract,
+                &asset,
+                &bridge,
+                &astro,
+                BRIDGES_INITIAL_DEPTH,
+                &mut memo,
+            )?;
+
+            ROUTES.save(deps.storage, asset.to_string(), &vec![bridge.clone()])?;
+            record_pair_types(deps.storage, &[pool])?;
+            BRIDGE_CHANGES.save(
+                deps.storage,
+                (env.block.height, &asset.to_string()),
+                &Empty {},
+            )?;
+            changes.push(attr(asset.to_string(), bridge.to_string()));
+        }
+    }
+
+    // Each bridge is validated against the bridges stored before it, so the whole chain is
+    // checked once all of them are in place. Assets added several times are checked once
+    let mut checked = HashSet::new();
+    for (asset, _) in &added {
+        if checked.insert(asset.to_string()) {
+            check_bridge_chain(deps.as_ref(), &cfg, asset)?;
+        }
+    }
+
+    check_max_bridges(deps.storage, &cfg)?;
+
+    record_config_change(deps.storage, &env, &info.sender, "update_bridges", changes)?;
+
+    Ok(Response::default().add_attribute("action", "update_bridges"))
+}
+
+/// Checks a bridge of a fee token before it is stored. The bridge must be approved and can't be
+/// the fee token itself, nor can ROIDS be bridged.
+fn check_bridge_entry(
+    cfg: &Config,
+    asset: &AssetInfo,
+    bridge: &AssetInfo,
+) -> Result<(), ContractError> {
+    if asset.equal(bridge) || asset.equal(&cfg.roids_token) {
+        return Err(ContractError::InvalidBridge(asset.clone(), bridge.clone()));
+    }
+
+    check_bridge_approved(cfg, bridge)
+}
+
+/// Bulk loads routes exported with `ExportBridges`. Routes of several hops are validated like
+/// in `UpdateRoutes`. Every bridge is stored before any of them is validated like in
+/// `UpdateBridges`, so a bridge may go through a bridge imported after it.
+///
+/// * **entries** fee tokens along with their routes.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn import_bridges(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    entries: Vec<BridgeInfo>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let imported = entries.len();
+    let mut bridges = vec![];
+    let mut changes = vec![];
+    for BridgeInfo { asset, route } in entries {
+        changes.push(attr(
+            asset.to_string(),
+            route
+                .iter()
+                .map(|hop| hop.to_string())
+                .collect::<Vec<_>>()
+                .join(" -> "),
+        ));
+
+        match &route[..] {
+            [bridge] => {
+                check_bridge_entry(&cfg, &asset, bridge)?;
+
+                ROUTES.save(deps.storage, asset.to_string(), &route)?;
+                BRIDGE_CHANGES.save(
+                    deps.storage,
+                    (env.block.height, &asset.to_string()),
+                    &Empty {},
+                )?;
+                bridges.push((asset, bridge.clone()));
+            }
+            _ => {
+                let pools = validate_route(deps.as_ref(), &cfg, &asset, &route)?;
+                save_route(deps.storage, &env, &asset, &route, &pools)?;
+            }
+        }
+    }
+
+    let mut memo = BridgeMemo::new();
+    for (asset, bridge) in &bridges {
+        let pool = validate_bridge_memo(
+            deps.as_ref(),
+            &cfg.factory_contract,
+            asset,
+            bridge,
+            &cfg.roids_token,
+            BRIDGES_INITIAL_DEPTH,
+            &mut memo,
+        )?;
+        record_pair_types(deps.storage, &[pool])?;
+        check_bridge_chain(deps.as_ref(), &cfg, asset)?;
+    }
+
+    check_max_bridges(deps.storage, &cfg)?;
+
+    record_config_change(deps.storage, &env, &info.sender, "import_bridges", changes)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "import_bridges"),
+        attr("imported", imported.to_string()),
+    ]))
+}
+
+/// Sets or removes full routes of fee tokens to ROIDS. Every hop of a route must have a pair in
+/// the factory. A route of several hops is swapped through the router at once, so only the route
+/// of the fee token itself decides the hops it takes.
+///
+/// * **add** fee tokens along with their routes. Each route ends with ROIDS.
+///
+/// * **remove** fee tokens whose routes are removed.
+///
+/// ## Executor
+/// Only the bridge manager can execute this.
+fn update_routes(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    add: Option<Vec<(AssetInfo, Vec<AssetInfo>)>>,
+    remove: Option<Vec<AssetInfo>>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    assert_role(deps.as_ref(), &cfg, &info.sender, &Role::BridgeManager)?;
+
+    for asset in remove.unwrap_or_default() {
+        ROUTES.remove(deps.storage, asset.to_string());
+        BRIDGE_CHANGES.save(
+            deps.storage,
+            (env.block.height, &asset.to_string()),
+            &Empty {},
+        )?;
+    }
+
+    for (asset, route) in add.unwrap_or_default() {
+        let pools = validate_route(deps.as_ref(), &cfg, &asset, &route)?;
+        save_route(deps.storage, &env, &asset, &route, &pools)?;
+    }
+
+    check_max_bridges(deps.storage, &cfg)?;
+
+    Ok(Response::new().add_attribute("action", "update_routes"))
+}
+
+/// Checks that a route of a fee token ends with ROIDS, doesn't visit an asset twice, fits the
+/// bridges depth and has a pair in the factory for every hop. Routes of several hops need the
+/// router to be swapped. Returns the pools of the hops.
+fn validate_route(
+    deps: Deps,
+    cfg: &Config,
+    asset: &AssetInfo,
+    route: &[AssetInfo],
+) -> Result<Vec<PairInfo>, ContractError> {
+    let mut uniq = HashSet::from([asset.to_string()]);
+    if route.last() != Some(&cfg.roids_token)
+        || !route.iter().all(|hop| uniq.insert(hop.to_string()))
+    {
+        return Err(ContractError::InvalidRoute(asset.clone()));
+    }
+
+    // The first hop is made by collect itself, the following ones by SwapBridgeAssets
+    if route.len() as u64 > execution_max_depth(cfg) + 1 {
+        return Err(ContractError::MaxBridgeDepth(route.len() as u64));
+    }
+
+    if route.len() > 1 && cfg.router_contract.is_none() {
+        return Err(ContractError::RouterRequired(asset.clone()));
+    }
+
+    let mut pools = vec![];
+    let mut from = asset;
+    for hop in route {
+        check_bridge_approved(cfg, hop)?;
+        pools.push(get_pool(&deps.querier, &cfg.factory_contract, from, hop)?);
+        from = hop;
+    }
+
+    Ok(pools)
+}
+
+/// Stores a validated route of a fee token along with the pair types of its pools.
+fn save_route(
+    storage: &mut dyn Storage,
+    env: &Env,
+    asset: &AssetInfo,
+    route: &[AssetInfo],
+    pools: &[PairInfo],
+) -> StdResult<()> {
+    ROUTES.save(storage, asset.to_string(), &route.to_vec())?;
+    record_pair_types(storage, pools)?;
+    BRIDGE_CHANGES.save(storage, (env.block.height, &asset.to_string()), &Empty {})
+}
+
+/// Makes sure the stored routes, bridges included, don't exceed `max_bridges`.
+fn check_max_bridges(storage: &dyn Storage, cfg: &Config) -> Result<(), ContractError> {
+    if let Some(max) = cfg.max_bridges {
+        let count = ROUTES.keys(storage, None, None, Order::Ascending).count() as u32;
+        if count > max {
+            return Err(ContractError::MaxBridgesExceeded { max });
+        }
+    }
+
+    Ok(())
+}
+
+/// Proposes a route of a fee token to ROIDS. The route is validated against the factory and
+/// waits for the owner's review.
+///
+/// * **asset** fee token to route.
+///
+/// * **route** proposed route of the fee token.
+fn propose_bridge(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: AssetInfo,
+    route: Vec<AssetInfo>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // A pending proposal can't be replaced before it is reviewed
+    if BRIDGE_PROPOSALS.has(deps.storage, asset.to_string()) {
+        return Err(ContractError::BridgeProposalExists(asset));
+    }
+
+    validate_route(deps.as_ref(), &cfg, &asset, &route)?;
+
+    BRIDGE_PROPOSALS.save(
+        deps.storage,
+        asset.to_string(),
+        &BridgeProposal {
+            asset: asset.clone(),
+            route,
+            proposer: info.sender.clone(),
+        },
+    )?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "propose_bridge"),
+        attr("asset", asset.to_string()),
+        attr("proposer", info.sender),
+    ]))
+}
+
+/// Approves or rejects the pending route proposal of a fee token. An approved route is set as
+/// if by `UpdateRoutes`.
+///
+/// * **asset** fee token whose proposal is reviewed.
+///
+/// * **approve** whether the proposed route is set.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn review_bridge_proposal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset: AssetInfo,
+    approve: bool,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let proposal = BRIDGE_PROPOSALS
+        .may_load(deps.storage, asset.to_string())?
+        .ok_or_else(|| ContractError::NoBridgeProposal(asset.clone()))?;
+    BRIDGE_PROPOSALS.remove(deps.storage, asset.to_string());
+
+    if approve {
+        // Pairs or routes may have changed since the proposal
+        let pools = validate_route(deps.as_ref(), &cfg, &asset, &proposal.route)?;
+        save_route(deps.storage, &env, &asset, &proposal.route, &pools)?;
+        check_max_bridges(deps.storage, &cfg)?;
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "review_bridge_proposal"),
+        attr("asset", asset.to_string()),
+        attr("approved", approve.to_string()),
+    ]))
+}
+
+/// Sets or removes the minimum ROIDS fee tokens must realize when collected.
+///
+/// * **add** fee tokens along with their minimum received.
+///
+/// * **remove** fee tokens whose minimum received is removed.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn update_min_received(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Option<Vec<(AssetInfo, MinReceived)>>,
+    remove: Option<Vec<AssetInfo>>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for asset in remove.unwrap_or_default() {
+        MIN_RECEIVED.remove(deps.storage, asset.to_string());
+    }
+
+    for (asset, min_received) in add.unwrap_or_default() {
+        let min_received = match min_received {
+            MinReceived::Price(price) if !price.is_zero() => MinReceived::Price(price),
+            MinReceived::Oracle {
+                contract,
+                tolerance_bps,
+            } if tolerance_bps < 10000 => MinReceived::Oracle {
+                contract: deps.api.addr_validate(&contract)?.to_string(),
+                tolerance_bps,
+            },
+            _ => return Err(ContractError::InvalidMinReceived(asset)),
+        };
+        MIN_RECEIVED.save(deps.storage, asset.to_string(), &min_received)?;
+    }
+
+    Ok(Response::new().add_attribute("action", "update_min_received"))
+}
+
+/// Sets or removes max spreads of specific fee tokens. They take precedence over the global max
+/// spread and are translated for concentrated pairs just like it.
+///
+/// * **add** fee tokens along with their max spreads.
+///
+/// * **remove** fee tokens whose max spreads are removed.
+///
+/// ## Executor
+/// Only the owner or the operator can execute this.
+fn update_spreads(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Option<Vec<(AssetInfo, Decimal)>>,
+    remove: Option<Vec<AssetInfo>>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner && cfg.operator.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes = vec![attr("action", "update_spreads")];
+
+    for asset in remove.unwrap_or_default() {
+        SPREADS.remove(deps.storage, asset.to_string());
+        attributes.push(attr("removed_spread", asset.to_string()));
+    }
+
+    for (asset, max_spread) in add.unwrap_or_default() {
+        if max_spread.is_zero() || max_spread > Decimal::from_str(MAX_ALLOWED_SLIPPAGE)? {
+            return Err(ContractError::IncorrectMaxSpread {});
+        }
+        SPREADS.save(deps.storage, asset.to_string(), &max_spread)?;
+        attributes.push(attr("spread", format!("{asset}:{max_spread}")));
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Adds or removes assets which are never swapped, e.g. malicious or fee-on-transfer tokens.
+///
+/// * **add** assets to blacklist.
+///
+/// * **remove** assets removed from the blacklist.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn update_blacklist(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Option<Vec<AssetInfo>>,
+    remove: Option<Vec<AssetInfo>>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes = vec![attr("action", "update_blacklist")];
+
+    for asset in remove.unwrap_or_default() {
+        BLACKLIST.remove(deps.storage, asset.to_string());
+        attributes.push(attr("removed_asset", asset.to_string()));
+    }
+
+    for asset in add.unwrap_or_default() {
+        BLACKLIST.save(deps.storage, asset.to_string(), &asset)?;
+        attributes.push(attr("blacklisted_asset", asset.to_string()));
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Adds or removes callers whose collects aren't subject to the collect cooldown, e.g. the DAO's
+/// own bot collecting in an emergency.
+///
+/// * **add** callers exempted from the cooldown.
+///
+/// * **remove** callers subject to the cooldown again.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn update_cooldown_exempt(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Option<Vec<String>>,
+    remove: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes = vec![attr("action", "update_cooldown_exempt")];
+
+    for addr in remove.unwrap_or_default() {
+        let addr = deps.api.addr_validate(&addr)?;
+        COOLDOWN_EXEMPT.remove(deps.storage, &addr);
+        attributes.push(attr("removed_caller", addr));
+    }
+
+    for addr in add.unwrap_or_default() {
+        let addr = deps.api.addr_validate(&addr)?;
+        COOLDOWN_EXEMPT.save(deps.storage, &addr, &Empty {})?;
+        attributes.push(attr("exempt_caller", addr));
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Adds or removes contracts called with `AfterDistribute` after each distribution, e.g. to
+/// react to burns without polling.
+///
+/// * **add** contracts called after each distribution.
+///
+/// * **remove** contracts no longer called.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn update_distribution_hooks(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Option<Vec<String>>,
+    remove: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes = vec![attr("action", "update_distribution_hooks")];
+
+    for addr in remove.unwrap_or_default() {
+        let addr = deps.api.addr_validate(&addr)?;
+        DISTRIBUTION_HOOKS.remove(deps.storage, &addr);
+        attributes.push(attr("removed_hook", addr));
+    }
+
+    for addr in add.unwrap_or_default() {
+        let addr = deps.api.addr_validate(&addr)?;
+        DISTRIBUTION_HOOKS.save(deps.storage, &addr, &Empty {})?;
+        attributes.push(attr("added_hook", addr));
+    }
+
+    let count = DISTRIBUTION_HOOKS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .count() as u32;
+    if count > MAX_DISTRIBUTION_HOOKS {
+        return Err(ContractError::MaxDistributionHooksExceeded {
+            max: MAX_DISTRIBUTION_HOOKS,
+        });
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Sets or removes the TWAP oracles the pool prices of specific assets are checked against
+/// before swapping them.
+///
+/// * **add** assets along with their oracles.
+///
+/// * **remove** assets whose oracles are removed.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn update_price_oracles(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Option<Vec<(AssetInfo, PriceOracle)>>,
+    remove: Option<Vec<AssetInfo>>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes = vec![attr("action", "update_price_oracles")];
+
+    for asset in remove.unwrap_or_default() {
+        PRICE_ORACLES.remove(deps.storage, asset.to_string());
+        attributes.push(attr("removed_price_oracle", asset.to_string()));
+    }
+
+    for (asset, oracle) in add.unwrap_or_default() {
+        if oracle.max_deviation_bps == 0 || oracle.max_deviation_bps > 10000 {
+            return Err(ContractError::InvalidPriceOracle(asset));
+        }
+        deps.api.addr_validate(&oracle.contract)?;
+        attributes.push(attr("price_oracle", format!("{asset}:{}", oracle.contract)));
+        PRICE_ORACLES.save(deps.storage, asset.to_string(), &oracle)?;
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Removes the external DEXes specific fee tokens are swapped on. External DEXes receive fee
+/// tokens, so they are only set through [`propose_config_change`].
+///
+/// * **remove** fee tokens swapped in the factory pairs again.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn update_external_dexes(
+    deps: DepsMut,
+    info: MessageInfo,
+    remove: Option<Vec<AssetInfo>>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes = vec![attr("action", "update_external_dexes")];
+
+    for asset in remove.unwrap_or_default() {
+        EXTERNAL_DEXES.remove(deps.storage, asset.to_string());
+        attributes.push(attr("removed_external_dex", asset.to_string()));
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Pauses or unpauses collects. Unpausing resets the consecutive failed collects counter and
+/// starts the post unpause grace window.
+///
+/// ## Executor
+/// Only the pauser or the guardian can execute this.
+fn set_paused(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if cfg.guardian.as_ref() != Some(&info.sender) {
+        assert_role(deps.as_ref(), &cfg, &info.sender, &Role::Pauser)?;
+    }
+
+    PAUSED.save(deps.storage, &paused)?;
+    if !paused {
+        CONSECUTIVE_FAILED_COLLECTS.save(deps.storage, &0)?;
+        UNPAUSED_AT.save(deps.storage, &env.block.time.seconds())?;
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_paused"),
+        attr("paused", paused.to_string()),
+    ]))
+}
+
+/// Pulls ROIDS sent by the Maker to the factory or a pair by mistake back to the Maker.
+/// This is only possible when ROIDS is a CW20 token and the holder granted the Maker an allowance,
+/// in which case a [`Cw20ExecuteMsg::TransferFrom`] is issued.
+///
+/// * **from** address of the factory or a factory pair holding the ROIDS.
+///
+/// * **amount** amount of ROIDS to reclaim.
+///
+/// ## Executor
+/// Only the owner can execute this and only while collects are paused.
+fn reclaim_roids(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    from: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if !PAUSED.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::NotPaused {});
+    }
+
+    let from = deps.api.addr_validate(&from)?;
+    if from != cfg.factory_contract {
+        validate_factory_pair(&deps.querier, &cfg.factory_contract, &from)?;
+    }
+
+    let contract_addr = match &cfg.roids_token {
+        AssetInfo::Token { contract_addr } => contract_addr,
+        AssetInfo::NativeToken { .. } => return Err(ContractError::ReclaimNotSupported {}),
+    };
+
+    // Only ROIDS actually held by the factory or pair can be reclaimed
+    let balance = cfg.roids_token.query_pool(&deps.querier, &from)?;
+    if amount.is_zero() || amount > balance {
+        return Err(ContractError::InvalidReclaimAmount {
+            from: from.to_string(),
+            amount,
+            balance,
+        });
+    }
+
+    let msg = wasm_execute(
+        contract_addr,
+        &Cw20ExecuteMsg::TransferFrom {
+            owner: from.to_string(),
+            recipient: env.contract.address.to_string(),
+            amount,
+        },
+        vec![],
+    )?;
+
+    Ok(Response::new().add_message(msg).add_attributes([
+        attr("action", "reclaim_roids"),
+        attr("sender", info.sender),
+        attr("from", from),
+        attr("recipient", env.contract.address),
+        attr("roids_token", contract_addr),
+        attr("amount", amount),
+    ]))
+}
+
+/// Checks that none of the assets can be swapped to ROIDS. Assets with a route are collected
+/// instead of being seized.
+fn assert_seizable(deps: Deps, cfg: &Config, assets: &[Asset]) -> Result<(), ContractError> {
+    for asset in assets {
+        if asset.info == cfg.roids_token || resolve_route(deps, cfg, &asset.info).is_ok() {
+            return Err(ContractError::SeizeRoutable(asset.info.clone()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Proposes to send assets without a route to ROIDS out of the Maker. The proposal replaces any
+/// pending one and can be executed once the seize delay passed.
+///
+/// * **assets** assets to send.
+///
+/// * **recipient** address receiving the assets.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn propose_seize(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    assets: Vec<Asset>,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    assert_seizable(deps.as_ref(), &cfg, &assets)?;
+
+    let proposal = SeizeProposal {
+        assets,
+        recipient: deps.api.addr_validate(&recipient)?,
+        executable_at: env.block.time.seconds()
+            + cfg.seize_delay_secs.unwrap_or(DEFAULT_SEIZE_DELAY_SECS),
+    };
+    SEIZE_PROPOSAL.save(deps.storage, &proposal)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "propose_seize"),
+        attr("recipient", proposal.recipient),
+        attr("executable_at", proposal.executable_at.to_string()),
+    ]))
+}
+
+/// Sends the assets of the pending seize proposal to its recipient. The assets are checked again
+/// in case a route to ROIDS was added in the meantime.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn seize(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let proposal = SEIZE_PROPOSAL
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoSeizeProposal {})?;
+    if env.block.time.seconds() < proposal.executable_at {
+        return Err(ContractError::SeizeNotReady {
+            executable_at: proposal.executable_at,
+        });
+    }
+
+    assert_seizable(deps.as_ref(), &cfg, &proposal.assets)?;
+    SEIZE_PROPOSAL.remove(deps.storage);
+
+    let mut attributes = vec![
+        attr("action", "seize"),
+        attr("recipient", proposal.recipient.to_string()),
+    ];
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for asset in proposal.assets {
+        attributes.push(attr("seized", asset.to_string()));
+        messages.push(asset.into_msg(&proposal.recipient)?);
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attributes))
+}
+
+/// Removes the pending seize proposal.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn drop_seize_proposal(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    SEIZE_PROPOSAL.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "drop_seize_proposal"))
+}
+
+/// Proposes a change of the contracts fees are sent to. Proposing again replaces the pending
+/// change and restarts its delay.
+///
+/// * **config_change_delay_secs** new delay between proposing and applying config changes.
+///
+/// * **router_contract** new router contract, an empty string removes it.
+///
+/// * **second_receiver_params** new secondary receiver, a zero cut removes it.
+///
+/// * **dev_fund_config** new dev fund, a zero share removes it.
+///
+/// * **distribution_mode** new way ROIDS leave the Maker.
+///
+/// * **bridge_call** new payload of the Asteroid bridge call, an empty destination removes it.
+///
+/// * **external_dexes** fee tokens along with the external DEXes they are swapped on.
+///
+/// ## Executor
+/// Only the owner can execute this.
+#[allow(clippy::too_many_arguments)]
+fn propose_config_change(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    factory_contract: Option<String>,
+    roids_token: Option<AssetInfo>,
+    asteroid_contract: Option<String>,
+    config_change_delay_secs: Option<u64>,
+    router_contract: Option<String>,
+    second_receiver_params: Option<SecondReceiverParams>,
+    dev_fund_config: Option<DevFundConfig>,
+    distribution_mode: Option<DistributionMode>,
+    bridge_call: Option<BridgeCall>,
+    external_dexes: Option<Vec<(AssetInfo, ExternalDex)>>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if factory_contract.is_none()
+        && roids_token.is_none()
+        && asteroid_contract.is_none()
+        && config_change_delay_secs.is_none()
+        && router_contract.is_none()
+        && second_receiver_params.is_none()
+        && dev_fund_config.is_none()
+        && distribution_mode.is_none()
+        && bridge_call.is_none()
+        && external_dexes.is_none()
+    {
+        return Err(ContractError::EmptyConfigChange {});
+    }
+    if config_change_delay_secs == Some(0) {
+        return Err(ContractError::IncorrectConfigChangeDelay {});
+    }
+    if let Some(roids_token) = &roids_token {
+        roids_token.check(deps.api)?;
+    }
+    // Settings are validated again once applied, as the config may change in between
+    if let Some(router_contract) = router_contract.as_ref().filter(|addr| !addr.is_empty()) {
+        deps.api.addr_validate(router_contract)?;
+    }
+    if let Some(params) = &second_receiver_params {
+        if params.cut_bps > MAX_SECOND_RECEIVER_CUT_BPS {
+            return Err(ContractError::IncorrectSecondReceiverCut {
+                max: MAX_SECOND_RECEIVER_CUT_BPS,
+            });
+        }
+        if params.cut_bps > 0 {
+            deps.api.addr_validate(&params.address)?;
+        }
+    }
+    if let Some(dev_fund) = dev_fund_config
+        .as_ref()
+        .filter(|fund| !fund.share.is_zero())
+    {
+        validate_dev_fund(deps.as_ref(), &cfg, dev_fund)?;
+    }
+    if let Some(distribution_mode) = &distribution_mode {
+        validate_distribution_mode(&cfg, distribution_mode)?;
+    }
+    for (asset, dex) in external_dexes.iter().flatten() {
+        validate_external_dex(deps.as_ref(), &cfg, asset, dex)?;
+    }
+
+    let change = ConfigChange {
+        factory_contract: factory_contract
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?,
+        roids_token,
+        asteroid_contract: asteroid_contract
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?,
+        config_change_delay_secs,
+        router_contract,
+        second_receiver_params,
+        dev_fund_config,
+        distribution_mode,
+        bridge_call,
+        external_dexes,
+        executable_at: env.block.time.seconds()
+            + cfg
+                .config_change_delay_secs
+                .unwrap_or(DEFAULT_CONFIG_CHANGE_DELAY_SECS),
+    };
+    PENDING_CONFIG_CHANGE.save(deps.storage, &change)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "propose_config_change"),
+        attr("executable_at", change.executable_at.to_string()),
+    ]))
+}
+
+/// Applies the pending config change once its delay passed.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn apply_config_change(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let change = PENDING_CONFIG_CHANGE
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoConfigChange {})?;
+    if env.block.time.seconds() < change.executable_at {
+        return Err(ContractError::ConfigChangeNotReady {
+            executable_at: change.executable_at,
+        });
+    }
+    PENDING_CONFIG_CHANGE.remove(deps.storage);
+
+    let mut attributes = vec![attr("action", "apply_config_change")];
+
+    // Every route may change along with the factory or ROIDS
+    if change.factory_contract.is_some() || change.roids_token.is_some() {
+        ROUTES_RESET_HEIGHT.save(deps.storage, &env.block.height)?;
+    }
+
+    if let Some(factory_contract) = change.factory_contract {
+        attributes.push(attr("factory_contract", factory_contract.as_str()));
+        config.factory_contract = factory_contract;
+    }
+
+    if let Some(asteroid_contract) = change.asteroid_contract {
+        attributes.push(attr("asteroid_contract", asteroid_contract.as_str()));
+        config.asteroid_contract = asteroid_contract;
+    }
+
+    if let Some(roids_token) = change.roids_token {
+        attributes.push(attr("new_roids_token", roids_token.to_string()));
+        config.roids_token = roids_token;
+        if config.tokenfactory_burn {
+            validate_tokenfactory_burn(&env, &config.roids_token)?;
+        }
+        validate_distribution_mode(&config, &config.distribution_mode)?;
+    }
+
+    if let Some(delay_secs) = change.config_change_delay_secs {
+        attributes.push(attr("config_change_delay_secs", delay_secs.to_string()));
+        config.config_change_delay_secs = Some(delay_secs);
+    }
+
+    if let Some(router_contract) = change.router_contract {
+        config.router_contract = if router_contract.is_empty() {
+            // Routes of several hops are only swapped through the router
+            for item in ROUTES.range(deps.storage, None, None, Order::Ascending) {
+                if item?.1.len() > 1 {
+                    return Err(ContractError::RouterInUse {});
+                }
+            }
+            None
+        } else {
+            Some(deps.api.addr_validate(&router_contract)?)
+        };
+        attributes.push(attr("router_contract", router_contract));
+    }
+
+    if let Some(params) = change.second_receiver_params {
+        // A zero cut removes the second receiver
+        config.second_receiver_cfg = if params.cut_bps == 0 {
+            None
+        } else {
+            Some(SecondReceiverConfig {
+                address: deps.api.addr_validate(&params.address)?,
+                cut_bps: params.cut_bps,
+            })
+        };
+        attributes.push(attr("second_receiver", params.address));
+        attributes.push(attr("second_receiver_cut_bps", params.cut_bps.to_string()));
+    }
+
+    if let Some(dev_fund) = change.dev_fund_config {
+        attributes.push(attr("dev_fund", &dev_fund.address));
+        attributes.push(attr("dev_fund_share", dev_fund.share.to_string()));
+        // A zero share removes the dev fund
+        config.dev_fund_config = if dev_fund.share.is_zero() {
+            None
+        } else {
+            validate_dev_fund(deps.as_ref(), &config, &dev_fund)?;
+            Some(dev_fund)
+        };
+    }
+
+    if let Some(bridge_call) = change.bridge_call {
+        attributes.push(attr("bridge_destination", &bridge_call.destination));
+        // An empty destination removes the bridge call
+        config.bridge_call = Some(bridge_call).filter(|call| !call.destination.is_empty());
+    }
+
+    if let Some(distribution_mode) = change.distribution_mode {
+        attributes.push(attr("distribution_mode", distribution_mode.as_str()));
+        validate_distribution_mode(&config, &distribution_mode)?;
+        match &distribution_mode {
+            DistributionMode::Hold { threshold } => {
+                attributes.push(attr("hold_threshold", threshold));
+            }
+            DistributionMode::IbcTransfer {
+                channel, receiver, ..
+            } => {
+                attributes.push(attr("ibc_channel", channel));
+                attributes.push(attr("ibc_receiver", receiver));
+            }
+            DistributionMode::Burn | DistributionMode::BridgeToHub => {}
+        }
+        config.distribution_mode = distribution_mode;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    for (asset, dex) in change.external_dexes.unwrap_or_default() {
+        validate_external_dex(deps.as_ref(), &config, &asset, &dex)?;
+        attributes.push(attr("external_dex", format!("{asset}:{}", dex.adapter)));
+        EXTERNAL_DEXES.save(deps.storage, asset.to_string(), &dex)?;
+
+        resolve_route(deps.as_ref(), &config, &asset)?;
+    }
+
+    record_config_change(
+        deps.storage,
+        &env,
+        &info.sender,
+        "apply_config_change",
+        attributes[1..].to_vec(),
+    )?;
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Removes the pending config change.
+///
+/// ## Executor
+/// Only the owner or the guardian can execute this.
+fn drop_config_change(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner && cfg.guardian.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    PENDING_CONFIG_CHANGE.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "drop_config_change"))
+}
+
+/// Starts a declining price auction selling a fee token for ROIDS. Collects leave the
+/// auctioned fee token alone until the auction sells out or is cancelled.
+///
+/// * **asset** fee token and amount to sell. The Maker must hold the amount.
+///
+/// * **start_price** price in ROIDS per unit of the fee token when the auction starts.
+///
+/// * **end_price** price the auction declines to.
+///
+/// * **duration_secs** number of seconds the price takes to decline to the end price.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn start_auction(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset: Asset,
+    start_price: Decimal,
+    end_price: Decimal,
+    duration_secs: u64,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if asset.info == cfg.roids_token || asset.amount.is_zero() {
+        return Err(ContractError::InvalidAuction {});
+    }
+    validate_auction_prices(start_price, end_price, duration_secs)?;
+
+    if AUCTIONS.has(deps.storage, asset.info.to_string()) {
+        return Err(ContractError::AuctionExists(asset.info));
+    }
+
+    let balance = asset
+        .info
+        .query_pool(&deps.querier, &env.contract.address)?;
+    if balance < asset.amount {
+        return Err(ContractError::AuctionExceedsBalance(asset.info));
+    }
+
+    let start_ts = env.block.time.seconds();
+    let auction = Auction {
+        asset,
+        start_price,
+        end_price,
+        start_ts,
+        end_ts: start_ts + duration_secs,
+    };
+    AUCTIONS.save(deps.storage, auction.asset.info.to_string(), &auction)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "start_auction"),
+        attr("asset", auction.asset.info.to_string()),
+        attr("amount", auction.asset.amount),
+        attr("start_price", start_price.to_string()),
+        attr("end_price", end_price.to_string()),
+        attr("end_ts", auction.end_ts.to_string()),
+    ]))
+}
+
+/// Cancels the auction of a fee token.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn cancel_auction(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_info: AssetInfo,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if !AUCTIONS.has(deps.storage, asset_info.to_string()) {
+        return Err(ContractError::NoAuction(asset_info));
+    }
+    AUCTIONS.remove(deps.storage, asset_info.to_string());
+
+    Ok(Response::new().add_attributes([
+        attr("action", "cancel_auction"),
+        attr("asset", asset_info.to_string()),
+    ]))
+}
+
+/// Sells a fee token from its auction at the current price. The ROIDS paid stays in the Maker
+/// and is distributed along with the next collect, ROIDS paid for more than the unsold amount
+/// is refunded.
+///
+/// * **bidder** address receiving the fee token.
+///
+/// * **paid** amount of ROIDS sent by the bidder.
+fn bid_auction(
+    deps: DepsMut,
+    env: Env,
+    bidder: Addr,
+    asset_info: AssetInfo,
+    paid: Uint128,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let mut auction = AUCTIONS
+        .may_load(deps.storage, asset_info.to_string())?
+        .ok_or_else(|| ContractError::NoAuction(asset_info.clone()))?;
+
+    let price = auction_price(&auction, env.block.time.seconds());
+    let bought = paid
+        .multiply_ratio(Decimal::one().atomics(), price.atomics())
+        .min(auction.asset.amount);
+    if bought.is_zero() {
+        return Err(ContractError::BidTooLow(asset_info));
+    }
+    let cost = bought.mul_ceil(price);
+
+    auction.asset.amount -= bought;
+    if auction.asset.amount.is_zero() {
+        AUCTIONS.remove(deps.storage, asset_info.to_string());
+    } else {
+        AUCTIONS.save(deps.storage, asset_info.to_string(), &auction)?;
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![Asset {
+        info: asset_info.clone(),
+        amount: bought,
+    }
+    .into_msg(&bidder)?];
+    if paid > cost {
+        messages.push(
+            Asset {
+                info: cfg.roids_token,
+                amount: paid - cost,
+            }
+            .into_msg(&bidder)?,
+        );
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes([
+        attr("action", "bid_auction"),
+        attr("bidder", bidder.as_str()),
+        attr("asset", asset_info.to_string()),
+        attr("amount", bought),
+        attr("price", price.to_string()),
+        attr("roids_paid", cost),
+    ]))
+}
+
+/// Hands a role over to another address. Assigning a role back to the owner makes it follow
+/// ownership changes again.
+///
+/// * **role** role to hand over.
+///
+/// * **address** new holder of the role.
+///
+/// ## Executor
+/// Only the current holder of the role can execute this.
+fn assign_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    assert_role(deps.as_ref(), &cfg, &info.sender, &role)?;
+
+    let holder = deps.api.addr_validate(&address)?;
+    if holder == cfg.owner {
+        ROLES.remove(deps.storage, role.as_str());
+    } else {
+        ROLES.save(deps.storage, role.as_str(), &holder)?;
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "assign_role"),
+        attr("role", role.as_str()),
+        attr("holder", holder),
+    ]))
+}
+
+/// Caches the decimals of every asset in [`ROUTES`] along with ROIDS and the default bridge.
+/// Assets whose decimals can't be queried are skipped.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn refresh_decimals(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut assets = vec![cfg.roids_token.clone()];
+    assets.extend(cfg.default_bridge.clone());
+    for item in ROUTES.range(deps.storage, None, None, Order::Ascending) {
+        assets.extend(item?.1);
+    }
+
+    let mut uniq = HashSet::new();
+    let mut refreshed = 0u32;
+    let mut failed = 0u32;
+    for asset in assets.into_iter().filter(|a| uniq.insert(a.to_string())) {
+        match asset.decimals(&deps.querier, &cfg.factory_contract) {
+            Ok(decimals) => {
+                DECIMALS.save(deps.storage, asset.to_string(), &decimals)?;
+                refreshed += 1;
+            }
+            Err(_) => failed += 1,
+        }
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "refresh_decimals"),
+        attr("refreshed", refreshed.to_string()),
+        attr("failed", failed.to_string()),
+    ]))
+}
+
+/// Sets the assets with an elastic supply.
+///
+/// * **assets** rebasing assets. An empty list treats every asset as non-rebasing.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn update_rebasing_assets(
+    deps: DepsMut,
+    info: MessageInfo,
+    assets: Vec<AssetInfo>,
+) -> Result<Response, ContractError> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut uniq = HashSet::new();
+    for asset in &assets {
+        asset.check(deps.api)?;
+        if !uniq.insert(asset.to_string()) {
+            return Err(ContractError::DuplicatedAsset {});
+        }
+    }
+
+    let rebasing_assets = assets
+        .iter()
+        .map(|a| a.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    cfg.rebasing_assets = assets;
+    CONFIG.save(deps.storage, &cfg)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "update_rebasing_assets"),
+        attr("rebasing_assets", rebasing_assets),
+    ]))
+}
+
+/// Sets the whitelist of assets that can be used as intermediate bridge assets.
+///
+/// * **assets** approved bridge assets. `None` allows any asset to be used as a bridge.
+///
+/// ## Executor
+/// Only the bridge manager can execute this.
+fn update_approved_bridge_assets(
+    deps: DepsMut,
+    info: MessageInfo,
+    assets: Option<Vec<AssetInfo>>,
+) -> Result<Response, ContractError> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    assert_role(deps.as_ref(), &cfg, &info.sender, &Role::BridgeManager)?;
+
+    let mut attributes = vec![attr("action", "update_approved_bridge_assets")];
+
+    if let Some(assets) = &assets {
+        for asset in assets {
+            asset.check(deps.api)?;
+        }
+
+        // The current default bridge must stay usable
+        if let Some(default_bridge) = &cfg.default_bridge {
+            if !assets.contains(default_bridge) {
+                return Err(ContractError::BridgeAssetNotApproved(
+                    default_bridge.clone(),
+                ));
+            }
+        }
+
+        attributes.push(attr(
+            "approved_bridge_assets",
+            assets
+                .iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        ));
+    } else {
+        attributes.push(attr("approved_bridge_assets", "any"));
+    }
+
+    cfg.approved_bridge_assets = assets;
+    CONFIG.save(deps.storage, &cfg)?;
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Exposes all the queries available in the contract.
+///
+/// ## Queries
+/// * **QueryMsg::Config {}** Returns the Maker contract configuration using a [`ConfigResponse`] object.
+///
+/// * **QueryMsg::EffectiveConfig {}** Returns the configuration with all defaults resolved using an
+/// [`EffectiveConfigResponse`] object.
+///
+/// * **QueryMsg::Balances { assets, include_zero }** Returns the balances of certain fee tokens accrued by the Maker
+/// using a [`ConfigResponse`] object.
+///
+/// * **QueryMsg::AllBalances { start_after, limit }** Returns a page of the balances of every
+/// native coin held by the Maker and every CW20 token traded in the factory pairs using a
+/// [`BalancesResponse`] object.
+///
+/// * **QueryMsg::Bridges { start_after, limit }** Returns a page of the bridges used for swapping
+/// fee tokens using a [`BridgesResponse`] object.
+///
+/// * **QueryMsg::ExportBridges {}** Returns every route, bridges included, using a vector of
+/// [`BridgeInfo`] objects.
+///
+/// * **QueryMsg::ValidateRoutes { assets }** Returns the broken routes of the specified fee
+/// tokens, or of every fee token, using a vector of [`BrokenRoute`] objects.
+///
+/// * **QueryMsg::Routes {}** Returns the stored routes of fee tokens to ROIDS.
+///
+/// * **QueryMsg::MinReceived {}** Returns the minimum ROIDS fee tokens must realize when collected.
+///
+/// * **QueryMsg::Spreads {}** Returns the max spreads of specific fee tokens.
+///
+/// * **QueryMsg::PriceOracles {}** Returns the TWAP oracles of specific assets.
+///
+/// * **QueryMsg::ExternalDexes {}** Returns the external DEXes of specific fee tokens.
+///
+/// * **QueryMsg::Blacklist {}** Returns the assets which are never swapped.
+///
+/// * **QueryMsg::CooldownExempt {}** Returns the callers which aren't subject to the collect
+/// cooldown.
+///
+/// * **QueryMsg::DistributionHooks {}** Returns the contracts called after each distribution.
+///
+/// * **QueryMsg::DirectPairs { assets }** Returns the assets which have a direct pair with ROIDS
+/// using a [`DirectPairsResponse`] object.
+///
+/// * **QueryMsg::ResolveBurnDestination {}** Returns the address distributed ROIDS is sent to
+/// using a [`BurnDestinationResponse`] object.
+///
+/// * **QueryMsg::BridgeCall {}** Returns the call made to the Asteroid bridge contract using a
+/// [`BridgeCallResponse`] object.
+///
+/// * **QueryMsg::SimulateCollect { assets }** Returns the amount of ROIDS a collect of the specified
+/// assets would produce in total and per asset using a [`SimulateCollectResponse`] object.
+///
+/// * **QueryMsg::SimulateSwaps { assets }** Returns the route, the ROIDS output and the spread of
+/// the swap of each of the specified assets using a vector of [`SwapSimulation`] objects.
+///
+/// * **QueryMsg::SimulateWithConfig { overrides, assets }** Same as `SimulateCollect` but with
+/// the config overrides applied in memory.
+///
+/// * **QueryMsg::MaxCollectNow {}** Returns the amount of ROIDS a collect of all held routable
+/// assets would produce using a [`MaxCollectNowResponse`] object.
+///
+/// * **QueryMsg::CollectStatus {}** Returns the latest collect time, when the next collect is
+/// allowed and whether it is possible right now using a [`CollectStatusResponse`] object.
+///
+/// * **QueryMsg::KeeperCommitment {}** Returns the current keeper commitment using an optional
+/// [`KeeperCommitment`] object.
+///
+/// * **QueryMsg::SeizeProposal {}** Returns the pending seize proposal using an optional
+/// [`SeizeProposal`] object.
+///
+/// * **QueryMsg::PendingConfigChange {}** Returns the pending change of the critical settings
+/// using an optional [`ConfigChange`] object.
+///
+/// * **QueryMsg::Auction { asset_info }** Returns the auction of a fee token along with its
+/// current price using an optional [`AuctionResponse`] object.
+///
+/// * **QueryMsg::BridgeProposals {}** Returns the pending route proposals using a vector of
+/// [`BridgeProposal`] objects.
+///
+/// * **QueryMsg::FailedAssets {}** Returns the fee tokens whose swaps were quarantined.
+///
+/// * **QueryMsg::RecentCollectors { limit }** Returns the latest collects with their callers using
+/// a vector of [`CollectorRecord`] objects.
+///
+/// * **QueryMsg::ConfigHistory { start_after, limit }** Returns a page of the successful config
+/// and bridge updates using a vector of [`ConfigHistoryEntry`] objects.
+///
+/// * **QueryMsg::CanOnboard { asset }** Checks whether a fee token has a route to ROIDS or which
+/// bridges would give it one using a [`CanOnboardResponse`] object.
+///
+/// * **QueryMsg::SwapPreview { asset, amount }** Returns the expected and the worst case amount
+/// of ROIDS a swap returns along with its route using a [`SwapPreviewResponse`] object.
+///
+/// * **QueryMsg::Route { asset_info }** Returns the hops of a fee token to ROIDS along with the
+/// pairs used using a [`RouteResponse`] object.
+///
+/// * **QueryMsg::AssetStats { asset_info }** Returns the total amount of a fee token swapped by
+/// the Maker using an [`AssetStats`] object.
+///
+/// * **QueryMsg::Donations { donor }** Returns the total amounts donated by a donor.
+///
+/// * **QueryMsg::Permissions {}** Returns the owner and the holder of each role using a
+/// [`PermissionsResponse`] object.
+///
+/// * **QueryMsg::RoutesChangedSince { height }** Returns the assets whose route changed after
+/// the given block height using a [`RoutesChangedSinceResponse`] object.
+///
+/// * **QueryMsg::PendingBurn {}** Returns the amount of ROIDS whose burn was blocked by the token.
+///
+/// * **QueryMsg::WorstHop {}** Returns the swap with the highest slippage since the latest
+/// collect using an optional [`HopSlippage`] object.
+///
+/// * **QueryMsg::SupplyImpact {}** Returns the net amount of ROIDS removed from circulation by
+/// the Maker using a [`SupplyImpactResponse`] object.
+///
+/// * **QueryMsg::Statistics {}** Returns lifetime collect and distribution statistics using a
+/// [`StatisticsResponse`] object.
+///
+/// * **QueryMsg::PreUpgradeRewards {}** Returns the release of the ROIDS held from before the
+/// Maker upgrade using an optional [`PreUpgradeRewards`] object.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    match msg {
+        QueryMsg::Config {} => Ok(to_json_binary(&query_get_config(deps)?)?),
+        QueryMsg::EffectiveConfig {} => Ok(to_json_binary(&query_effective_config(deps)?)?),
+        QueryMsg::Balances {
+            assets,
+            include_zero,
+        } => Ok(to_json_binary(&query_get_balances(
+            deps,
+            env,
+            assets,
+            include_zero.unwrap_or_default(),
+        )?)?),
+        QueryMsg::AllBalances { start_after, limit } => Ok(to_json_binary(&query_all_balances(
+            deps,
+            env,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::Bridges { start_after, limit } => {
+            Ok(to_json_binary(&query_bridges(deps, start_after, limit)?)?)
+        }
+        QueryMsg::ExportBridges {} => Ok(to_json_binary(
+            &load_routes(deps)?
+                .into_iter()
+                .map(|(asset, route)| BridgeInfo { asset, route })
+                .collect::<Vec<_>>(),
+        )?),
+        QueryMsg::ValidateRoutes { assets } => {
+            Ok(to_json_binary(&query_validate_routes(deps, assets)?)?)
+        }
+        QueryMsg::Routes {} => Ok(to_json_binary(
+            &ROUTES
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?,
+        )?),
+        QueryMsg::MinReceived {} => Ok(to_json_binary(
+            &MIN_RECEIVED
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?,
+        )?),
+        QueryMsg::Spreads {} => Ok(to_json_binary(
+            &SPREADS
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?,
+        )?),
+        QueryMsg::PriceOracles {} => Ok(to_json_binary(
+            &PRICE_ORACLES
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?,
+        )?),
+        QueryMsg::ExternalDexes {} => Ok(to_json_binary(
+            &EXTERNAL_DEXES
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?,
+        )?),
+        QueryMsg::Blacklist {} => Ok(to_json_binary(
+            &BLACKLIST
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| item.map(|(_, asset)| asset))
+                .collect::<StdResult<Vec<_>>>()?,
+        )?),
+        QueryMsg::CooldownExempt {} => Ok(to_json_binary(
+            &COOLDOWN_EXEMPT
+                .keys(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?,
+        )?),
+        QueryMsg::DistributionHooks {} => Ok(to_json_binary(
+            &DISTRIBUTION_HOOKS
+                .keys(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?,
+        )?),
+        QueryMsg::DirectPairs { assets } => Ok(to_json_binary(&query_direct_pairs(deps, assets)?)?),
+        QueryMsg::ResolveBurnDestination {} => {
+            let (destination, source) = resolve_burn_destination(&CONFIG.load(deps.storage)?);
+            Ok(to_json_binary(&BurnDestinationResponse {
+                destination,
+                source,
+            })?)
+        }
+        QueryMsg::BridgeCall {} => {
+            let cfg = CONFIG.load(deps.storage)?;
+            Ok(to_json_binary(&BridgeCallResponse {
+                msg: bridge_payload(&cfg)
+                    .map(|msg| to_json_binary(&msg))
+                    .transpose()?,
+                contract: cfg.asteroid_contract,
+            })?)
+        }
+        QueryMsg::SimulateCollect { assets } => {
+            let cfg = CONFIG.load(deps.storage)?;
+            Ok(to_json_binary(&query_simulate_collect(
+                deps, env, &cfg, assets,
+            )?)?)
+        }
+        QueryMsg::SimulateSwaps { assets } => {
+            Ok(to_json_binary(&query_simulate_swaps(deps, env, assets)?)?)
+        }
+        QueryMsg::SimulateWithConfig { overrides, assets } => {
+            let cfg = apply_config_overrides(deps, CONFIG.load(deps.storage)?, overrides)?;
+            Ok(to_json_binary(&query_simulate_collect(
+                deps, env, &cfg, assets,
+            )?)?)
+        }
+        QueryMsg::RecentCollectors { limit } => {
+            Ok(to_json_binary(&query_recent_collectors(deps, limit)?)?)
+        }
+        QueryMsg::ConfigHistory { start_after, limit } => Ok(to_json_binary(
+            &query_config_history(deps, start_after, limit)?,
+        )?),
+        QueryMsg::CanOnboard { asset } => Ok(to_json_binary(&query_can_onboard(deps, asset)?)?),
+        QueryMsg::SwapPreview { asset, amount } => {
+            Ok(to_json_binary(&query_swap_preview(deps, asset, amount)?)?)
+        }
+        QueryMsg::Route { asset_info } => Ok(to_json_binary(&query_route(deps, asset_info)?)?),
+        QueryMsg::AssetStats { asset_info } => Ok(to_json_binary(
+            &ASSET_STATS
+                .may_load(deps.storage, asset_info.to_string())?
+                .unwrap_or_default(),
+        )?),
+        QueryMsg::Donations { donor } => {
+            let donor = deps.api.addr_validate(&donor)?;
+            Ok(to_json_binary(
+                &DONATIONS
+                    .prefix(&donor)
+                    .range(deps.storage, None, None, Order::Ascending)
+                    .map(|item| item.map(|(_, asset)| asset))
+                    .collect::<StdResult<Vec<_>>>()?,
+            )?)
+        }
+        QueryMsg::Permissions {} => Ok(to_json_binary(&query_permissions(deps)?)?),
+        QueryMsg::RoutesChangedSince { height } => {
+            Ok(to_json_binary(&query_routes_changed_since(deps, height)?)?)
+        }
+        QueryMsg::WorstHop {} => Ok(to_json_binary(&WORST_HOP.may_load(deps.storage)?)?),
+        QueryMsg::SupplyImpact {} => Ok(to_json_binary(&query_supply_impact(deps)?)?),
+        QueryMsg::Statistics {} => Ok(to_json_binary(&query_statistics(deps)?)?),
+        QueryMsg::PreUpgradeRewards {} => Ok(to_json_binary(
+            &PRE_UPGRADE_REWARDS.may_load(deps.storage)?,
+        )?),
+        QueryMsg::PendingBurn {} => Ok(to_json_binary(&PENDING_BURN.may_load(deps.storage)?)?),
+        QueryMsg::KeeperCommitment {} => {
+            Ok(to_json_binary(&KEEPER_COMMITMENT.may_load(deps.storage)?)?)
+        }
+        QueryMsg::SeizeProposal {} => Ok(to_json_binary(&SEIZE_PROPOSAL.may_load(deps.storage)?)?),
+        QueryMsg::PendingConfigChange {} => Ok(to_json_binary(
+            &PENDING_CONFIG_CHANGE.may_load(deps.storage)?,
+        )?),
+        QueryMsg::Auction { asset_info } => {
+            let response = AUCTIONS
+                .may_load(deps.storage, asset_info.to_string())?
+                .map(|auction| AuctionResponse {
+                    price: auction_price(&auction, env.block.time.seconds()),
+                    auction,
+                });
+            Ok(to_json_binary(&response)?)
+        }
+        QueryMsg::BridgeProposals {} => Ok(to_json_binary(
+            &BRIDGE_PROPOSALS
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| item.map(|(_, proposal)| proposal))
+                .collect::<StdResult<Vec<_>>>()?,
+        )?),
+        QueryMsg::FailedAssets {} => Ok(to_json_binary(
+            &FAILED_ASSETS
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| item.map(|(_, failed)| failed))
+                .collect::<StdResult<Vec<_>>>()?,
+        )?),
+        QueryMsg::MaxCollectNow {} => Ok(to_json_binary(&query_max_collect_now(deps, env)?)?),
+        QueryMsg::CollectStatus {} => Ok(to_json_binary(&query_collect_status(deps, env)?)?),
+    }
+}
+
+/// Returns information about the Maker configuration using a [`ConfigResponse`] object.
+fn query_get_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        owner: config.owner,
+        factory_contract: config.factory_contract,
+        asteroid_contract: config.asteroid_contract,
+        roids_token: config.roids_token,
+        max_spread: config.max_spread,
+        default_bridge: config.default_bridge,
+        swap_deadline_secs: config.swap_deadline_secs,
+        approved_bridge_assets: config.approved_bridge_assets,
+        auto_pause_threshold: config.auto_pause_threshold,
+        burn_every_n_collects: config.burn_every_n_collects,
+        dust_threshold: config.dust_threshold,
+        keeper_commit_window_secs: config.keeper_commit_window_secs,
+        min_receive_buffer_bps: config.min_receive_buffer_bps,
+        lenient_burn: config.lenient_burn,
+        max_bridges: config.max_bridges,
+        rebasing_assets: config.rebasing_assets,
+        strict_collect: config.strict_collect,
+        post_unpause_grace_secs: config.post_unpause_grace_secs,
+        burn_retry_limit: config.burn_retry_limit,
+        learn_routes: config.learn_routes,
+        keeper_fee_bps: config.keeper_fee_bps,
+        router_contract: config.router_contract,
+        guardian: config.guardian,
+        seize_delay_secs: config.seize_delay_secs,
+        min_collect_amount: config.min_collect_amount,
+        quarantine_failed_swaps: config.quarantine_failed_swaps,
+        second_receiver_cfg: config.second_receiver_cfg,
+        dev_fund_config: config.dev_fund_config,
+        max_collect_value_per_window: config.max_collect_value_per_window,
+        distribution_mode: config.distribution_mode,
+        bridge_call: config.bridge_call,
+        bridges_execution_max_depth: config.bridges_execution_max_depth,
+        tokenfactory_burn: config.tokenfactory_burn,
+        max_assets_per_tx: config.max_assets_per_tx,
+        belief_price_source: config.belief_price_source,
+        on_spread_exceeded: config.on_spread_exceeded,
+        reserve_amount: config.reserve_amount,
+        auction_fallback: config.auction_fallback,
+        config_change_delay_secs: config.config_change_delay_secs,
+        operator: config.operator,
+        paused: PAUSED.may_load(deps.storage)?.unwrap_or_default(),
+    })
+}
+
+/// Returns the configuration with every unset option resolved to the value used at runtime.
+fn query_effective_config(deps: Deps) -> StdResult<EffectiveConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(EffectiveConfigResponse {
+        owner: config.owner.clone(),
+        factory_contract: config.factory_contract.clone(),
+        asteroid_contract: config.asteroid_contract.clone(),
+        burn_destination: resolve_burn_destination(&config).0,
+        roids_token: config.roids_token.clone(),
+        default_bridge: config.default_bridge.clone(),
+        max_spread: config.max_spread,
+        concentrated_max_spread: get_max_spread(
+            &config,
+            &PairType::Custom(CONCENTRATED_PAIR_TYPE.to_string()),
+        )?,
+        collect_cooldown: config.collect_cooldown.unwrap_or_default(),
+        swap_deadline_secs: config.swap_deadline_secs.unwrap_or_default(),
+        approved_bridge_assets: config.approved_bridge_assets,
+        auto_pause_threshold: config.auto_pause_threshold.unwrap_or_default(),
+        // Without a burn frequency ROIDS is burned on every collect
+        burn_every_n_collects: config.burn_every_n_collects.unwrap_or(1),
+        dust_threshold: config.dust_threshold.unwrap_or_default(),
+        keeper_commit_window_secs: config.keeper_commit_window_secs.unwrap_or_default(),
+        min_receive_buffer_bps: config.min_receive_buffer_bps.unwrap_or_default(),
+        lenient_burn: config.lenient_burn,
+        burn_retry_limit: config.burn_retry_limit.unwrap_or_default(),
+        max_bridges: config.max_bridges.unwrap_or_default(),
+        bridges_max_depth: BRIDGES_MAX_DEPTH,
+        bridges_execution_max_depth: execution_max_depth(&config),
+        rebasing_assets: config.rebasing_assets,
+        rebase_drift_buffer_bps: REBASE_DRIFT_BUFFER_BPS,
+        strict_collect: config.strict_collect,
+        post_unpause_grace_secs: config.post_unpause_grace_secs.unwrap_or_default(),
+        learn_routes: config.learn_routes,
+        keeper_fee_bps: config.keeper_fee_bps.unwrap_or_default(),
+        router_contract: config.router_contract.clone(),
+        guardian: config.guardian.clone(),
+        seize_delay_secs: config.seize_delay_secs.unwrap_or(DEFAULT_SEIZE_DELAY_SECS),
+        min_collect_amount: config.min_collect_amount.unwrap_or_default(),
+        quarantine_failed_swaps: config.quarantine_failed_swaps,
+        second_receiver_cfg: config.second_receiver_cfg,
+        dev_fund_config: config.dev_fund_config,
+        max_collect_value_per_window: config.max_collect_value_per_window.unwrap_or_default(),
+        distribution_mode: config.distribution_mode,
+        bridge_call: config.bridge_call,
+        tokenfactory_burn: config.tokenfactory_burn,
+        max_assets_per_tx: config.max_assets_per_tx.unwrap_or_default(),
+        belief_price_source: config.belief_price_source,
+        on_spread_exceeded: config.on_spread_exceeded,
+        reserve_amount: config.reserve_amount.unwrap_or_default(),
+        auction_fallback: config.auction_fallback,
+        config_change_delay_secs: config
+            .config_change_delay_secs
+            .unwrap_or(DEFAULT_CONFIG_CHANGE_DELAY_SECS),
+        operator: config.operator,
+        paused: PAUSED.may_load(deps.storage)?.unwrap_or_default(),
+    })
+}
+
+/// Returns Maker's fee token balances for specific tokens using a [`BalancesResponse`] object.
+///
+/// * **assets** array with assets for which we query the Maker's balances.
+///
+/// * **include_zero** whether assets with a zero balance are returned too.
+fn query_get_balances(
+    deps: Deps,
+    env: Env,
+    assets: Vec<AssetInfo>,
+    include_zero: bool,
+) -> StdResult<BalancesResponse> {
+    let mut resp = BalancesResponse { balances: vec![] };
+
+    for a in assets {
+        // Get balance
+        let balance = a.query_pool(&deps.querier, &env.contract.address)?;
+        if include_zero || !balance.is_zero() {
+            resp.balances.push(Asset {
+                info: a,
+                amount: balance,
+            })
+        }
+    }
+
+    Ok(resp)
+}
+
+/// Returns the non-zero balances of every native coin held by the Maker and of every CW20 token
+/// traded in the factory pairs, ordered by asset.
+///
+/// * **start_after** asset to start reading after.
+///
+/// * **limit** number of balances to return. Capped at [`MAX_BALANCES_LIMIT`].
+fn query_all_balances(
+    deps: Deps,
+    env: Env,
+    start_after: Option<AssetInfo>,
+    limit: Option<u32>,
+) -> StdResult<BalancesResponse> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let limit = limit
+        .unwrap_or(DEFAULT_BALANCES_LIMIT)
+        .min(MAX_BALANCES_LIMIT) as usize;
+
+    let mut assets: BTreeMap<String, AssetInfo> = deps
+        .querier
+        .query_all_balances(&env.contract.address)?
+        .into_iter()
+        .map(|coin| (coin.denom.clone(), native_asset_info(coin.denom)))
+        .collect();
+    assets.insert(cfg.roids_token.to_string(), cfg.roids_token.clone());
+
+    // CW20 balances can't be enumerated, so the tokens traded in the factory pairs are checked
+    let mut start_pair = None;
+    loop {
+        let pairs = query_pairs_info(&deps.querier, &cfg.factory_contract, start_pair, None)?.pairs;
+        start_pair = match pairs.last() {
+            Some(pair) => Some(pair.asset_infos.clone()),
+            None => break,
+        };
+        for asset in pairs.into_iter().flat_map(|pair| pair.asset_infos) {
+            if !asset.is_native_token() {
+                assets.insert(asset.to_string(), asset);
+            }
+        }
+    }
+
+    let start = start_after.map(|asset| asset.to_string());
+    let mut balances = vec![];
+    for (key, asset) in assets {
+        if balances.len() == limit {
+            break;
+        }
+        if start.as_ref().map_or(false, |start| &key <= start) {
+            continue;
+        }
+
+        let amount = asset.query_pool(&deps.querier, &env.contract.address)?;
+        if !amount.is_zero() {
+            balances.push(Asset {
+                info: asset,
+                amount,
+            });
+        }
+    }
+
+    Ok(BalancesResponse { balances })
+}
+
+/// Returns bridge tokens used for swapping fee tokens to ASTRO.
+///
+/// * **start_after** fee token to start reading after.
+///
+/// * **limit** number of bridges to read. Capped at [`MAX_BRIDGES_LIMIT`].
+fn query_bridges(
+    deps: Deps,
+    start_after: Option<AssetInfo>,
+    limit: Option<u32>,
+) -> StdResult<BridgesResponse> {
+    let limit = limit
+        .unwrap_or(DEFAULT_BRIDGES_LIMIT)
+        .min(MAX_BRIDGES_LIMIT) as usize;
+
+    let bridges = load_bridges(deps, start_after.map(|asset| asset.to_string()), limit)?
+        .into_iter()
+        .map(|(asset, bridge)| BridgeInfo {
+            asset,
+            route: vec![bridge],
+        })
+        .collect();
+
+    Ok(BridgesResponse { bridges })
+}
+
+/// Returns the assets which can be swapped to ROIDS without a bridge along with their ROIDS pairs.
+///
+/// * **assets** array with assets to check.
+fn query_direct_pairs(deps: Deps, assets: Vec<AssetInfo>) -> StdResult<DirectPairsResponse> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    let pairs = assets
+        .into_iter()
+        .filter(|asset| asset != &cfg.roids_token)
+        .filter_map(|asset| {
+            get_pool(
+                &deps.querier,
+                &cfg.factory_contract,
+                &asset,
+                &cfg.roids_token,
+            )
+            .ok()
+            .map(|pair| (asset, pair.contract_addr))
+        })
+        .collect();
+
+    Ok(DirectPairsResponse { pairs })
+}
+
+/// Simulates a collect of the specified assets and returns the amount of ROIDS it would produce
+/// in total and per fee token. Each asset is simulated along its whole route including bridge hops, failing if a hop
+/// exceeds the max spread.
+///
+/// * **cfg** the config to simulate the collect with.
+///
+/// * **assets** array with fee tokens to simulate swapping to ROIDS.
+fn query_simulate_collect(
+    deps: Deps,
+    env: Env,
+    cfg: &Config,
+    assets: Vec<AssetWithLimit>,
+) -> Result<SimulateCollectResponse, ContractError> {
+    let mut response = SimulateCollectResponse {
+        roids_amount: Uint128::zero(),
+        assets: vec![],
+    };
+    for a in assets.into_iter().filter(|a| a.info != cfg.roids_token) {
+        let amount = get_collect_amount(deps, cfg, &env.contract.address, &a)?;
+        if amount.is_zero() || is_dust(cfg, amount) {
+            continue;
+        }
+        let roids_amount = simulate_swap_to_roids(deps, cfg, &a.info, amount, 0)?;
+        response.roids_amount += roids_amount;
+        response.assets.push(AssetSimulation {
+            asset: Asset {
+                info: a.info,
+                amount,
+            },
+            roids_amount,
+        });
+    }
+
+    Ok(response)
+}
+
+/// Re-validates stored routes against the factory: each bridge pool and the pool of the bridge
+/// asset with ROIDS must still be listed and hold liquidity, as must every hop of a full route.
+/// Bridges further down the chain are reported under their own fee token.
+///
+/// * **assets** fee tokens whose routes are checked, every route is checked if not specified.
+fn query_validate_routes(
+    deps: Deps,
+    assets: Option<Vec<AssetInfo>>,
+) -> Result<Vec<BrokenRoute>, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    let routes = match assets {
+        Some(assets) => assets
+            .into_iter()
+            .filter_map(|asset| {
+                ROUTES
+                    .may_load(deps.storage, asset.to_string())
+                    .transpose()
+                    .map(|route| route.map(|route| (asset, route)))
+            })
+            .collect::<StdResult<Vec<_>>>()?,
+        None => load_routes(deps)?,
+    };
+
+    let mut memo = BridgeMemo::new();
+    let mut check = |asset: &AssetInfo, route: &[AssetInfo]| -> Result<(), ContractError> {
+        // Each hop of a full route is checked on its own
+        let bridge = match route {
+            [bridge] => bridge,
+            _ => {
+                let mut from = asset;
+                for hop in route {
+                    let pool = get_pool(&deps.querier, &cfg.factory_contract, from, hop)?;
+                    check_pool_liquidity(&deps.querier, &pool)?;
+                    from = hop;
+                }
+                return Ok(());
+            }
+        };
+
+        let pool = validate_bridge_memo(
+            deps,
+            &cfg.factory_contract,
+            asset,
+            bridge,
+            &cfg.roids_token,
+            BRIDGES_INITIAL_DEPTH,
+            &mut memo,
+        )?;
+        check_pool_liquidity(&deps.querier, &pool)?;
 
-    let mut amount = cfg
-        .roids_token
-        .query_pool(&deps.querier, &env.contract.address)?;
-    if amount.is_zero() {
-        return Ok((result, attributes));
-    }
-    
-    // if !amount.is_zero() {
-    //         result.push(SubMsg::new(build_send_msg(
-    //             &Asset {
-    //                 info: cfg.astro_token.clone(),
-    //                 amount,
-    //             },
-    //             governance_contract.to_string(),
-    //             None,
-    //         )?))
-    //     }
-
-    attributes = vec![
-        attr("action", "distribute_roids"),
-    ];
-    
-    Ok((result, attributes))
+        if !ROUTES.has(deps.storage, bridge.to_string()) && bridge != &cfg.roids_token {
+            let pool = get_pool(
+                &deps.querier,
+                &cfg.factory_contract,
+                bridge,
+                &cfg.roids_token,
+            )?;
+            check_pool_liquidity(&deps.querier, &pool)?;
+        }
+
+        Ok(())
+    };
+
+    Ok(routes
+        .into_iter()
+        .filter_map(|(asset, route)| {
+            check(&asset, &route).err().map(|err| BrokenRoute {
+                asset: asset.to_string(),
+                bridge: route[0].clone(),
+                error: err.to_string(),
+            })
+        })
+        .collect())
 }
 
-/// Updates general contract parameters.
-///
-/// * **factory_contract** address of the factory contract.
-///
-/// * **staking_contract** address of the xASTRO staking contract.
-///
-/// * **governance_contract** address of the vxASTRO fee distributor contract.
-///
-/// * **governance_percent** percentage of ASTRO that goes to the vxASTRO fee distributor.
-///
-/// * **default_bridge_opt** default bridge asset used for intermediate swaps to ASTRO.
+/// Simulates the swap of each fee token to ROIDS along its route in a single query. Fee tokens
+/// which can't be swapped are reported with their error instead of failing the query.
 ///
-/// * **max_spread** max spread used when swapping fee tokens to ASTRO.
+/// * **assets** array with fee tokens to simulate swapping to ROIDS.
+fn query_simulate_swaps(
+    deps: Deps,
+    env: Env,
+    assets: Vec<AssetWithLimit>,
+) -> Result<Vec<SwapSimulation>, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    assets
+        .into_iter()
+        .map(|a| {
+            let amount = get_collect_amount(deps, &cfg, &env.contract.address, &a)?;
+            let simulation = match simulate_route(deps, &cfg, &a.info, amount) {
+                Ok((route, roids_amount, spread)) => SwapSimulation {
+                    asset: Asset {
+                        info: a.info,
+                        amount,
+                    },
+                    route,
+                    roids_amount,
+                    spread,
+                    error: None,
+                },
+                Err(err) => SwapSimulation {
+                    asset: Asset {
+                        info: a.info,
+                        amount,
+                    },
+                    route: vec![],
+                    roids_amount: Uint128::zero(),
+                    spread: Decimal::zero(),
+                    error: Some(err.to_string()),
+                },
+            };
+
+            Ok(simulation)
+        })
+        .collect()
+}
+
+/// Simulates swapping a fee token to ROIDS hop by hop. Returns the route, the ROIDS returned and
+/// the share of the output lost to the spread over the whole route.
 ///
-/// * **second_receiver_params** describes the second receiver of fees
+/// * **asset** fee token to swap.
 ///
-/// ## Executor
-/// Only the owner can execute this.
-#[allow(clippy::too_many_arguments)]
-fn update_config(
-    deps: DepsMut,
-    info: MessageInfo,
-    factory_contract: Option<String>,
-    default_bridge_opt: Option<AssetInfo>,
-    max_spread: Option<Decimal>,
-    collect_cooldown: Option<u64>,
-    roids_token: Option<AssetInfo>,
-    asteroid_contract: Option<String>,
-) -> Result<Response, ContractError> {
-    let mut attributes = vec![attr("action", "set_config")];
+/// * **amount** amount of the fee token to swap.
+fn simulate_route(
+    deps: Deps,
+    cfg: &Config,
+    asset: &AssetInfo,
+    amount: Uint128,
+) -> Result<(Vec<AssetInfo>, Uint128, Decimal), ContractError> {
+    let mut route = vec![];
+    let mut current = asset.clone();
+    let mut amount = amount;
+    let mut kept = Decimal::one();
+    while current != cfg.roids_token {
+        // The first hop is made by collect itself, the following ones by SwapBridgeAssets
+        if route.len() as u64 > execution_max_depth(cfg) {
+            return Err(ContractError::MaxBridgeDepth(route.len() as u64));
+        }
 
-    let mut config = CONFIG.load(deps.storage)?;
+        let (next, pool) = get_next_hop(deps, cfg, &current)?;
+        if !amount.is_zero() {
+            let simulation = simulate(
+                &deps.querier,
+                &pool.contract_addr,
+                &Asset {
+                    info: current.clone(),
+                    amount: net_of_transfer_tax(deps.storage, &current, amount)?,
+                },
+            )?;
+            kept = kept
+                * Decimal::checked_from_ratio(
+                    simulation.return_amount,
+                    simulation.return_amount + simulation.spread_amount,
+                )
+                .unwrap_or(Decimal::one());
+            amount = simulation.return_amount;
+        }
 
-    // Permission check
-    if info.sender != config.owner {
-        return Err(ContractError::Unauthorized {});
+        route.push(next.clone());
+        current = next;
     }
 
-    if let Some(factory_contract) = factory_contract {
-        config.factory_contract = deps.api.addr_validate(&factory_contract)?;
-        attributes.push(attr("factory_contract", &factory_contract));
-    };
+    Ok((route, amount, Decimal::one() - kept))
+}
 
-    if let Some(asteroid_contract) = asteroid_contract {
-        config.asteroid_contract = deps.api.addr_validate(&asteroid_contract)?;
-        attributes.push(attr("asteroid_contract", &asteroid_contract));
-    };
+/// Returns when the next collect is allowed. The cooldown and the post unpause grace period both
+/// push the next collect back, a paused Maker can't collect at all.
+fn query_collect_status(deps: Deps, env: Env) -> StdResult<CollectStatusResponse> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let last_collect_ts = LAST_COLLECT_TS.may_load(deps.storage)?.unwrap_or_default();
 
-    if let Some(default_bridge) = &default_bridge_opt {
-        default_bridge.check(deps.api)?;
-        attributes.push(attr("default_bridge", default_bridge.to_string()));
-        config.default_bridge = default_bridge_opt;
+    let mut next_allowed_ts = next_collect_ts(deps, &cfg)?.unwrap_or(last_collect_ts);
+    if let Some(grace_secs) = cfg.post_unpause_grace_secs {
+        if let Some(unpaused_at) = UNPAUSED_AT.may_load(deps.storage)? {
+            next_allowed_ts = next_allowed_ts.max(unpaused_at + grace_secs);
+        }
     }
 
-    if let Some(max_spread) = max_spread {
-        if max_spread.is_zero() || max_spread > Decimal::from_str(MAX_ALLOWED_SLIPPAGE)? {
-            return Err(ContractError::IncorrectMaxSpread {});
-        };
+    let paused = PAUSED.may_load(deps.storage)?.unwrap_or_default();
 
-        config.max_spread = max_spread;
-        attributes.push(attr("max_spread", max_spread.to_string()));
-    };
+    Ok(CollectStatusResponse {
+        last_collect_ts,
+        next_allowed_ts,
+        can_collect: !paused && env.block.time.seconds() >= next_allowed_ts,
+    })
+}
 
-    if let Some(collect_cooldown) = collect_cooldown {
-        validate_cooldown(Some(collect_cooldown))?;
-        config.collect_cooldown = Some(collect_cooldown);
-        attributes.push(attr("collect_cooldown", collect_cooldown.to_string()));
-    }
+/// Returns lifetime collect and distribution statistics.
+fn query_statistics(deps: Deps) -> StdResult<StatisticsResponse> {
+    let stats = STATS.may_load(deps.storage)?.unwrap_or_default();
 
-    if let Some(roids_token) = roids_token {
-        roids_token.check(deps.api)?;
-        attributes.push(attr("new_roids_token", roids_token.to_string()));
-        config.roids_token = roids_token;
-    }
+    Ok(StatisticsResponse {
+        total_roids: stats.total_roids,
+        collects: COLLECT_ID.may_load(deps.storage)?.unwrap_or_default(),
+        distributions: stats.distributions,
+        last_distribution_amount: stats.last_distribution_amount,
+        last_distribution_ts: stats.last_distribution_ts,
+    })
+}
 
-    CONFIG.save(deps.storage, &config)?;
+/// Returns the net amount of ROIDS removed from circulation by the Maker.
+fn query_supply_impact(deps: Deps) -> StdResult<SupplyImpactResponse> {
+    let total_burned = TOTAL_BURNED.may_load(deps.storage)?.unwrap_or_default();
+    let returned_to_circulation = RETURNED_TO_CIRCULATION
+        .may_load(deps.storage)?
+        .unwrap_or_default();
 
-    Ok(Response::new().add_attributes(attributes))
+    Ok(SupplyImpactResponse {
+        total_burned,
+        returned_to_circulation,
+        net_removed: total_burned.saturating_sub(returned_to_circulation),
+    })
 }
 
-/// Adds or removes bridge tokens used to swap fee tokens to ASTRO.
-///
-/// * **add** array of bridge tokens added to swap fee tokens with.
-///
-/// * **remove** array of bridge tokens removed from being used to swap certain fee tokens.
+/// Checks whether a fee token can be swapped to ROIDS. Without a route, the assets already
+/// known to reach ROIDS (the default bridge, approved bridge assets and stored bridges) are
+/// checked for a pair with the fee token.
 ///
-/// ## Executor
-/// Only the owner can execute this.
-fn update_bridges(
-    deps: DepsMut,
-    info: MessageInfo,
-    add: Option<Vec<(AssetInfo, AssetInfo)>>,
-    remove: Option<Vec<AssetInfo>>,
-) -> Result<Response, ContractError> {
+/// * **asset** fee token to check.
+fn query_can_onboard(deps: Deps, asset: AssetInfo) -> Result<CanOnboardResponse, ContractError> {
     let cfg = CONFIG.load(deps.storage)?;
 
-    // Permission check
-    if info.sender != cfg.owner {
-        return Err(ContractError::Unauthorized {});
+    if let Ok(route) = resolve_route(deps, &cfg, &asset) {
+        return Ok(CanOnboardResponse {
+            route: Some(route),
+            candidate_bridges: vec![],
+        });
     }
 
-    // Remove old bridges
-    if let Some(remove_bridges) = remove {
-        for asset in remove_bridges {
-            BRIDGES.remove(deps.storage, asset.to_string());
-        }
+    let mut candidates = vec![cfg.roids_token.clone()];
+    candidates.extend(cfg.default_bridge.clone());
+    candidates.extend(cfg.approved_bridge_assets.clone().unwrap_or_default());
+    for item in ROUTES.range(deps.storage, None, None, Order::Ascending) {
+        candidates.extend(item?.1);
     }
 
-    // Add new bridges
-    let astro = cfg.roids_token.clone();
-    if let Some(add_bridges) = add {
-        for (asset, bridge) in add_bridges {
-            if asset.equal(&bridge) {
-                return Err(ContractError::InvalidBridge(asset, bridge));
-            }
+    let mut uniq = HashSet::new();
+    let candidate_bridges = candidates
+        .into_iter()
+        .filter(|bridge| uniq.insert(bridge.to_string()))
+        .filter(|bridge| {
+            !bridge.equal(&asset)
+                && check_bridge_approved(&cfg, bridge).is_ok()
+                && validate_bridge(
+                    deps,
+                    &cfg.factory_contract,
+                    &asset,
+                    bridge,
+                    &cfg.roids_token,
+                    BRIDGES_INITIAL_DEPTH,
+                )
+                .is_ok()
+        })
+        .collect();
 
-            // Check that bridge tokens can be swapped to ASTRO
-            validate_bridge(
-                deps.as_ref(),
-                &cfg.factory_contract,
-                &asset,
-                &bridge,
-                &astro,
-                BRIDGES_INITIAL_DEPTH,
-            )?;
+    Ok(CanOnboardResponse {
+        route: None,
+        candidate_bridges,
+    })
+}
+
+/// Returns the hops of a fee token to ROIDS. Each hop is resolved like collects do: stored routes
+/// first, then bridges, the default bridge and finally a direct pair with ROIDS.
+///
+/// * **asset_info** fee token to route.
+fn query_route(deps: Deps, asset_info: AssetInfo) -> Result<RouteResponse, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
 
-            BRIDGES.save(deps.storage, asset.to_string(), &bridge)?;
+    let mut hops = vec![];
+    let mut current = asset_info;
+    while current != cfg.roids_token {
+        // The first hop is made by collect itself, the following ones by SwapBridgeAssets
+        if hops.len() as u64 > execution_max_depth(&cfg) {
+            return Err(ContractError::MaxBridgeDepth(hops.len() as u64));
         }
+
+        let (next, pool) = get_next_hop(deps, &cfg, &current)?;
+        hops.push(RouteHop {
+            offer_asset: current,
+            ask_asset: next.clone(),
+            pair: pool.contract_addr,
+            pair_type: pool.pair_type,
+        });
+        current = next;
     }
 
-    Ok(Response::default().add_attribute("action", "update_bridges"))
+    Ok(RouteResponse { hops })
 }
 
-/// Exposes all the queries available in the contract.
+/// Simulates swapping a fee token to ROIDS along its route. The conservative output assumes each
+/// swap returns its simulated output minus the max spread of its pair.
 ///
-/// ## Queries
-/// * **QueryMsg::Config {}** Returns the Maker contract configuration using a [`ConfigResponse`] object.
-///
-/// * **QueryMsg::Balances { assets }** Returns the balances of certain fee tokens accrued by the Maker
-/// using a [`ConfigResponse`] object.
+/// * **asset** fee token to swap.
 ///
-/// * **QueryMsg::Bridges {}** Returns the bridges used for swapping fee tokens
-/// using a vector of [`(String, String)`] denoting Asset -> Bridge connections.
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::Config {} => to_json_binary(&query_get_config(deps)?),
-        QueryMsg::Balances { assets } => to_json_binary(&query_get_balances(deps, env, assets)?),
-        QueryMsg::Bridges {} => to_json_binary(&query_bridges(deps)?),
+/// * **amount** amount of the fee token to swap.
+fn query_swap_preview(
+    deps: Deps,
+    asset: AssetInfo,
+    amount: Uint128,
+) -> Result<SwapPreviewResponse, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    let mut route = vec![];
+    let mut current = asset;
+    let mut expected_roids = amount;
+    let mut conservative_roids = amount;
+    while current != cfg.roids_token {
+        // The first hop is made by collect itself, the following ones by SwapBridgeAssets
+        if route.len() as u64 > execution_max_depth(&cfg) {
+            return Err(ContractError::MaxBridgeDepth(route.len() as u64));
+        }
+
+        let (next, pool) = get_next_hop(deps, &cfg, &current)?;
+        let simulate_hop = |amount: Uint128| -> StdResult<Uint128> {
+            if amount.is_zero() {
+                return Ok(amount);
+            }
+            let simulation = simulate(
+                &deps.querier,
+                &pool.contract_addr,
+                &Asset {
+                    info: current.clone(),
+                    amount,
+                },
+            )?;
+            Ok(simulation.return_amount)
+        };
+
+        expected_roids = simulate_hop(expected_roids)?;
+        conservative_roids = simulate_hop(conservative_roids)?
+            * (Decimal::one() - get_asset_max_spread(deps.storage, &cfg, &current, &pool)?);
+
+        route.push(next.clone());
+        current = next;
     }
+
+    Ok(SwapPreviewResponse {
+        route,
+        expected_roids,
+        conservative_roids,
+    })
 }
 
-/// Returns information about the Maker configuration using a [`ConfigResponse`] object.
-fn query_get_config(deps: Deps) -> StdResult<ConfigResponse> {
-    let config = CONFIG.load(deps.storage)?;
-    Ok(ConfigResponse {
-        owner: config.owner,
-        factory_contract: config.factory_contract,
-        asteroid_contract: config.asteroid_contract,
-        roids_token: config.roids_token,
-        max_spread: config.max_spread,
-        default_bridge: config.default_bridge,
+/// Returns the owner and the holder of each role.
+fn query_permissions(deps: Deps) -> StdResult<PermissionsResponse> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let roles = Role::all()
+        .into_iter()
+        .map(|role| Ok((role.clone(), role_holder(deps, &cfg, &role)?)))
+        .collect::<StdResult<_>>()?;
+
+    Ok(PermissionsResponse {
+        owner: cfg.owner,
+        roles,
     })
 }
 
-/// Returns Maker's fee token balances for specific tokens using a [`BalancesResponse`] object.
+/// Returns the assets whose route changed after a block height. Besides the assets whose bridge
+/// was added or removed, the assets bridged through them are returned as well.
 ///
-/// * **assets** array with assets for which we query the Maker's balances.
-fn query_get_balances(deps: Deps, env: Env, assets: Vec<AssetInfo>) -> StdResult<BalancesResponse> {
-    let mut resp = BalancesResponse { balances: vec![] };
+/// * **height** block height after which changes are returned.
+fn query_routes_changed_since(deps: Deps, height: u64) -> StdResult<RoutesChangedSinceResponse> {
+    let all_routes_changed = ROUTES_RESET_HEIGHT
+        .may_load(deps.storage)?
+        .map(|reset_height| reset_height > height)
+        .unwrap_or_default();
 
-    for a in assets {
-        // Get balance
-        let balance = a.query_pool(&deps.querier, &env.contract.address)?;
-        if !balance.is_zero() {
-            resp.balances.push(Asset {
-                info: a,
-                amount: balance,
+    let mut changed = BRIDGE_CHANGES
+        .prefix_range(
+            deps.storage,
+            Some(PrefixBound::exclusive(height)),
+            None,
+            Order::Ascending,
+        )
+        .map(|item| item.map(|((_, asset), _)| asset))
+        .collect::<StdResult<BTreeSet<_>>>()?;
+
+    let routes = ROUTES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            item.map(|(asset, route)| {
+                let route: Vec<_> = route.iter().map(|hop| hop.to_string()).collect();
+                (asset, route)
             })
+        })
+        .collect::<StdResult<HashMap<_, _>>>()?;
+
+    // An asset is affected by a change of any asset on its route or on the routes it continues on
+    let mut dependents = vec![];
+    for asset in routes.keys() {
+        let mut current = asset;
+        for _ in 0..=BRIDGES_MAX_DEPTH {
+            match routes.get(current) {
+                Some(route) if route.iter().any(|hop| changed.contains(hop)) => {
+                    dependents.push(asset.clone());
+                    break;
+                }
+                Some(route) => match route.last() {
+                    Some(last) => current = last,
+                    None => break,
+                },
+                None => break,
+            }
         }
     }
+    changed.extend(dependents);
 
-    Ok(resp)
+    Ok(RoutesChangedSinceResponse {
+        assets: changed.into_iter().collect(),
+        all_routes_changed,
+    })
 }
 
-/// Returns bridge tokens used for swapping fee tokens to ASTRO.
-fn query_bridges(deps: Deps) -> StdResult<Vec<(String, String)>> {
-    BRIDGES
-        .range(deps.storage, None, None, Order::Ascending)
-        .map(|bridge| {
-            let (bridge, asset) = bridge?;
-            Ok((bridge, asset.to_string()))
-        })
+/// Returns the latest collects starting from the most recent one.
+///
+/// * **limit** max number of collects to return.
+fn query_recent_collectors(
+    deps: Deps,
+    limit: Option<u32>,
+) -> Result<Vec<CollectorRecord>, ContractError> {
+    if RECENT_COLLECTORS.state().may_load(deps.storage)?.is_none() {
+        return Ok(vec![]);
+    }
+
+    let buffer = BufferManager::new(deps.storage, RECENT_COLLECTORS)?;
+    let limit = limit
+        .unwrap_or(DEFAULT_RECENT_COLLECTORS_LIMIT)
+        .min(buffer.capacity());
+
+    let mut records = vec![];
+    for i in 1..=limit {
+        let index = (buffer.capacity() + buffer.head() - i) % buffer.capacity();
+        match buffer.read_single(deps.storage, index)? {
+            Some(record) => records.push(record),
+            None => break,
+        }
+    }
+
+    Ok(records)
+}
+
+/// Returns the successful config and bridge updates starting from the oldest one.
+///
+/// * **start_after** ID of the entry to start reading after.
+///
+/// * **limit** number of entries to read. Capped at [`MAX_CONFIG_HISTORY_LIMIT`].
+fn query_config_history(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<ConfigHistoryEntry>> {
+    let limit = limit
+        .unwrap_or(DEFAULT_CONFIG_HISTORY_LIMIT)
+        .min(MAX_CONFIG_HISTORY_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    CONFIG_HISTORY
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, entry)| entry))
         .collect()
 }
+
+/// Returns the config with the overrides applied. Overrides are validated like in `UpdateConfig`.
+fn apply_config_overrides(
+    deps: Deps,
+    mut cfg: Config,
+    overrides: ConfigOverrides,
+) -> Result<Config, ContractError> {
+    if let Some(default_bridge) = overrides.default_bridge {
+        default_bridge.check(deps.api)?;
+        check_bridge_approved(&cfg, &default_bridge)?;
+        cfg.default_bridge = Some(default_bridge);
+    }
+
+    if let Some(max_spread) = overrides.max_spread {
+        if max_spread.is_zero() || max_spread > Decimal::from_str(MAX_ALLOWED_SLIPPAGE)? {
+            return Err(ContractError::IncorrectMaxSpread {});
+        };
+        cfg.max_spread = max_spread;
+    }
+
+    Ok(cfg)
+}
+
+/// Simulates a collect of every held asset that can currently be swapped to ROIDS within
+/// the max spread. Native assets are taken from the bank balances, CW20 assets from the stored
+/// bridges. At most [`MAX_COLLECT_NOW_ASSETS`] assets are considered. Like a collect, at most
+/// `max_assets_per_tx` assets are swapped and the ROIDS value is capped by the value budget left
+/// in the current window.
+fn query_max_collect_now(deps: Deps, env: Env) -> StdResult<MaxCollectNowResponse> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let max_assets = cfg
+        .max_assets_per_tx
+        .map_or(MAX_COLLECT_NOW_ASSETS, |max| max as usize);
+    let mut value_budget = match cfg.max_collect_value_per_window {
+        Some(max_value) => {
+            Some(max_value.saturating_sub(current_value_window(deps, &env, &cfg)?.spent))
+        }
+        None => None,
+    };
+
+    let mut candidates: Vec<AssetInfo> = deps
+        .querier
+        .query_all_balances(&env.contract.address)?
+        .into_iter()
+        .map(|coin| AssetInfo::NativeToken { denom: coin.denom })
+        .collect();
+    for item in ROUTES.range(deps.storage, None, None, Order::Ascending) {
+        let (asset, route) = item?;
+        candidates.extend(route);
+        if let Ok(contract_addr) = deps.api.addr_validate(&asset) {
+            candidates.push(AssetInfo::Token { contract_addr });
+        }
+    }
+
+    let mut uniq = HashSet::new();
+    let mut response = MaxCollectNowResponse {
+        roids_amount: Uint128::zero(),
+        assets: vec![],
+    };
+    for info in candidates
+        .into_iter()
+        .filter(|a| a.ne(&cfg.roids_token) && uniq.insert(a.to_string()))
+        .take(MAX_COLLECT_NOW_ASSETS)
+    {
+        if response.assets.len() >= max_assets || value_budget == Some(Uint128::zero()) {
+            break;
+        }
+        // Blacklisted and auctioned assets are skipped by collects as well
+        if BLACKLIST.has(deps.storage, info.to_string())
+            || AUCTIONS.has(deps.storage, info.to_string())
+        {
+            continue;
+        }
+
+        // Assets which aren't tokens held by the Maker, are dust or can't be routed are skipped
+        let asset = AssetWithLimit {
+            info: info.clone(),
+            limit: None,
+            limit_semantics: None,
+        };
+        let mut amount = match get_collect_amount(deps, &cfg, &env.contract.address, &asset) {
+            Ok(amount) if !amount.is_zero() && !is_dust(&cfg, amount) => amount,
+            _ => continue,
+        };
+        let mut roids_amount = match simulate_swap_to_roids(deps, &cfg, &info, amount, 0) {
+            Ok(roids_amount) => roids_amount,
+            Err(_) => continue,
+        };
+
+        // The part of the balance exceeding the value budget is deferred to later collects
+        if let Some(budget) = value_budget.as_mut() {
+            if roids_amount > *budget {
+                amount = amount.multiply_ratio(*budget, roids_amount);
+                roids_amount = *budget;
+            }
+            *budget -= roids_amount;
+        }
+
+        response.roids_amount += roids_amount;
+        response.assets.push(Asset { info, amount });
+    }
+
+    Ok(response)
+}
+
+/// Manages the contract migration. The Astroport Maker is converted into the Asteroid Maker,
+/// see [`migrate_from_astroport`].
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let contract_version = get_contract_version(deps.storage)?;
+
+    let mut attributes = vec![];
+    match contract_version.contract.as_ref() {
+        "astroport-maker" => {
+            attributes = migrate_from_astroport(deps.storage, deps.api, &env, msg)?;
+        }
+        CONTRACT_NAME => {}
+        _ => return Err(ContractError::MigrationError {}),
+    }
+    let migrated_bridges = migrate_bridges(deps.storage)?;
+    attributes.push(attr("migrated_bridges", migrated_bridges.to_string()));
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("previous_contract_name", &contract_version.contract)
+        .add_attribute("previous_contract_version", &contract_version.version)
+        .add_attribute("new_contract_name", CONTRACT_NAME)
+        .add_attribute("new_contract_version", CONTRACT_VERSION)
+        .add_attributes(attributes))
+}
@@ -1,5 +1,6 @@
 use astroport::asset::AssetInfo;
-use cosmwasm_std::{DivideByZeroError, OverflowError, StdError};
+use astroport_circular_buffer::error::BufferError;
+use cosmwasm_std::{Addr, Decimal, DivideByZeroError, OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 /// This enum describes maker contract errors
@@ -20,9 +21,27 @@ pub enum ContractError {
     #[error("Invalid bridge destination. {0} cannot be swapped to ASTRO")]
     InvalidBridgeDestination(String),
 
+    #[error("Pool {0} has no liquidity")]
+    NoLiquidity(String),
+
+    #[error("Route of {0} must end with ROIDS and can't visit an asset twice")]
+    InvalidRoute(AssetInfo),
+
+    #[error("Route of {0} has several hops and can only be swapped through the router")]
+    RouterRequired(AssetInfo),
+
+    #[error("Routes of several hops are set, the router can't be removed")]
+    RouterInUse {},
+
     #[error("Max bridge length of {0} was reached")]
     MaxBridgeDepth(u64),
 
+    #[error("Bridges form a cycle: {0}")]
+    BridgeCycle(String),
+
+    #[error("Incorrect bridges execution depth. Must be within [{min}, {max}]")]
+    IncorrectBridgesExecutionDepth { min: u64, max: u64 },
+
     #[error("Cannot swap {0}. No swap destinations")]
     CannotSwap(AssetInfo),
 
@@ -41,6 +60,9 @@ pub enum ContractError {
     #[error("Rewards collecting is already enabled")]
     RewardsAlreadyEnabled {},
 
+    #[error("Number of blocks should be > 0")]
+    IncorrectPreUpgradeBlocks {},
+
     #[error("An error occurred during migration")]
     MigrationError {},
 
@@ -49,6 +71,213 @@ pub enum ContractError {
 
     #[error("Incorrect cooldown. Min: {min}, Max: {max}")]
     IncorrectCooldown { min: u64, max: u64 },
+
+    #[error("Incorrect swap deadline. Must be greater than zero")]
+    IncorrectSwapDeadline {},
+
+    #[error("Collect deadline {deadline} exceeded")]
+    CollectDeadlineExceeded { deadline: u64 },
+
+    #[error("Collect deadline {deadline} is too far ahead. Max: {max}")]
+    CollectDeadlineTooFar { deadline: u64, max: u64 },
+
+    #[error("Collect must carry a deadline")]
+    MissingCollectDeadline {},
+
+    #[error("No collect to continue")]
+    NoPendingCollect {},
+
+    #[error("Nothing to donate")]
+    EmptyDonation {},
+
+    #[error("Collect realized {actual} ROIDS, less than the minimum of {min}")]
+    MinRoidsOutNotMet { min: Uint128, actual: Uint128 },
+
+    #[error("Bridge asset {0} is not approved")]
+    BridgeAssetNotApproved(AssetInfo),
+
+    #[error("Collects are paused")]
+    Paused {},
+
+    #[error("Burn frequency must be greater than zero")]
+    IncorrectBurnFrequency {},
+
+    #[error("{0} is not a pair registered in the factory")]
+    NotFactoryPair(String),
+
+    #[error("Collects must be paused to perform this action")]
+    NotPaused {},
+
+    #[error("ROIDS can only be reclaimed when it is a CW20 token")]
+    ReclaimNotSupported {},
+
+    #[error("Can't reclaim {amount} ROIDS from {from} holding {balance}. Must be non zero and at most the balance")]
+    InvalidReclaimAmount {
+        from: String,
+        amount: Uint128,
+        balance: Uint128,
+    },
+
+    #[error("Dust consolidation is disabled")]
+    DustConsolidationDisabled {},
+
+    #[error("Balance {balance} of {asset} exceeds the dust threshold {threshold}")]
+    NotDust {
+        asset: AssetInfo,
+        balance: Uint128,
+        threshold: Uint128,
+    },
+
+    #[error("Swapping {0} exceeds the max spread")]
+    MaxSpreadExceeded(AssetInfo),
+
+    #[error("Keeper commitments are disabled")]
+    KeeperCommitDisabled {},
+
+    #[error("Collect is reserved for {keeper} until {expires_at}")]
+    CollectReserved { keeper: Addr, expires_at: u64 },
+
+    #[error("The previous commitment of this keeper lapsed unused")]
+    CommitmentLapsed {},
+
+    #[error("Min receive buffer must not exceed {max} bps")]
+    IncorrectMinReceiveBuffer { max: u16 },
+
+    #[error("Keeper fee must not exceed {max} bps")]
+    IncorrectKeeperFee { max: u16 },
+
+    #[error("Second receiver cut must not exceed {max} bps")]
+    IncorrectSecondReceiverCut { max: u16 },
+
+    #[error("Dev fund share must not exceed 1")]
+    IncorrectDevFundShare {},
+
+    #[error("IBC transfers require native ROIDS, a channel, a receiver, a non zero timeout and an empty or JSON object memo")]
+    InvalidIbcTransfer {},
+
+    #[error(
+        "TokenFactory burns require ROIDS to be a TokenFactory denom administered by the Maker"
+    )]
+    InvalidTokenFactoryBurn {},
+
+    #[error("ROIDS burn was blocked by the token: {reason}")]
+    BurnBlocked { reason: String },
+
+    #[error("{0}")]
+    CircularBuffer(#[from] BufferError),
+
+    #[error("Route of {0} doesn't match the expected one")]
+    RouteChanged(AssetInfo),
+
+    #[error("ROIDS was flushed recently. Next flush is possible at {next_flush_ts}")]
+    FlushCooldown { next_flush_ts: u64 },
+
+    #[error("Max spread {max_spread} combined with collect cooldown {collect_cooldown} would make collects fail constantly")]
+    RiskyParams {
+        max_spread: Decimal,
+        collect_cooldown: u64,
+    },
+
+    #[error("The number of bridges can't exceed {max}")]
+    MaxBridgesExceeded { max: u32 },
+
+    #[error("Max bridges {max} is lower than the current number of bridges {count}")]
+    MaxBridgesTooLow { max: u32, count: u32 },
+
+    #[error("The Maker holds no {0} to swap")]
+    NothingToSwap(AssetInfo),
+
+    #[error("Collects resume at {resume_ts} after unpausing")]
+    PostUnpauseGrace { resume_ts: u64 },
+
+    #[error("Invalid min received for {0}")]
+    InvalidMinReceived(AssetInfo),
+
+    #[error(
+        "Collect of {asset} realized {received} ROIDS, less than the minimum of {min_received}"
+    )]
+    MinReceivedNotMet {
+        asset: AssetInfo,
+        min_received: Uint128,
+        received: Uint128,
+    },
+
+    #[error("Incorrect seize delay. Must be greater than zero")]
+    IncorrectSeizeDelay {},
+
+    #[error("Invalid price oracle for {0}")]
+    InvalidPriceOracle(AssetInfo),
+
+    #[error("Invalid external DEX for {0}")]
+    InvalidExternalDex(AssetInfo),
+
+    #[error(
+        "Pool price of {asset} deviates {deviation_bps} bps from the oracle, more than the max of {max_deviation_bps} bps"
+    )]
+    OracleDeviation {
+        asset: AssetInfo,
+        deviation_bps: u16,
+        max_deviation_bps: u16,
+    },
+
+    #[error("{0} can be swapped to ROIDS and must be collected instead")]
+    SeizeRoutable(AssetInfo),
+
+    #[error("No seize proposal found")]
+    NoSeizeProposal {},
+
+    #[error("Seize can be executed at {executable_at}")]
+    SeizeNotReady { executable_at: u64 },
+
+    #[error("A route of {0} is already proposed")]
+    BridgeProposalExists(AssetInfo),
+
+    #[error("No route proposal found for {0}")]
+    NoBridgeProposal(AssetInfo),
+
+    #[error("Incorrect config change delay. Must be greater than zero")]
+    IncorrectConfigChangeDelay {},
+
+    #[error("The config change doesn't change anything")]
+    EmptyConfigChange {},
+
+    #[error("No config change found")]
+    NoConfigChange {},
+
+    #[error("Config change can be applied at {executable_at}")]
+    ConfigChangeNotReady { executable_at: u64 },
+
+    #[error("Invalid auction. Prices must be non zero and decline over a non zero duration")]
+    InvalidAuction {},
+
+    #[error("{0} is already auctioned")]
+    AuctionExists(AssetInfo),
+
+    #[error("No auction found for {0}")]
+    NoAuction(AssetInfo),
+
+    #[error("The Maker doesn't hold enough {0} to auction")]
+    AuctionExceedsBalance(AssetInfo),
+
+    #[error("Auctions must be paid in ROIDS only")]
+    InvalidAuctionPayment {},
+
+    #[error("The payment doesn't buy any {0} at the current price")]
+    BidTooLow(AssetInfo),
+
+    #[error("The number of distribution hooks can't exceed {max}")]
+    MaxDistributionHooksExceeded { max: u32 },
+
+    #[error("No failed assets to retry")]
+    NoFailedAssets {},
+
+    #[error("Failed to parse or process reply message")]
+    FailedToParseReply {},
+
+    #[error(
+        "Migrating from the Astroport Maker requires the asteroid contract and the ROIDS token"
+    )]
+    MissingMigrationParams {},
 }
 
 impl From<OverflowError> for ContractError {
@@ -3,8 +3,12 @@ extern crate cosmwasm_std;
 
 pub mod contract;
 pub mod error;
+pub mod migration;
+pub mod reply;
 pub mod state;
 pub mod utils;
 
+#[cfg(test)]
+mod mock_querier;
 #[cfg(test)]
 mod testing;
@@ -0,0 +1,146 @@
+use astroport::asset::AssetInfo;
+use astroport::maker::{Config, MigrateMsg, SecondReceiverConfig};
+use cosmwasm_std::{attr, Addr, Api, Attribute, Decimal, Env, Order, StdResult, Storage, Uint64};
+use cw_storage_plus::{Item, Map};
+use serde::{Deserialize, Serialize};
+
+use crate::error::ContractError;
+use crate::state::{CONFIG, LAST_COLLECT_TS, PAUSED, ROUTES};
+
+/// This structure partially captures the second receiver config of the Astroport Maker.
+#[derive(Serialize, Deserialize)]
+struct SecondReceiverConfigV1 {
+    pub second_fee_receiver: Addr,
+    /// The share of each distribution sent to the second receiver, in percent
+    pub second_receiver_cut: Uint64,
+}
+
+/// This structure partially captures the config of the Astroport Maker.
+/// We don't use cw_serde macro intentionally to allow unknown fields in the config.
+/// Thus migration is compatible with any Astroport Maker 1.x version.
+#[derive(Serialize, Deserialize)]
+struct ConfigV1 {
+    pub owner: Addr,
+    pub factory_contract: Addr,
+    pub staking_contract: Option<Addr>,
+    pub default_bridge: Option<AssetInfo>,
+    pub governance_contract: Option<Addr>,
+    pub governance_percent: Uint64,
+    pub max_spread: Decimal,
+    #[serde(default)]
+    pub second_receiver_cfg: Option<SecondReceiverConfigV1>,
+    #[serde(default)]
+    pub collect_cooldown: Option<u64>,
+}
+
+const CONFIG_V1: Item<ConfigV1> = Item::new("config");
+/// Bridges stored by the Astroport Maker and earlier versions, a single asset per fee token.
+const BRIDGES_V1: Map<String, AssetInfo> = Map::new("bridges");
+
+/// Converts the config of the Astroport Maker into the Asteroid Maker config. Bridges are moved
+/// into routes by [`migrate_bridges`]. Staking and governance contracts have no
+/// counterpart since ROIDS is sent to the Asteroid bridge instead.
+///
+/// The Maker is left paused so the owner can review the converted config before the first
+/// distribution. Returns the attributes describing the dropped settings.
+pub fn migrate_from_astroport(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    msg: MigrateMsg,
+) -> Result<Vec<Attribute>, ContractError> {
+    let (asteroid_contract, roids_token) = match (msg.asteroid_contract, msg.roids_token) {
+        (Some(asteroid_contract), Some(roids_token)) => (asteroid_contract, roids_token),
+        _ => return Err(ContractError::MissingMigrationParams {}),
+    };
+    roids_token.check(api)?;
+
+    let old_config = CONFIG_V1.load(storage)?;
+
+    let second_receiver_cfg = old_config
+        .second_receiver_cfg
+        .map(|cfg| SecondReceiverConfig {
+            address: cfg.second_fee_receiver,
+            cut_bps: (cfg.second_receiver_cut.u64() * 100) as u16,
+        });
+
+    let config = Config {
+        owner: old_config.owner,
+        factory_contract: old_config.factory_contract,
+        asteroid_contract: api.addr_validate(&asteroid_contract)?,
+        default_bridge: old_config.default_bridge,
+        roids_token,
+        max_spread: old_config.max_spread,
+        collect_cooldown: old_config.collect_cooldown,
+        swap_deadline_secs: None,
+        approved_bridge_assets: None,
+        auto_pause_threshold: None,
+        burn_every_n_collects: None,
+        dust_threshold: None,
+        keeper_commit_window_secs: None,
+        min_receive_buffer_bps: None,
+        lenient_burn: false,
+        max_bridges: None,
+        rebasing_assets: vec![],
+        strict_collect: false,
+        post_unpause_grace_secs: None,
+        burn_retry_limit: None,
+        learn_routes: false,
+        keeper_fee_bps: None,
+        router_contract: None,
+        guardian: None,
+        seize_delay_secs: None,
+        min_collect_amount: None,
+        quarantine_failed_swaps: false,
+        second_receiver_cfg,
+        dev_fund_config: None,
+        max_collect_value_per_window: None,
+        distribution_mode: Default::default(),
+        bridge_call: None,
+        bridges_execution_max_depth: None,
+        tokenfactory_burn: false,
+        max_assets_per_tx: None,
+        belief_price_source: Default::default(),
+        on_spread_exceeded: Default::default(),
+        reserve_amount: None,
+        auction_fallback: None,
+        config_change_delay_secs: None,
+        operator: None,
+    };
+    CONFIG.save(storage, &config)?;
+
+    // The cooldown is counted from the latest collect
+    if LAST_COLLECT_TS.may_load(storage)?.is_none() {
+        LAST_COLLECT_TS.save(storage, &env.block.time.seconds())?;
+    }
+
+    // Nothing is distributed until the owner unpauses the Maker
+    PAUSED.save(storage, &true)?;
+
+    let addr_or_none = |addr: Option<Addr>| addr.map_or_else(|| "none".to_string(), String::from);
+    Ok(vec![
+        attr(
+            "dropped_staking_contract",
+            addr_or_none(old_config.staking_contract),
+        ),
+        attr(
+            "dropped_governance_contract",
+            addr_or_none(old_config.governance_contract),
+        ),
+        attr("dropped_governance_percent", old_config.governance_percent),
+    ])
+}
+
+/// Moves the bridges stored by earlier versions into routes made of the bridge asset. Returns
+/// the number of bridges moved.
+pub fn migrate_bridges(storage: &mut dyn Storage) -> StdResult<usize> {
+    let bridges = BRIDGES_V1
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (asset, bridge) in &bridges {
+        ROUTES.save(storage, asset.clone(), &vec![bridge.clone()])?;
+        BRIDGES_V1.remove(storage, asset.clone());
+    }
+
+    Ok(bridges.len())
+}
@@ -0,0 +1,295 @@
+use std::collections::{HashMap, HashSet};
+
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Coin, Decimal, Empty, OwnedDeps, Querier, QuerierResult,
+    QueryRequest, SystemError, SystemResult, Uint128, Uint256, WasmQuery,
+};
+use cw20::{BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
+
+use astroport::asset::{Asset, AssetInfo, PairInfo};
+use astroport::factory::{PairType, PairsResponse, QueryMsg as FactoryQueryMsg};
+use astroport::oracle::QueryMsg as OracleQueryMsg;
+use astroport::pair::{PoolResponse, QueryMsg as PairQueryMsg, SimulationResponse};
+
+/// The factory address used by the Maker in unit tests
+pub const FACTORY: &str = "factory";
+
+/// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies.
+/// This uses the Astroport CustomQuerier.
+pub fn mock_dependencies(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let custom_querier: WasmMockQuerier =
+        WasmMockQuerier::new(MockQuerier::new(&[(MOCK_CONTRACT_ADDR, contract_balance)]));
+
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: custom_querier,
+        custom_query_type: Default::default(),
+    }
+}
+
+pub struct WasmMockQuerier {
+    base: MockQuerier<Empty>,
+    /// Pairs registered in the factory, keyed by their sorted asset list
+    pairs: HashMap<String, PairInfo>,
+    /// Swap rates returned by pair simulations, keyed by pair address
+    rates: HashMap<String, Decimal>,
+    /// Spreads returned by pair simulations as a share of the offer amount, keyed by pair address
+    spreads: HashMap<String, Decimal>,
+    /// CW20 balances, keyed by token address and then by holder address
+    token_balances: HashMap<String, HashMap<String, Uint128>>,
+    /// CW20 decimals, keyed by token address
+    token_decimals: HashMap<String, u8>,
+    /// TWAP oracles pricing tokens in an ask asset, keyed by oracle address
+    oracles: HashMap<String, (AssetInfo, Decimal)>,
+    /// Pairs without liquidity, keyed by pair address
+    drained: HashSet<String>,
+}
+
+fn pair_key(asset_infos: &[AssetInfo]) -> String {
+    let mut keys: Vec<_> = asset_infos.iter().map(|a| a.to_string()).collect();
+    keys.sort();
+    keys.join("-")
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        // MockQuerier doesn't support Custom, so we ignore it completely
+        let request: QueryRequest<Empty> = match from_json(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.handle_query(&request)
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+        match &request {
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                if contract_addr == FACTORY {
+                    self.handle_factory_query(from_json(msg).unwrap())
+                } else if let Some((ask_asset, price)) = self.oracles.get(contract_addr) {
+                    match from_json(msg).unwrap() {
+                        OracleQueryMsg::Consult { amount, .. } => SystemResult::Ok(
+                            to_json_binary(&vec![(
+                                ask_asset.clone(),
+                                Uint256::from(amount * *price),
+                            )])
+                            .into(),
+                        ),
+                    }
+                } else if let Some(balances) = self.token_balances.get(contract_addr) {
+                    match from_json(msg).unwrap() {
+                        Cw20QueryMsg::Balance { address } => {
+                            let balance = balances.get(&address).cloned().unwrap_or_default();
+                            SystemResult::Ok(to_json_binary(&BalanceResponse { balance }).into())
+                        }
+                        Cw20QueryMsg::TokenInfo {} => SystemResult::Ok(
+                            to_json_binary(&TokenInfoResponse {
+                                name: contract_addr.to_string(),
+                                symbol: "TOKEN".to_string(),
+                                decimals: self
+                                    .token_decimals
+                                    .get(contract_addr)
+                                    .cloned()
+                                    .unwrap_or(6),
+                                total_supply: balances.values().sum(),
+                            })
+                            .into(),
+                        ),
+                        _ => panic!("DO NOT ENTER HERE"),
+                    }
+                } else if let Ok(msg) = from_json(msg) {
+                    self.handle_pair_query(contract_addr, msg)
+                } else {
+                    SystemResult::Err(SystemError::NoSuchContract {
+                        addr: contract_addr.to_string(),
+                    })
+                }
+            }
+            _ => self.base.handle_query(request),
+        }
+    }
+
+    fn handle_factory_query(&self, msg: FactoryQueryMsg) -> QuerierResult {
+        match msg {
+            FactoryQueryMsg::Pair { asset_infos } => {
+                match self.pairs.get(&pair_key(&asset_infos)) {
+                    Some(pair_info) => SystemResult::Ok(to_json_binary(pair_info).into()),
+                    None => SystemResult::Err(SystemError::InvalidRequest {
+                        error: "Pair not found".to_string(),
+                        request: Default::default(),
+                    }),
+                }
+            }
+            FactoryQueryMsg::Pairs { start_after, limit } => {
+                let start_after = start_after.map(|asset_infos| pair_key(&asset_infos));
+                let mut pairs: Vec<_> = self
+                    .pairs
+                    .iter()
+                    .filter(|(key, _)| start_after.as_ref().map_or(true, |start| *key > start))
+                    .collect();
+                pairs.sort_by(|a, b| a.0.cmp(b.0));
+                let pairs = pairs
+                    .into_iter()
+                    .take(limit.unwrap_or(10) as usize)
+                    .map(|(_, pair_info)| pair_info.clone())
+                    .collect();
+                SystemResult::Ok(to_json_binary(&PairsResponse { pairs }).into())
+            }
+            // Native decimals are resolved through the coin registry, which isn't mocked
+            FactoryQueryMsg::Config {} => SystemResult::Err(SystemError::InvalidRequest {
+                error: "Coin registry not mocked".to_string(),
+                request: Default::default(),
+            }),
+            _ => panic!("DO NOT ENTER HERE"),
+        }
+    }
+
+    fn handle_pair_query(&self, contract_addr: &str, msg: PairQueryMsg) -> QuerierResult {
+        match msg {
+            PairQueryMsg::Simulation { offer_asset, .. } => {
+                let rate = match self.rates.get(contract_addr) {
+                    Some(rate) => *rate,
+                    None => {
+                        return SystemResult::Err(SystemError::NoSuchContract {
+                            addr: contract_addr.to_string(),
+                        })
+                    }
+                };
+
+                let spread_amount = offer_asset.amount
+                    * self.spreads.get(contract_addr).cloned().unwrap_or_default();
+
+                SystemResult::Ok(
+                    to_json_binary(&SimulationResponse {
+                        return_amount: (offer_asset.amount - spread_amount) * rate,
+                        spread_amount,
+                        commission_amount: Uint128::zero(),
+                    })
+                    .into(),
+                )
+            }
+            PairQueryMsg::Pool {} => {
+                let amount = if self.drained.contains(contract_addr) {
+                    Uint128::zero()
+                } else {
+                    Uint128::new(1_000_000)
+                };
+                let assets = self
+                    .pairs
+                    .values()
+                    .find(|pair| pair.contract_addr == contract_addr)
+                    .map(|pair| {
+                        pair.asset_infos
+                            .iter()
+                            .map(|info| Asset {
+                                info: info.clone(),
+                                amount,
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                SystemResult::Ok(
+                    to_json_binary(&PoolResponse {
+                        assets,
+                        total_share: amount,
+                    })
+                    .into(),
+                )
+            }
+            _ => panic!("DO NOT ENTER HERE"),
+        }
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn new(base: MockQuerier<Empty>) -> Self {
+        WasmMockQuerier {
+            base,
+            pairs: HashMap::new(),
+            rates: HashMap::new(),
+            spreads: HashMap::new(),
+            token_balances: HashMap::new(),
+            token_decimals: HashMap::new(),
+            oracles: HashMap::new(),
+            drained: HashSet::new(),
+        }
+    }
+
+    /// Registers an xyk pair in the mocked factory which swaps 1:1
+    pub fn with_pair(&mut self, asset_infos: &[AssetInfo], contract_addr: &str) {
+        self.with_pair_rate(asset_infos, contract_addr, Decimal::one());
+    }
+
+    /// Registers an xyk pair in the mocked factory which swaps at the specified rate
+    pub fn with_pair_rate(
+        &mut self,
+        asset_infos: &[AssetInfo],
+        contract_addr: &str,
+        rate: Decimal,
+    ) {
+        self.pairs.insert(
+            pair_key(asset_infos),
+            PairInfo {
+                asset_infos: asset_infos.to_vec(),
+                contract_addr: Addr::unchecked(contract_addr),
+                liquidity_token: format!("{contract_addr}/lp"),
+                pair_type: PairType::Xyk {},
+            },
+        );
+        self.rates.insert(contract_addr.to_string(), rate);
+    }
+
+    /// Registers a 1:1 xyk pair in the mocked factory whose swaps lose the specified share to spread
+    pub fn with_pair_spread(
+        &mut self,
+        asset_infos: &[AssetInfo],
+        contract_addr: &str,
+        spread: Decimal,
+    ) {
+        self.with_pair(asset_infos, contract_addr);
+        self.spreads.insert(contract_addr.to_string(), spread);
+    }
+
+    /// Changes the type of a registered pair
+    pub fn with_pair_type(&mut self, asset_infos: &[AssetInfo], pair_type: PairType) {
+        if let Some(pair_info) = self.pairs.get_mut(&pair_key(asset_infos)) {
+            pair_info.pair_type = pair_type;
+        }
+    }
+
+    /// Removes the liquidity of a registered pair
+    pub fn with_drained_pair(&mut self, contract_addr: &str) {
+        self.drained.insert(contract_addr.to_string());
+    }
+
+    /// Registers a TWAP oracle pricing tokens in the ask asset
+    pub fn with_oracle(&mut self, contract_addr: &str, ask_asset: AssetInfo, price: Decimal) {
+        self.oracles
+            .insert(contract_addr.to_string(), (ask_asset, price));
+    }
+
+    /// Sets the CW20 balance of a holder
+    pub fn with_token_decimals(&mut self, token: &str, decimals: u8) {
+        self.token_decimals.insert(token.to_string(), decimals);
+        self.token_balances.entry(token.to_string()).or_default();
+    }
+
+    pub fn with_token_balance(&mut self, token: &str, holder: &str, balance: Uint128) {
+        self.token_balances
+            .entry(token.to_string())
+            .or_default()
+            .insert(holder.to_string(), balance);
+    }
+}
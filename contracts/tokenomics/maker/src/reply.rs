@@ -0,0 +1,498 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use std::str::FromStr;
+
+use astroport::asset::AssetInfo;
+use astroport::ibc_transfer::parse_transfer_sequence;
+use astroport::maker::Config;
+use astroport::maker::{AssetStats, DistributionMode, FailedSwap, HopSlippage};
+use astroport::querier::query_token_balance;
+use cosmwasm_std::{
+    attr, to_json_binary, Attribute, Decimal, DepsMut, Empty, Env, Event, Order, Reply, Response,
+    StdResult, Storage, SubMsgResponse, SubMsgResult, Uint128,
+};
+
+use crate::error::ContractError;
+use crate::state::{
+    DiscoveredRoute, RouteProgress, ASSET_STATS, BRIDGE_CHANGES, BURNING_ROIDS, BURN_RETRIES,
+    COLLECT_FAILED, COLLECT_ID, COLLECT_MIN_ROIDS_OUT, COLLECT_ROIDS_OUT, CONFIG,
+    CONSECUTIVE_FAILED_COLLECTS, DISCOVERED_ROUTES, FAILED_ASSETS, IBC_TRANSFERS, MIN_RECEIVED,
+    PAUSED, PENDING_BURN, QUARANTINE_BATCH, ROUTES, ROUTE_PROGRESS, SWAP_HOP, TOKEN_BALANCES,
+    TRANSFER_TAXES, WORST_HOP,
+};
+use crate::utils::{
+    get_min_received, record_burn, record_pair_types, resolve_route, tag_collect_id,
+};
+
+/// Reply ID of the messages transferring ROIDS to the burn destination
+pub const BURN_REPLY_ID: u64 = 2;
+/// Reply ID of swaps along a route to ROIDS whose slippage is recorded
+pub const SWAP_REPLY_ID: u64 = 3;
+/// Reply ID of the last message of a collect with a minimum ROIDS output, replied to once all
+/// its swaps and the distribution settled
+pub const COLLECT_SETTLED_REPLY_ID: u64 = 4;
+/// Reply ID of the calls to the distribution hooks, replied to only if a hook fails
+pub const DISTRIBUTION_HOOK_REPLY_ID: u64 = 5;
+/// Reply IDs of quarantined swaps start here. The offset is added to the index of the offered
+/// asset in the quarantine batch
+pub const QUARANTINE_REPLY_ID_OFFSET: u64 = 1000;
+
+/// The entry point to the contract for processing replies from submessages.
+/// Replies are tagged with the ID of the collect they belong to.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(mut deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let response = handle_reply(deps.branch(), env, msg)?;
+
+    Ok(tag_collect_id(deps.as_ref(), response)?)
+}
+
+fn handle_reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg {
+        // Caller context: contract:swap_assets(). Swaps only reply on failure with the circuit
+        // breaker enabled, the failed swap is skipped and the collect is counted as failed.
+        Reply {
+            id: SWAP_REPLY_ID,
+            result: SubMsgResult::Err(err_msg),
+        } => record_collect_failure(deps, err_msg),
+        // Caller context: contract:swap_assets().
+        Reply {
+            id: SWAP_REPLY_ID,
+            result: SubMsgResult::Ok(response),
+        } => record_swap(deps, &env, response),
+        // Caller context: contract:collect(). Every swap of the collect settled, so the ROIDS
+        // realized altogether is checked against the keeper's minimum.
+        Reply {
+            id: COLLECT_SETTLED_REPLY_ID,
+            result: SubMsgResult::Ok(_),
+        } => {
+            let min = COLLECT_MIN_ROIDS_OUT.load(deps.storage)?;
+            let actual = COLLECT_ROIDS_OUT
+                .may_load(deps.storage)?
+                .unwrap_or_default();
+            COLLECT_MIN_ROIDS_OUT.remove(deps.storage);
+            COLLECT_ROIDS_OUT.remove(deps.storage);
+
+            if actual < min {
+                return Err(ContractError::MinRoidsOutNotMet { min, actual });
+            }
+
+            Ok(Response::new().add_attribute("roids_out", actual))
+        }
+        // Caller context: contract:distribute(). The ROIDS left the Maker, nothing is pending
+        // anymore.
+        Reply {
+            id: BURN_REPLY_ID,
+            result: SubMsgResult::Ok(response),
+        } => {
+            PENDING_BURN.remove(deps.storage);
+            BURN_RETRIES.remove(deps.storage);
+            let burned = match BURNING_ROIDS.may_load(deps.storage)? {
+                Some(burned) => burned,
+                None => return Ok(Response::new()),
+            };
+            BURNING_ROIDS.remove(deps.storage);
+
+            // ROIDS sent over ICS20 may still be refunded, it is accounted for on the ack
+            let cfg = CONFIG.load(deps.storage)?;
+            match &cfg.distribution_mode {
+                DistributionMode::IbcTransfer { channel, .. } if !cfg.tokenfactory_burn => {
+                    let sequence = response
+                        .data
+                        .and_then(|data| parse_transfer_sequence(&data))
+                        .ok_or(ContractError::FailedToParseReply {})?;
+                    IBC_TRANSFERS.save(deps.storage, (channel, sequence), &burned)?;
+
+                    Ok(Response::new().add_attributes([
+                        attr("ibc_channel", channel),
+                        attr("ibc_sequence", sequence.to_string()),
+                    ]))
+                }
+                _ => {
+                    record_burn(deps.storage, &env, burned)?;
+                    Ok(Response::new())
+                }
+            }
+        }
+        // Caller context: contract:distribute(). The ROIDS token rejected the transfer, e.g. due to
+        // transfer hooks, allowlists or pausing. With lenient burns the ROIDS stays in the Maker
+        // as pending so the swaps of the collect aren't reverted.
+        Reply {
+            id: BURN_REPLY_ID,
+            result: SubMsgResult::Err(err_msg),
+        } => {
+            let cfg = CONFIG.load(deps.storage)?;
+            if !cfg.lenient_burn {
+                return Err(ContractError::BurnBlocked { reason: err_msg });
+            }
+            // Only the amount being burned is pending, not the ROIDS held back such as the reserve
+            let pending = BURNING_ROIDS.may_load(deps.storage)?.unwrap_or_default();
+            BURNING_ROIDS.remove(deps.storage);
+            PENDING_BURN.save(deps.storage, &pending)?;
+
+            let retries = BURN_RETRIES.may_load(deps.storage)?.unwrap_or_default() + 1;
+            BURN_RETRIES.save(deps.storage, &retries)?;
+
+            let response = Response::new().add_attributes([
+                attr("burn_blocked", err_msg),
+                attr("pending_roids", pending),
+                attr("failed_burn_attempts", retries.to_string()),
+            ]);
+
+            // Further burns only happen at the regular burn frequency
+            match cfg.burn_retry_limit {
+                Some(limit) if retries == limit + 1 => Ok(response.add_event(
+                    Event::new("burn_retries_exhausted")
+                        .add_attribute("failed_burn_attempts", retries.to_string())
+                        .add_attribute("pending_roids", pending),
+                )),
+                _ => Ok(response),
+            }
+        }
+        // Caller context: contract:distribute(). A failing hook doesn't revert the distribution.
+        Reply {
+            id: DISTRIBUTION_HOOK_REPLY_ID,
+            result: SubMsgResult::Err(err_msg),
+        } => Ok(Response::new()
+            .add_event(Event::new("distribution_hook_failed").add_attribute("error", err_msg))),
+        // Caller context: contract:quarantine_swaps(). A failed swap doesn't revert the collect.
+        Reply { id, result } if id >= QUARANTINE_REPLY_ID_OFFSET => {
+            let asset = QUARANTINE_BATCH
+                .load(deps.storage)?
+                .get((id - QUARANTINE_REPLY_ID_OFFSET) as usize)
+                .cloned()
+                .ok_or(ContractError::FailedToParseReply {})?;
+
+            match result {
+                SubMsgResult::Ok(response) => {
+                    FAILED_ASSETS.remove(deps.storage, asset.to_string());
+                    record_swap(deps, &env, response)
+                }
+                SubMsgResult::Err(error) => {
+                    FAILED_ASSETS.save(
+                        deps.storage,
+                        asset.to_string(),
+                        &FailedSwap {
+                            asset: asset.clone(),
+                            error: error.clone(),
+                            collect_id: COLLECT_ID.may_load(deps.storage)?.unwrap_or_default(),
+                        },
+                    )?;
+
+                    Ok(Response::new().add_event(
+                        Event::new("swap_quarantined")
+                            .add_attribute("asset", asset.to_string())
+                            .add_attribute("error", error),
+                    ))
+                }
+            }
+        }
+        _ => Err(ContractError::FailedToParseReply {}),
+    }
+}
+
+/// Counts the collect as failed once, however many of its swaps failed, and pauses the Maker
+/// when the consecutive failed collects reach the threshold of the circuit breaker.
+fn record_collect_failure(deps: DepsMut, err_msg: String) -> Result<Response, ContractError> {
+    let response = Response::new().add_attribute("swap_error", err_msg);
+
+    // Count each collect only once, even if several of its swaps failed
+    if COLLECT_FAILED.may_load(deps.storage)?.unwrap_or_default() {
+        return Ok(response);
+    }
+    COLLECT_FAILED.save(deps.storage, &true)?;
+
+    let failures = CONSECUTIVE_FAILED_COLLECTS
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        + 1;
+    CONSECUTIVE_FAILED_COLLECTS.save(deps.storage, &failures)?;
+
+    let response = response.add_attribute("consecutive_failed_collects", failures.to_string());
+
+    let cfg = CONFIG.load(deps.storage)?;
+    match cfg.auto_pause_threshold {
+        Some(threshold) if failures >= threshold => {
+            PAUSED.save(deps.storage, &true)?;
+
+            Ok(response.add_event(
+                Event::new("auto_paused")
+                    .add_attribute("consecutive_failed_collects", failures.to_string()),
+            ))
+        }
+        _ => Ok(response),
+    }
+}
+
+/// Records a successful swap: emits its slippage and its outcome, keeps track of the worst hop
+/// since the latest collect and moves the assets swept by the collect along their routes. The
+/// swap is described by the attributes emitted by the pairs. A router swap emits one swap event
+/// per pair, so the offer is taken from the first event and the return from the last one.
+/// Assets reaching ROIDS below their minimum received revert the collect. The amounts of CW20
+/// tokens actually received are measured against their balance before the swap so tokens taxing
+/// their transfers are accounted for.
+fn record_swap(
+    deps: DepsMut,
+    env: &Env,
+    response: SubMsgResponse,
+) -> Result<Response, ContractError> {
+    let swaps: Vec<_> = response
+        .events
+        .into_iter()
+        .filter(|event| {
+            event
+                .attributes
+                .iter()
+                .any(|a| a.key == "action" && a.value == "swap")
+        })
+        .map(|event| event.attributes)
+        .collect();
+    let (first, last) = match (swaps.first(), swaps.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Ok(Response::new()),
+    };
+
+    let find = |attrs: &[Attribute], key: &str| {
+        attrs
+            .iter()
+            .find(|a| a.key == key)
+            .map(|a| a.value.clone())
+            .unwrap_or_default()
+    };
+    let amount =
+        |attrs: &[Attribute], key: &str| Uint128::from_str(&find(attrs, key)).unwrap_or_default();
+
+    let offer_asset = find(first, "offer_asset");
+    let ask_asset = find(last, "ask_asset");
+    let offer_amount = amount(first, "offer_amount");
+    let return_amount = amount(last, "return_amount");
+
+    // Each pair a router swap went through is a hop of its own
+    let mut response = Response::new();
+    let first_hop = SWAP_HOP.may_load(deps.storage)?.unwrap_or_default();
+    let mut worst = WORST_HOP.may_load(deps.storage)?;
+    for (index, attrs) in swaps.iter().enumerate() {
+        let hop_return = amount(attrs, "return_amount");
+        let spread_amount = amount(attrs, "spread_amount");
+        let hop = HopSlippage {
+            hop: first_hop + index as u64,
+            offer_asset: find(attrs, "offer_asset"),
+            ask_asset: find(attrs, "ask_asset"),
+            slippage: Decimal::checked_from_ratio(spread_amount, hop_return + spread_amount)
+                .unwrap_or_default(),
+        };
+
+        response = response.add_event(
+            Event::new("hop_slippage")
+                .add_attribute("hop", hop.hop.to_string())
+                .add_attribute("offer_asset", &hop.offer_asset)
+                .add_attribute("ask_asset", &hop.ask_asset)
+                .add_attribute("offer_amount", amount(attrs, "offer_amount"))
+                .add_attribute("return_amount", hop_return)
+                .add_attribute("spread_amount", spread_amount)
+                .add_attribute("slippage", hop.slippage.to_string()),
+        );
+
+        let is_worst = worst
+            .as_ref()
+            .map(|worst| hop.slippage > worst.slippage)
+            .unwrap_or(true);
+        if is_worst {
+            WORST_HOP.save(deps.storage, &hop)?;
+            worst = Some(hop);
+        }
+    }
+
+    if !offer_asset.is_empty() {
+        ASSET_STATS.update(deps.storage, offer_asset.clone(), |stats| {
+            let mut stats: AssetStats = stats.unwrap_or_default();
+            stats.total_swapped += offer_amount;
+            stats.swaps += 1;
+            StdResult::Ok(stats)
+        })?;
+    }
+
+    // The offered tokens left the Maker
+    if TOKEN_BALANCES.has(deps.storage, offer_asset.clone()) {
+        let balance = query_token_balance(&deps.querier, &offer_asset, &env.contract.address)?;
+        TOKEN_BALANCES.save(deps.storage, offer_asset.clone(), &balance)?;
+    }
+
+    // Tokens taxing their transfers deliver less than the pair returned
+    let mut received = return_amount;
+    let mut transfer_tax = None;
+    if let Some(before) = TOKEN_BALANCES.may_load(deps.storage, ask_asset.clone())? {
+        let balance = query_token_balance(&deps.querier, &ask_asset, &env.contract.address)?;
+        TOKEN_BALANCES.save(deps.storage, ask_asset.clone(), &balance)?;
+
+        received = balance.saturating_sub(before).min(return_amount);
+        if received < return_amount {
+            let tax = Decimal::from_ratio(return_amount - received, return_amount);
+            TRANSFER_TAXES.save(deps.storage, ask_asset.clone(), &tax)?;
+            transfer_tax = Some(tax);
+        } else {
+            TRANSFER_TAXES.remove(deps.storage, ask_asset.clone());
+        }
+    }
+
+    // Indexers match swaps with the collects they belong to without parsing the pair events
+    response = response.add_event(
+        Event::new("astroport/maker/swap")
+            .add_attribute("asset", &offer_asset)
+            .add_attribute("ask_asset", &ask_asset)
+            .add_attribute("amount_in", offer_amount)
+            .add_attribute("amount_out", return_amount)
+            .add_attribute("pool", find(first, "_contract_address")),
+    );
+
+    if let Some(tax) = transfer_tax {
+        response = response.add_event(
+            Event::new("transfer_tax")
+                .add_attribute("asset", &ask_asset)
+                .add_attribute("return_amount", return_amount)
+                .add_attribute("received_amount", received)
+                .add_attribute("tax_rate", tax.to_string()),
+        );
+    }
+
+    let mut progress = match ROUTE_PROGRESS.may_load(deps.storage)? {
+        Some(progress) => progress,
+        None => return Ok(response),
+    };
+    let cfg = CONFIG.load(deps.storage)?;
+    let mut discovered = DISCOVERED_ROUTES
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let discovered_count = discovered.len();
+
+    let mut realized_any = false;
+    for entry in progress
+        .iter_mut()
+        .filter(|entry| !entry.held.is_zero() && entry.holding.to_string() == offer_asset)
+    {
+        // The next hop is either the route or bridge of the asset, the default bridge or ROIDS.
+        // The router swaps several hops of the route at once
+        let next = ROUTES
+            .may_load(deps.storage, entry.holding.to_string())?
+            .and_then(|route| route.first().cloned())
+            .into_iter()
+            .chain(cfg.default_bridge.clone())
+            .chain([cfg.roids_token.clone()])
+            .chain(
+                cfg.router_contract
+                    .as_ref()
+                    .and_then(|_| resolve_route(deps.as_ref(), &cfg, &entry.holding).ok())
+                    .unwrap_or_default(),
+            )
+            .find(|asset| asset.to_string() == ask_asset);
+        let next = match next {
+            Some(next) => next,
+            None => continue,
+        };
+
+        // A route discovered by the collect is learned once the first swap along it succeeded
+        if entry.holding == entry.asset {
+            let index = discovered
+                .iter()
+                .position(|route| route.asset == entry.asset && route.bridge == next);
+            if let Some(index) = index {
+                let route = discovered.remove(index);
+                if learn_route(deps.storage, env, &cfg, &route)? {
+                    response = response.add_attribute(
+                        "learned_route",
+                        format!("{}:{}", route.asset, route.bridge),
+                    );
+                }
+            }
+        }
+
+        // Bridge swaps may offer the bridged amounts of several assets at once
+        entry.held = entry
+            .held
+            .multiply_ratio(received, offer_amount.max(Uint128::one()));
+        entry.holding = next;
+
+        if entry.holding == cfg.roids_token {
+            if let Some(min_received) =
+                MIN_RECEIVED.may_load(deps.storage, entry.asset.to_string())?
+            {
+                let min_received = get_min_received(
+                    &deps.querier,
+                    &cfg,
+                    &entry.asset,
+                    entry.input,
+                    &min_received,
+                )?;
+                if entry.held < min_received {
+                    return Err(ContractError::MinReceivedNotMet {
+                        asset: entry.asset.clone(),
+                        min_received,
+                        received: entry.held,
+                    });
+                }
+            }
+
+            realized_any = true;
+            response = response.add_event(
+                Event::new("effective_price")
+                    .add_attribute("asset", entry.asset.to_string())
+                    .add_attribute("input_amount", entry.input)
+                    .add_attribute("realized_roids", entry.held)
+                    .add_attribute("price", effective_price(entry).to_string()),
+            );
+        }
+    }
+    ROUTE_PROGRESS.save(deps.storage, &progress)?;
+    if discovered.len() < discovered_count {
+        DISCOVERED_ROUTES.save(deps.storage, &discovered)?;
+    }
+
+    if realized_any {
+        let prices: Vec<(AssetInfo, Decimal)> = progress
+            .iter()
+            .filter(|entry| entry.holding == cfg.roids_token)
+            .map(|entry| (entry.asset.clone(), effective_price(entry)))
+            .collect();
+        response = response.set_data(to_json_binary(&prices)?);
+    }
+
+    Ok(response)
+}
+
+/// Saves a route discovered by a collect as the bridge of its asset along with the pair type of
+/// its pool. Returns whether the route was learned.
+fn learn_route(
+    storage: &mut dyn Storage,
+    env: &Env,
+    cfg: &Config,
+    route: &DiscoveredRoute,
+) -> StdResult<bool> {
+    // The route may have been set since the collect, or the bridges limit reached
+    if ROUTES.has(storage, route.asset.to_string()) {
+        return Ok(false);
+    }
+    if let Some(max) = cfg.max_bridges {
+        let count = ROUTES.keys(storage, None, None, Order::Ascending).count() as u32;
+        if count >= max {
+            return Ok(false);
+        }
+    }
+
+    ROUTES.save(
+        storage,
+        route.asset.to_string(),
+        &vec![route.bridge.clone()],
+    )?;
+    record_pair_types(storage, &[route.pool.clone()])?;
+    BRIDGE_CHANGES.save(
+        storage,
+        (env.block.height, &route.asset.to_string()),
+        &Empty {},
+    )?;
+
+    Ok(true)
+}
+
+/// Returns the ROIDS realized per unit of the swept asset, zero if nothing was swept.
+fn effective_price(entry: &RouteProgress) -> Decimal {
+    Decimal::checked_from_ratio(entry.held, entry.input).unwrap_or_default()
+}
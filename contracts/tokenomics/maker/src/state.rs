@@ -1,15 +1,189 @@
-use astroport::asset::AssetInfo;
+use astroport::asset::{Asset, AssetInfo, PairInfo};
 use astroport::common::OwnershipProposal;
-use astroport::maker::Config;
+use astroport::factory::PairType;
+use astroport::maker::{
+    AssetStats, AssetWithLimit, Auction, BridgeProposal, CollectorRecord, Config, ConfigChange,
+    ConfigHistoryEntry, ExternalDex, FailedSwap, HopSlippage, KeeperCommitment, MinReceived,
+    PreUpgradeRewards, PriceOracle, SeizeProposal,
+};
+use astroport_circular_buffer::CircularBuffer;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Empty, Uint128};
 use cw_storage_plus::{Item, Map};
 
+/// An asset swept by a collect on its way to ROIDS.
+#[cw_serde]
+pub struct RouteProgress {
+    /// The swept asset
+    pub asset: AssetInfo,
+    /// The swept amount
+    pub input: Uint128,
+    /// The asset the swept amount is currently held in
+    pub holding: AssetInfo,
+    /// The amount of `holding` derived from the swept amount
+    pub held: Uint128,
+}
+
+/// A route taken by a collect for an asset without a bridge, learned once its swap succeeded.
+#[cw_serde]
+pub struct DiscoveredRoute {
+    /// The swept asset
+    pub asset: AssetInfo,
+    /// The asset the route continues through
+    pub bridge: AssetInfo,
+    /// The pool swapping the asset to the bridge
+    pub pool: PairInfo,
+}
+
+/// The ROIDS value swapped by the collects of the current window.
+#[cw_serde]
+pub struct ValueWindow {
+    /// Timestamp (in seconds) the window started at
+    pub start: u64,
+    /// The ROIDS value swapped within the window
+    pub spent: Uint128,
+}
+
+/// Lifetime distribution statistics of the Maker.
+#[cw_serde]
+#[derive(Default)]
+pub struct Statistics {
+    /// The total amount of ROIDS distributed
+    pub total_roids: Uint128,
+    /// The number of distributions
+    pub distributions: u64,
+    /// The amount of ROIDS of the latest distribution
+    pub last_distribution_amount: Uint128,
+    /// Timestamp (in seconds) of the latest distribution
+    pub last_distribution_ts: Option<u64>,
+}
+
 /// Stores the contract configuration at the given key
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Stores the ID of the latest collect. Every step of a collect is tagged with it
+pub const COLLECT_ID: Item<u64> = Item::new("collect_id");
+
+/// Stores the routes of fee tokens to ROIDS. A bridge is a route of a single asset which then
+/// continues along its own route, routes of several assets end with ROIDS
+pub const ROUTES: Map<String, Vec<AssetInfo>> = Map::new("routes");
+/// Stores the pair type of the pools on the validated bridges and routes by pool address
+pub const PAIR_TYPES: Map<String, PairType> = Map::new("pair_types");
+/// Stores the minimum ROIDS each fee token must realize when collected
+pub const MIN_RECEIVED: Map<String, MinReceived> = Map::new("min_received");
+/// Stores the max spreads of specific fee tokens, overriding the global max spreads
+pub const SPREADS: Map<String, Decimal> = Map::new("spreads");
+/// Stores the assets which are never swapped, keyed by asset
+pub const BLACKLIST: Map<String, AssetInfo> = Map::new("blacklist");
+/// Stores the TWAP oracles the pool prices of specific assets are checked against
+pub const PRICE_ORACLES: Map<String, PriceOracle> = Map::new("price_oracles");
+/// Stores the external DEXes specific fee tokens are swapped on, keyed by fee token
+pub const EXTERNAL_DEXES: Map<String, ExternalDex> = Map::new("external_dexes");
+
+/// Stores the holders of the roles handed over by the owner, keyed by role
+pub const ROLES: Map<&str, Addr> = Map::new("roles");
+
 /// Stores the latest proposal to change contract ownership
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
 
-/// Stores bridge tokens used to swap fee tokens to ASTRO
-pub const BRIDGES: Map<String, AssetInfo> = Map::new("bridges");
 /// Stores the latest timestamp when fees were collected
 pub const LAST_COLLECT_TS: Item<u64> = Item::new("last_collect_ts");
+/// Stores the callers whose collects aren't subject to the collect cooldown
+pub const COOLDOWN_EXEMPT: Map<&Addr, Empty> = Map::new("cooldown_exempt");
+/// Stores the end of the current cooldown window when `collect_cooldown` was changed during it.
+/// The new cooldown only applies to windows started by later collects
+pub const COOLDOWN_WINDOW_END: Item<u64> = Item::new("cooldown_window_end");
+
+/// Stores the contracts called after each distribution
+pub const DISTRIBUTION_HOOKS: Map<&Addr, Empty> = Map::new("distribution_hooks");
+
+/// Stores whether collects are paused
+pub const PAUSED: Item<bool> = Item::new("paused");
+/// Stores the timestamp of the latest unpause
+pub const UNPAUSED_AT: Item<u64> = Item::new("unpaused_at");
+/// Stores the number of consecutive collects with at least one failed swap
+pub const CONSECUTIVE_FAILED_COLLECTS: Item<u32> = Item::new("consecutive_failed_collects");
+/// Stores whether a swap failed during the latest collect
+pub const COLLECT_FAILED: Item<bool> = Item::new("collect_failed");
+/// Stores the number of collects since ROIDS was last burned
+pub const COLLECTS_SINCE_BURN: Item<u32> = Item::new("collects_since_burn");
+/// Stores the release of the ROIDS held from before the Maker upgrade
+pub const PRE_UPGRADE_REWARDS: Item<PreUpgradeRewards> = Item::new("pre_upgrade_rewards");
+/// Stores the amount of ROIDS left in the Maker after its burn was blocked by the token
+pub const PENDING_BURN: Item<Uint128> = Item::new("pending_burn");
+/// Stores the latest keeper commitment to collect
+pub const KEEPER_COMMITMENT: Item<KeeperCommitment> = Item::new("keeper_commitment");
+/// Stores the pending route proposals keyed by fee token
+pub const BRIDGE_PROPOSALS: Map<String, BridgeProposal> = Map::new("bridge_proposals");
+/// Stores the pending proposal to send assets without a route out of the Maker
+pub const SEIZE_PROPOSAL: Item<SeizeProposal> = Item::new("seize_proposal");
+/// Stores the pending change of the critical settings
+pub const PENDING_CONFIG_CHANGE: Item<ConfigChange> = Item::new("pending_config_change");
+/// Stores every successful config and bridge update keyed by sequential ID
+pub const CONFIG_HISTORY: Map<u64, ConfigHistoryEntry> = Map::new("config_history");
+/// Stores the running auctions keyed by the fee token they sell
+pub const AUCTIONS: Map<String, Auction> = Map::new("auctions");
+/// Stores the minimum ROIDS the swaps of the collect in flight must realize
+pub const COLLECT_MIN_ROIDS_OUT: Item<Uint128> = Item::new("collect_min_roids_out");
+/// Stores the ROIDS realized by the swaps of the collect in flight
+pub const COLLECT_ROIDS_OUT: Item<Uint128> = Item::new("collect_roids_out");
+/// Stores the assets left over by the latest collect for ContinueCollect
+pub const PENDING_COLLECT: Item<Vec<AssetWithLimit>> = Item::new("pending_collect");
+/// Stores the total amounts donated, keyed by donor and asset
+pub const DONATIONS: Map<(&Addr, String), Asset> = Map::new("donations");
+/// Stores the caller of the latest collect until its ROIDS is distributed
+pub const PENDING_COLLECTOR: Item<Addr> = Item::new("pending_collector");
+/// Stores the latest collects with their callers
+pub const RECENT_COLLECTORS: CircularBuffer<CollectorRecord> =
+    CircularBuffer::new("recent_collectors_state", "recent_collectors");
+/// Stores the Maker's ROIDS balance before the swaps of the latest collect
+pub const SWAPS_START_ROIDS: Item<Uint128> = Item::new("swaps_start_roids");
+/// Stores the amount of swap-derived ROIDS which wasn't distributed yet
+pub const SWAPPED_ROIDS: Item<Uint128> = Item::new("swapped_roids");
+/// Stores the amount of ROIDS sent to the burn destination and awaiting the burn reply
+pub const BURNING_ROIDS: Item<Uint128> = Item::new("burning_roids");
+/// Stores the amounts of ROIDS sent over ICS20 and awaiting the acknowledgement of the Hub, by
+/// channel and packet sequence
+pub const IBC_TRANSFERS: Map<(&str, u64), Uint128> = Map::new("ibc_transfers");
+
+/// Stores the number of failed attempts to burn the pending ROIDS
+pub const BURN_RETRIES: Item<u32> = Item::new("burn_retries");
+/// Stores the lifetime distribution statistics, updated when a burn is confirmed
+pub const STATS: Item<Statistics> = Item::new("stats");
+/// Stores the total amount of ROIDS burned by the Maker
+pub const TOTAL_BURNED: Item<Uint128> = Item::new("total_burned");
+/// Stores the total amount of ROIDS paid out of distributions as keeper fees, second receiver
+/// cuts and dev fund shares
+pub const RETURNED_TO_CIRCULATION: Item<Uint128> = Item::new("returned_to_circulation");
+/// Stores the route hop index of the swaps being executed
+pub const SWAP_HOP: Item<u64> = Item::new("swap_hop");
+/// Stores the hop with the highest slippage since the latest collect
+pub const WORST_HOP: Item<HopSlippage> = Item::new("worst_hop");
+/// Stores the assets offered by the quarantined swaps being executed, indexed by reply ID
+pub const QUARANTINE_BATCH: Item<Vec<AssetInfo>> = Item::new("quarantine_batch");
+/// Stores the fee tokens whose swaps failed and await a retry
+pub const FAILED_ASSETS: Map<String, FailedSwap> = Map::new("failed_assets");
+/// Stores the amount of each asset swapped by the Maker
+pub const ASSET_STATS: Map<String, AssetStats> = Map::new("asset_stats");
+/// Stores the ROIDS value swapped by the collects of the current window
+pub const COLLECT_VALUE_WINDOW: Item<ValueWindow> = Item::new("collect_value_window");
+/// Stores the assets swept by the latest collect along with their progress to ROIDS
+pub const ROUTE_PROGRESS: Item<Vec<RouteProgress>> = Item::new("route_progress");
+/// Stores the routes discovered by the latest collect that are learned once their swaps succeed
+pub const DISCOVERED_ROUTES: Item<Vec<DiscoveredRoute>> = Item::new("discovered_routes");
+/// Stores the balances of the bridge assets held before a dust consolidation swapped to them,
+/// so only the consolidated amounts are swapped on the next hop
+pub const DUST_BRIDGE_BALANCES: Item<Vec<Asset>> = Item::new("dust_bridge_balances");
+/// Stores the assets whose bridge was added or removed by block height
+pub const BRIDGE_CHANGES: Map<(u64, &str), Empty> = Map::new("bridge_changes");
+/// Stores the latest block height at which a config change could affect every route
+pub const ROUTES_RESET_HEIGHT: Item<u64> = Item::new("routes_reset_height");
+/// Stores the decimals of the bridge assets, ROIDS and the default bridge
+pub const DECIMALS: Map<String, u8> = Map::new("decimals");
+/// Stores the latest timestamp when standing ROIDS was flushed
+pub const LAST_FLUSH_TS: Item<u64> = Item::new("last_flush_ts");
+/// Stores the transfer tax rate observed for each CW20 token taxing its transfers
+pub const TRANSFER_TAXES: Map<String, Decimal> = Map::new("transfer_taxes");
+/// Stores the Maker's balance of the CW20 bridge tokens swapped to by the latest collect step,
+/// updated after each swap to measure what actually arrived
+pub const TOKEN_BALANCES: Map<String, Uint128> = Map::new("token_balances");
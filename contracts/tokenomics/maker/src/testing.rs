@@ -1,36 +1,84 @@
-use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-use cosmwasm_std::{from_json, Addr, Decimal, Uint128, Uint64};
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    attr, coin, from_json, to_json_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps,
+    DepsMut, Env, Event, Order, Reply, ReplyOn, Response, StdResult, Storage, SubMsg,
+    SubMsgResponse, SubMsgResult, Timestamp, Uint128, WasmMsg,
+};
+use cw_storage_plus::Map;
 
-use crate::contract::{execute, instantiate, query};
-use crate::state::CONFIG;
-use astroport::asset::{native_asset_info, token_asset_info};
-use astroport::maker::{Config, ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::contract::{execute, instantiate, migrate, query, sudo};
+use crate::error::ContractError;
+use crate::mock_querier::{mock_dependencies, FACTORY};
+use crate::reply::{
+    reply, BURN_REPLY_ID, COLLECT_SETTLED_REPLY_ID, DISTRIBUTION_HOOK_REPLY_ID, SWAP_REPLY_ID,
+};
+use crate::state::{
+    RouteProgress, BURNING_ROIDS, CONFIG, DECIMALS, KEEPER_COMMITMENT, PAIR_TYPES, ROUTES,
+    ROUTE_PROGRESS,
+};
+use astroport::asset::{native_asset_info, token_asset_info, Asset, AssetInfo};
+use astroport::factory::PairType;
+use astroport::maker::{
+    AssetSimulation, AssetStats, AssetTypeFilter, AssetWithLimit, AsteroidBridgeExecuteMsg,
+    Auction, AuctionFallback, AuctionResponse, BalancesResponse, BeliefPriceSource, BridgeCall,
+    BridgeCallResponse, BridgeInfo, BridgeProposal, BridgesResponse, BrokenRoute,
+    BurnDestinationResponse, BurnDestinationSource, CanOnboardResponse, CollectStatusResponse,
+    CollectorRecord, Config, ConfigChange, ConfigHistoryEntry, ConfigOverrides, ConfigResponse,
+    Cw20HookMsg, DevFundConfig, DirectPairsResponse, DistributeResponseData, DistributionHookMsg,
+    DistributionMode, EffectiveConfigResponse, ExecuteMsg, ExternalDex, FailOrSkip, FailedSwap,
+    HopSlippage, IbcLifecycleComplete, InstantiateMsg, KeeperCommitment, LimitSemantics,
+    MaxCollectNowResponse, MigrateMsg, MinReceived, PermissionsResponse, PreUpgradeRewards,
+    PriceOracle, QueryMsg, Role, RouteHop, RouteResponse, RoutesChangedSinceResponse,
+    SecondReceiverConfig, SecondReceiverParams, SeizeProposal, SimulateCollectResponse,
+    StatisticsResponse, SudoMsg, SupplyImpactResponse, SwapPreviewResponse, SwapSimulation,
+    DEFAULT_CONFIG_CHANGE_DELAY_SECS, DEFAULT_SEIZE_DELAY_SECS, MAX_SECOND_RECEIVER_CUT_BPS,
+};
+use astroport::pair::ExecuteMsg as PairExecuteMsg;
+use astroport::token_factory::tf_burn_msg;
+use astroport_pair::contract::assert_max_spread;
 use std::str::FromStr;
 
+const OWNER: &str = "owner";
+const ROIDS: &str = "roids-token";
+
+fn roids() -> AssetInfo {
+    token_asset_info(Addr::unchecked(ROIDS))
+}
+
+fn instantiate_maker(deps: DepsMut) {
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        default_bridge: Some(native_asset_info("uluna".to_string())),
+        roids_token: roids(),
+        factory_contract: FACTORY.to_string(),
+        asteroid_contract: "asteroid".to_string(),
+        max_spread: None,
+        collect_cooldown: None,
+        swap_deadline_secs: None,
+    };
+
+    instantiate(deps, mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+}
+
 #[test]
 fn proper_initialization() {
-    let mut deps = mock_dependencies();
+    let mut deps = mock_dependencies(&[]);
     let info = mock_info("addr0000", &[]);
 
     let env = mock_env();
     let owner = Addr::unchecked("owner");
-    let factory = Addr::unchecked("factory");
-    let staking = Addr::unchecked("staking");
-    let governance_contract = Addr::unchecked("governance");
-    let governance_percent = Uint64::new(50);
-    let astro_token_contract = Addr::unchecked("astro-token");
+    let factory = Addr::unchecked(FACTORY);
+    let asteroid = Addr::unchecked("asteroid");
 
     let instantiate_msg = InstantiateMsg {
         owner: owner.to_string(),
         factory_contract: factory.to_string(),
-        staking_contract: Some(staking.to_string()),
-        governance_contract: Option::from(governance_contract.to_string()),
-        governance_percent: Option::from(governance_percent),
-        astro_token: token_asset_info(astro_token_contract.clone()),
+        asteroid_contract: asteroid.to_string(),
+        roids_token: roids(),
         default_bridge: Some(native_asset_info("uluna".to_string())),
         max_spread: None,
-        second_receiver_params: None,
         collect_cooldown: None,
+        swap_deadline_secs: None,
     };
     let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
     assert_eq!(0, res.messages.len());
@@ -40,53 +88,145 @@ fn proper_initialization() {
         state,
         Config {
             owner: Addr::unchecked("owner"),
-            factory_contract: Addr::unchecked("factory"),
-            staking_contract: Some(Addr::unchecked("staking")),
+            factory_contract: factory,
+            asteroid_contract: asteroid,
             default_bridge: Some(native_asset_info("uluna".to_string())),
-            governance_contract: Option::from(governance_contract),
-            governance_percent,
-            astro_token: token_asset_info(astro_token_contract),
+            roids_token: roids(),
             max_spread: Decimal::from_str("0.05").unwrap(),
-            rewards_enabled: false,
-            pre_upgrade_blocks: 0,
-            last_distribution_block: 0,
-            remainder_reward: Uint128::zero(),
-            pre_upgrade_astro_amount: Uint128::zero(),
-            second_receiver_cfg: None,
             collect_cooldown: None,
+            swap_deadline_secs: None,
+            approved_bridge_assets: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: None,
+            dust_threshold: None,
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: None,
+            lenient_burn: false,
+            max_bridges: None,
+            rebasing_assets: vec![],
+            strict_collect: false,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: None,
+            learn_routes: false,
+            keeper_fee_bps: None,
+            router_contract: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: false,
+            second_receiver_cfg: None,
+            dev_fund_config: None,
+            max_collect_value_per_window: None,
+            distribution_mode: Default::default(),
+            bridge_call: None,
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: false,
+            max_assets_per_tx: None,
+            belief_price_source: Default::default(),
+            on_spread_exceeded: Default::default(),
+            reserve_amount: None,
+            auction_fallback: None,
+            config_change_delay_secs: None,
+            operator: None,
         }
     )
 }
 
 #[test]
-fn update_owner() {
-    let mut deps = mock_dependencies();
-    let info = mock_info("addr0000", &[]);
+fn migrate_from_astroport() {
+    let mut deps = mock_dependencies(&[]);
+    cw2::set_contract_version(deps.as_mut().storage, "astroport-maker", "1.4.0").unwrap();
+    deps.as_mut().storage.set(
+        b"config",
+        br#"{
+            "owner": "owner",
+            "factory_contract": "factory",
+            "staking_contract": "staking",
+            "default_bridge": {"native_token": {"denom": "uluna"}},
+            "governance_contract": "governance",
+            "governance_percent": "10",
+            "astro_token": {"token": {"contract_addr": "astro"}},
+            "max_spread": "0.05",
+            "rewards_enabled": true,
+            "pre_upgrade_blocks": 0,
+            "last_distribution_block": 0,
+            "remainder_reward": "0",
+            "pre_upgrade_astro_amount": "0",
+            "second_receiver_cfg": {"second_fee_receiver": "receiver", "second_receiver_cut": "20"},
+            "collect_cooldown": 300
+        }"#,
+    );
+    Map::<String, AssetInfo>::new("bridges")
+        .save(
+            deps.as_mut().storage,
+            "ufee".to_string(),
+            &native_asset_info("uluna".to_string()),
+        )
+        .unwrap();
 
-    let owner = Addr::unchecked("owner");
-    let factory = Addr::unchecked("factory");
-    let staking = Addr::unchecked("staking");
-    let governance_contract = Addr::unchecked("governance");
-    let governance_percent = Uint64::new(50);
-    let astro_token_contract = Addr::unchecked("astro-token");
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap_err();
+    assert_eq!(err, ContractError::MissingMigrationParams {});
 
-    let msg = InstantiateMsg {
-        owner: owner.to_string(),
-        factory_contract: factory.to_string(),
-        staking_contract: Some(staking.to_string()),
-        governance_contract: Option::from(governance_contract.to_string()),
-        governance_percent: Option::from(governance_percent),
-        astro_token: token_asset_info(astro_token_contract),
-        default_bridge: Some(native_asset_info("uluna".to_string())),
-        max_spread: None,
-        second_receiver_params: None,
-        collect_cooldown: None,
-    };
+    let res = migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            asteroid_contract: Some("asteroid".to_string()),
+            roids_token: Some(roids()),
+        },
+    )
+    .unwrap();
+    assert!(res
+        .attributes
+        .contains(&attr("dropped_staking_contract", "staking")));
+    assert!(res
+        .attributes
+        .contains(&attr("dropped_governance_percent", "10")));
+    assert!(res.attributes.contains(&attr("migrated_bridges", "1")));
 
-    let env = mock_env();
+    let config = CONFIG.load(deps.as_ref().storage).unwrap();
+    assert_eq!(config.owner, Addr::unchecked(OWNER));
+    assert_eq!(config.factory_contract, Addr::unchecked("factory"));
+    assert_eq!(config.asteroid_contract, Addr::unchecked("asteroid"));
+    assert_eq!(config.roids_token, roids());
+    assert_eq!(config.collect_cooldown, Some(300));
+    assert_eq!(
+        config.second_receiver_cfg,
+        Some(SecondReceiverConfig {
+            address: Addr::unchecked("receiver"),
+            cut_bps: 2000,
+        })
+    );
+
+    // Bridges carry over as routes and the Maker waits for the owner to unpause it
+    let bridges: BridgesResponse = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Bridges {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(bridges.bridges.len(), 1);
+    let config: ConfigResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert!(config.paused);
+
+    let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+    assert_eq!(version.contract, "asteroid-maker");
+}
+
+#[test]
+fn update_owner() {
+    let mut deps = mock_dependencies(&[]);
+    let owner = Addr::unchecked(OWNER);
 
     // We can just call .unwrap() to assert this was a success
-    instantiate(deps.as_mut(), env, info, msg).unwrap();
+    instantiate_maker(deps.as_mut());
 
     let new_owner = String::from("new_owner");
 
@@ -142,6 +282,9279 @@ fn update_owner() {
 
     // Let's query the state
     let config: ConfigResponse =
-        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+        from_json(query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
     assert_eq!(new_owner, config.owner);
 }
+
+#[test]
+fn approved_bridge_assets() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let fee_token = native_asset_info("ufee".to_string());
+    let uluna = native_asset_info("uluna".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    deps.querier
+        .with_pair(&[fee_token.clone(), uluna.clone()], "fee-luna");
+    deps.querier
+        .with_pair(&[uluna.clone(), roids()], "luna-roids");
+    deps.querier
+        .with_pair(&[fee_token.clone(), uatom.clone()], "fee-atom");
+    deps.querier
+        .with_pair(&[uatom.clone(), roids()], "atom-roids");
+
+    // Only the owner can set the whitelist
+    let msg = ExecuteMsg::UpdateApprovedBridgeAssets {
+        assets: Some(vec![uluna.clone()]),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("random", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // The whitelist must contain the default bridge
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateApprovedBridgeAssets {
+            assets: Some(vec![uatom.clone()]),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::BridgeAssetNotApproved(uluna.clone()));
+
+    execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+
+    // Bridge through a non-approved asset is rejected
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateBridges {
+            add: Some(vec![(fee_token.clone(), uatom.clone())]),
+            remove: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::BridgeAssetNotApproved(uatom.clone()));
+
+    // Bridge through an approved asset is accepted
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateBridges {
+            add: Some(vec![(fee_token.clone(), uluna.clone())]),
+            remove: None,
+        },
+    )
+    .unwrap();
+
+    // ROIDS is always an allowed bridge
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateBridges {
+            add: Some(vec![(uatom.clone(), roids())]),
+            remove: None,
+        },
+    )
+    .unwrap();
+
+    // Removing the whitelist allows any bridge again
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateApprovedBridgeAssets { assets: None },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateBridges {
+            add: Some(vec![(fee_token, uatom)]),
+            remove: None,
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn distribute_sets_response_data() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let env = mock_env();
+
+    // Only the Maker itself can distribute
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("random", &[]),
+        ExecuteMsg::DistributeAstro {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // Nothing to distribute still sets data
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::DistributeAstro {},
+    )
+    .unwrap();
+    let data: DistributeResponseData = from_json(res.data.unwrap()).unwrap();
+    assert_eq!(
+        data,
+        DistributeResponseData {
+            amount: Uint128::zero(),
+            destination: "asteroid".to_string(),
+        }
+    );
+
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(1000));
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::DistributeAstro {},
+    )
+    .unwrap();
+    let data: DistributeResponseData = from_json(res.data.unwrap()).unwrap();
+    assert_eq!(data.amount, Uint128::new(1000));
+}
+
+#[test]
+fn resolve_burn_destination() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(1000));
+
+    let res: BurnDestinationResponse = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ResolveBurnDestination {},
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        BurnDestinationResponse {
+            destination: "asteroid".to_string(),
+            source: BurnDestinationSource::AsteroidContract,
+        }
+    );
+
+    // The query mirrors the destination of the distribution
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::DistributeAstro {},
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("destination", "asteroid")));
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ROIDS.to_string(),
+            msg: to_json_binary(&cw20::Cw20ExecuteMsg::Transfer {
+                recipient: "asteroid".to_string(),
+                amount: Uint128::new(1000),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+}
+
+#[test]
+fn resolve_burn_destination_per_mode() {
+    let tf_denom = format!("factory/{MOCK_CONTRACT_ADDR}/uroids");
+    let mut deps = mock_dependencies(&[coin(1000, "uroids"), coin(1000, &tf_denom)]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(1000));
+
+    let set = |deps: DepsMut, roids_token: AssetInfo, mode, tokenfactory_burn| {
+        CONFIG
+            .update(deps.storage, |mut cfg| -> StdResult<_> {
+                cfg.roids_token = roids_token;
+                cfg.distribution_mode = mode;
+                cfg.tokenfactory_burn = tokenfactory_burn;
+                Ok(cfg)
+            })
+            .unwrap();
+    };
+    let check = |deps: DepsMut, destination: &str, source| {
+        let res: BurnDestinationResponse = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ResolveBurnDestination {},
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res,
+            BurnDestinationResponse {
+                destination: destination.to_string(),
+                source,
+            }
+        );
+
+        // The distribution reports the same destination
+        let res = execute(
+            deps,
+            mock_env(),
+            mock_info(MOCK_CONTRACT_ADDR, &[]),
+            ExecuteMsg::DistributeAstro {},
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("destination", destination)));
+        let data: DistributeResponseData = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(data.destination, destination);
+    };
+
+    check(
+        deps.as_mut(),
+        "asteroid",
+        BurnDestinationSource::AsteroidContract,
+    );
+
+    set(deps.as_mut(), roids(), DistributionMode::Burn, false);
+    check(deps.as_mut(), ROIDS, BurnDestinationSource::Burn);
+
+    set(
+        deps.as_mut(),
+        native_asset_info("uroids".to_string()),
+        DistributionMode::Burn,
+        false,
+    );
+    check(deps.as_mut(), "uroids", BurnDestinationSource::Burn);
+
+    set(
+        deps.as_mut(),
+        native_asset_info("uroids".to_string()),
+        DistributionMode::IbcTransfer {
+            channel: "channel-0".to_string(),
+            receiver: "cosmos1burn".to_string(),
+            timeout_secs: 600,
+            memo: "{}".to_string(),
+        },
+        false,
+    );
+    check(
+        deps.as_mut(),
+        "cosmos1burn",
+        BurnDestinationSource::IbcReceiver,
+    );
+
+    // A TokenFactory burn takes precedence over the distribution mode
+    set(
+        deps.as_mut(),
+        native_asset_info(tf_denom.clone()),
+        DistributionMode::BridgeToHub,
+        true,
+    );
+    check(
+        deps.as_mut(),
+        &tf_denom,
+        BurnDestinationSource::TokenfactoryBurn,
+    );
+}
+
+#[test]
+fn auto_pause_after_failed_collects() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: None,
+            collect_cooldown: None,
+            swap_deadline_secs: None,
+            auto_pause_threshold: Some(2),
+            burn_every_n_collects: None,
+            dust_threshold: None,
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: None,
+            lenient_burn: None,
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: None,
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: None,
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+
+    let collect = ExecuteMsg::Collect {
+        assets: vec![],
+        asset_type_filter: None,
+        expected_routes: None,
+        deadline: None,
+        min_roids_out: None,
+    };
+    let failed_swap = Reply {
+        id: SWAP_REPLY_ID,
+        result: SubMsgResult::Err("swap failed".to_string()),
+    };
+
+    // Several failed swaps within the same collect count once
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        collect.clone(),
+    )
+    .unwrap();
+    reply(deps.as_mut(), mock_env(), failed_swap.clone()).unwrap();
+    let res = reply(deps.as_mut(), mock_env(), failed_swap.clone()).unwrap();
+    assert!(res.events.is_empty());
+
+    // A successful collect resets the streak
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        collect.clone(),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        collect.clone(),
+    )
+    .unwrap();
+    let res = reply(deps.as_mut(), mock_env(), failed_swap.clone()).unwrap();
+    assert!(res.events.is_empty());
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        collect.clone(),
+    )
+    .unwrap();
+    let res = reply(deps.as_mut(), mock_env(), failed_swap).unwrap();
+    assert_eq!(res.events[0].ty, "auto_paused");
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        collect.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Paused {});
+
+    // Only the owner can resume collects
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::SetPaused { paused: false },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::SetPaused { paused: false },
+    )
+    .unwrap();
+    execute(deps.as_mut(), mock_env(), mock_info("keeper", &[]), collect).unwrap();
+
+    // Successful swaps are recorded under the same ID
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: SWAP_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn query_direct_pairs() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let uluna = native_asset_info("uluna".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    deps.querier
+        .with_pair(&[uluna.clone(), roids()], "luna-roids");
+
+    let res: DirectPairsResponse = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::DirectPairs {
+                assets: vec![uluna.clone(), uatom, roids()],
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.pairs, vec![(uluna, Addr::unchecked("luna-roids"))]);
+}
+
+#[test]
+fn simulate_collect() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee"), coin(100, "ujunk")]);
+    instantiate_maker(deps.as_mut());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uempty = native_asset_info("uempty".to_string());
+    let ujunk = native_asset_info("ujunk".to_string());
+    let token = token_asset_info(Addr::unchecked("fee-token"));
+    deps.querier.with_pair_rate(
+        &[ufee.clone(), roids()],
+        "fee-roids",
+        Decimal::from_ratio(2u128, 1u128),
+    );
+    deps.querier
+        .with_pair(&[uempty.clone(), roids()], "empty-roids");
+    deps.querier
+        .with_pair(&[token.clone(), roids()], "token-roids");
+    deps.querier
+        .with_token_balance("fee-token", MOCK_CONTRACT_ADDR, Uint128::new(30));
+
+    let simulate = |deps: Deps, assets: Vec<(AssetInfo, Option<Uint128>)>| {
+        query(
+            deps,
+            mock_env(),
+            QueryMsg::SimulateCollect {
+                assets: assets
+                    .into_iter()
+                    .map(|(info, limit)| AssetWithLimit {
+                        info,
+                        limit,
+                        limit_semantics: None,
+                    })
+                    .collect(),
+            },
+        )
+        .map(|res| from_json::<SimulateCollectResponse>(res).unwrap())
+    };
+
+    // Limits cap the swapped amounts and assets without a balance are left out
+    let res = simulate(
+        deps.as_ref(),
+        vec![
+            (ufee.clone(), None),
+            (uempty, None),
+            (token.clone(), Some(Uint128::new(20))),
+        ],
+    )
+    .unwrap();
+    assert_eq!(res.roids_amount, Uint128::new(220));
+    assert_eq!(
+        res.assets,
+        vec![
+            AssetSimulation {
+                asset: Asset {
+                    info: ufee.clone(),
+                    amount: Uint128::new(100),
+                },
+                roids_amount: Uint128::new(200),
+            },
+            AssetSimulation {
+                asset: Asset {
+                    info: token,
+                    amount: Uint128::new(20),
+                },
+                roids_amount: Uint128::new(20),
+            },
+        ]
+    );
+
+    // A fee token without a route fails the simulation like it would fail the collect
+    simulate(deps.as_ref(), vec![(ufee, None), (ujunk, None)]).unwrap_err();
+}
+
+#[test]
+fn simulate_collect_follows_bridges() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    deps.querier.with_pair_rate(
+        &[ufee.clone(), uatom.clone()],
+        "fee-atom",
+        Decimal::from_ratio(2u128, 1u128),
+    );
+    deps.querier.with_pair_rate(
+        &[uatom.clone(), roids()],
+        "atom-roids",
+        Decimal::from_ratio(3u128, 1u128),
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateBridges {
+            add: Some(vec![(ufee.clone(), uatom)]),
+            remove: None,
+        },
+    )
+    .unwrap();
+
+    // 100 ufee -> 200 uatom -> 600 ROIDS, not just the first hop output
+    let res: SimulateCollectResponse = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SimulateCollect {
+                assets: vec![
+                    AssetWithLimit {
+                        info: ufee.clone(),
+                        limit: None,
+                        limit_semantics: None,
+                    },
+                    AssetWithLimit {
+                        info: roids(),
+                        limit: None,
+                        limit_semantics: None,
+                    },
+                ],
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.roids_amount, Uint128::new(600));
+    assert_eq!(
+        res.assets,
+        vec![AssetSimulation {
+            asset: Asset {
+                info: ufee,
+                amount: Uint128::new(100),
+            },
+            roids_amount: Uint128::new(600),
+        }]
+    );
+}
+
+#[test]
+fn consolidate_dust() {
+    let mut deps = mock_dependencies(&[coin(5, "ufee"), coin(50, "ubig")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let ubig = native_asset_info("ubig".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+    deps.querier
+        .with_pair(&[ubig.clone(), roids()], "big-roids");
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::ConsolidateDust {
+            assets: vec![ufee.clone()],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::DustConsolidationDisabled {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: None,
+            collect_cooldown: None,
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: None,
+            dust_threshold: Some(Uint128::new(10)),
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: None,
+            lenient_burn: None,
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: None,
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: None,
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::ConsolidateDust {
+            assets: vec![ufee.clone(), ubig.clone()],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NotDust {
+            asset: ubig,
+            balance: Uint128::new(50),
+            threshold: Uint128::new(10),
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::ConsolidateDust { assets: vec![ufee] },
+    )
+    .unwrap();
+    // One swap followed by the distribution
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(res.messages[0].id, SWAP_REPLY_ID);
+    assert!(res.attributes.contains(&attr("consolidated_count", "1")));
+    assert!(res.attributes.contains(&attr("consolidated", "5ufee")));
+}
+
+#[test]
+fn consolidate_dust_guards() {
+    let mut deps = mock_dependencies(&[coin(5, "ufee"), coin(3, "uatom")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    let start = mock_env().block.time.seconds();
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+    deps.querier
+        .with_pair(&[uatom.clone(), roids()], "atom-roids");
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: None,
+            collect_cooldown: None,
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: None,
+            dust_threshold: Some(Uint128::new(10)),
+            keeper_commit_window_secs: Some(60),
+            min_receive_buffer_bps: None,
+            lenient_burn: None,
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: Some(300),
+            burn_retry_limit: None,
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: None,
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+    let consolidate_at = |deps: DepsMut, sender: &str, ts: u64| {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(ts);
+        execute(
+            deps,
+            env,
+            mock_info(sender, &[]),
+            ExecuteMsg::ConsolidateDust {
+                assets: vec![ufee.clone(), uatom.clone()],
+            },
+        )
+    };
+
+    // Pools get time to stabilize after an incident
+    for paused in [true, false] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::SetPaused { paused },
+        )
+        .unwrap();
+    }
+    let err = consolidate_at(deps.as_mut(), "keeper1", start + 299).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::PostUnpauseGrace {
+            resume_ts: start + 300
+        }
+    );
+
+    // A committed keeper swaps exclusively
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(start + 300);
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("keeper1", &[]),
+        ExecuteMsg::CommitCollect {},
+    )
+    .unwrap();
+    let err = consolidate_at(deps.as_mut(), "keeper2", start + 300).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CollectReserved {
+            keeper: Addr::unchecked("keeper1"),
+            expires_at: start + 360,
+        }
+    );
+
+    // Auctioned dust is left for the bidders
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::StartAuction {
+            asset: Asset {
+                info: ufee.clone(),
+                amount: Uint128::new(5),
+            },
+            start_price: Decimal::percent(200),
+            end_price: Decimal::one(),
+            duration_secs: 100,
+        },
+    )
+    .unwrap();
+    let res = consolidate_at(deps.as_mut(), "keeper1", start + 300).unwrap();
+    assert!(res.attributes.contains(&attr("skipped_auctioned", "ufee")));
+    assert!(res.attributes.contains(&attr("consolidated", "3uatom")));
+    assert!(res.attributes.contains(&attr("consolidated_count", "1")));
+    assert_eq!(
+        KEEPER_COMMITMENT.may_load(deps.as_ref().storage).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn consolidate_dust_bridges() {
+    let mut deps = mock_dependencies(&[coin(5, "ufee"), coin(3, "uatom")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    // The Maker already holds some of the bridge token
+    deps.querier
+        .with_token_balance("bridge-token", MOCK_CONTRACT_ADDR, Uint128::new(1000));
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    let uluna = native_asset_info("uluna".to_string());
+    let bridge = token_asset_info(Addr::unchecked("bridge-token"));
+    deps.querier
+        .with_pair(&[ufee.clone(), bridge.clone()], "fee-bridge");
+    deps.querier
+        .with_pair(&[bridge.clone(), roids()], "bridge-roids");
+    deps.querier
+        .with_pair(&[uatom.clone(), uluna.clone()], "atom-luna");
+    deps.querier
+        .with_pair(&[uluna.clone(), roids()], "luna-roids");
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateBridges {
+            add: Some(vec![(ufee.clone(), bridge.clone())]),
+            remove: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: None,
+            collect_cooldown: Some(60),
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: None,
+            dust_threshold: Some(Uint128::new(10)),
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: None,
+            lenient_burn: None,
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: None,
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: None,
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+
+    let consolidate = ExecuteMsg::ConsolidateDust {
+        assets: vec![ufee, uatom],
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        consolidate.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 3);
+    // The fee token takes its bridge while the asset without one takes the default bridge
+    let contracts: Vec<_> = res.messages[..2]
+        .iter()
+        .map(|msg| match &msg.msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => contract_addr.clone(),
+            _ => panic!("Unexpected message"),
+        })
+        .collect();
+    assert_eq!(contracts, vec!["fee-bridge", "atom-luna"]);
+    let mut bridge_assets = match &res.messages[2].msg {
+        CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => match from_json(msg).unwrap() {
+            ExecuteMsg::SwapBridgeAssets { assets, depth, .. } => {
+                assert_eq!(depth, 0);
+                assets
+            }
+            _ => panic!("Unexpected message"),
+        },
+        _ => panic!("Unexpected message"),
+    };
+    bridge_assets.sort_by_key(|asset| asset.to_string());
+    assert_eq!(bridge_assets, vec![bridge.clone(), uluna]);
+
+    // Consolidations are subject to the collect cooldown
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        consolidate,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Cooldown {
+            next_collect_ts: mock_env().block.time.seconds() + 60
+        }
+    );
+
+    // The dust swap returned 5 bridge tokens, only those are swapped further
+    deps.querier
+        .with_token_balance("bridge-token", MOCK_CONTRACT_ADDR, Uint128::new(1005));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::SwapBridgeAssets {
+            assets: vec![bridge],
+            depth: 0,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "bridge-token".to_string(),
+            msg: to_json_binary(&cw20::Cw20ExecuteMsg::Send {
+                contract: "bridge-roids".to_string(),
+                amount: Uint128::new(5),
+                msg: to_json_binary(&astroport::pair::Cw20HookMsg::Swap {
+                    ask_asset_info: Some(roids()),
+                    belief_price: None,
+                    max_spread: Some(Decimal::percent(5)),
+                    to: None,
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+}
+
+fn change_config(mut deps: DepsMut, msg: ExecuteMsg) -> Result<Response, ContractError> {
+    execute(deps.branch(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = env
+        .block
+        .time
+        .plus_seconds(DEFAULT_CONFIG_CHANGE_DELAY_SECS);
+    execute(
+        deps,
+        env,
+        mock_info(OWNER, &[]),
+        ExecuteMsg::ApplyConfigChange {},
+    )
+}
+
+fn change_roids(deps: DepsMut, roids_token: AssetInfo) -> Result<Response, ContractError> {
+    change_config(
+        deps,
+        ExecuteMsg::ProposeConfigChange {
+            factory_contract: None,
+            roids_token: Some(roids_token),
+            asteroid_contract: None,
+            config_change_delay_secs: None,
+            router_contract: None,
+            second_receiver_params: None,
+            dev_fund_config: None,
+            distribution_mode: None,
+            bridge_call: None,
+            external_dexes: None,
+        },
+    )
+}
+
+fn set_router(deps: DepsMut, router: &str) -> Result<Response, ContractError> {
+    change_config(
+        deps,
+        ExecuteMsg::ProposeConfigChange {
+            factory_contract: None,
+            roids_token: None,
+            asteroid_contract: None,
+            config_change_delay_secs: None,
+            router_contract: Some(router.to_string()),
+            second_receiver_params: None,
+            dev_fund_config: None,
+            distribution_mode: None,
+            bridge_call: None,
+            external_dexes: None,
+        },
+    )
+}
+
+fn update_cooldown(deps: DepsMut, env: Env, collect_cooldown: u64) {
+    execute(
+        deps,
+        env,
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: None,
+            collect_cooldown: Some(collect_cooldown),
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: None,
+            dust_threshold: None,
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: None,
+            lenient_burn: None,
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: None,
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: None,
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+}
+
+fn collect_at(deps: DepsMut, ts: u64) -> Result<Response, ContractError> {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(ts);
+
+    execute(
+        deps,
+        env,
+        mock_info("keeper", &[]),
+        ExecuteMsg::Collect {
+            assets: vec![],
+            asset_type_filter: None,
+            expected_routes: None,
+            deadline: None,
+            min_roids_out: None,
+        },
+    )
+}
+
+#[test]
+fn lengthening_cooldown_keeps_current_window() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    let start = mock_env().block.time.seconds();
+
+    update_cooldown(deps.as_mut(), mock_env(), 60);
+    collect_at(deps.as_mut(), start).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(start + 10);
+    update_cooldown(deps.as_mut(), env, 600);
+
+    // The keeper scheduled for the old window is not locked out
+    collect_at(deps.as_mut(), start + 60).unwrap();
+
+    // The following window uses the new cooldown
+    let err = collect_at(deps.as_mut(), start + 120).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Cooldown {
+            next_collect_ts: start + 660
+        }
+    );
+    collect_at(deps.as_mut(), start + 660).unwrap();
+}
+
+#[test]
+fn collect_status() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    let start = mock_env().block.time.seconds();
+
+    let status_at = |deps: Deps, ts: u64| -> CollectStatusResponse {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(ts);
+        from_json(query(deps, env, QueryMsg::CollectStatus {}).unwrap()).unwrap()
+    };
+
+    // Without a cooldown a collect is always possible
+    assert_eq!(
+        status_at(deps.as_ref(), start),
+        CollectStatusResponse {
+            last_collect_ts: start,
+            next_allowed_ts: start,
+            can_collect: true,
+        }
+    );
+
+    update_cooldown(deps.as_mut(), mock_env(), 60);
+    collect_at(deps.as_mut(), start + 10).unwrap();
+    assert_eq!(
+        status_at(deps.as_ref(), start + 20),
+        CollectStatusResponse {
+            last_collect_ts: start + 10,
+            next_allowed_ts: start + 70,
+            can_collect: false,
+        }
+    );
+    assert!(status_at(deps.as_ref(), start + 70).can_collect);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::SetPaused { paused: true },
+    )
+    .unwrap();
+    assert!(!status_at(deps.as_ref(), start + 70).can_collect);
+}
+
+#[test]
+fn shortening_cooldown_keeps_current_window() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    let start = mock_env().block.time.seconds();
+
+    update_cooldown(deps.as_mut(), mock_env(), 600);
+    collect_at(deps.as_mut(), start).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(start + 10);
+    update_cooldown(deps.as_mut(), env.clone(), 60);
+    // Repeated changes don't move the end of the current window either
+    update_cooldown(deps.as_mut(), env, 30);
+
+    let err = collect_at(deps.as_mut(), start + 100).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Cooldown {
+            next_collect_ts: start + 600
+        }
+    );
+    collect_at(deps.as_mut(), start + 600).unwrap();
+
+    // The following window uses the new cooldown
+    collect_at(deps.as_mut(), start + 630).unwrap();
+}
+
+#[test]
+fn asset_type_filter() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let token = token_asset_info(Addr::unchecked("fee-token"));
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+    deps.querier
+        .with_pair(&[token.clone(), roids()], "token-roids");
+    deps.querier
+        .with_token_balance("fee-token", MOCK_CONTRACT_ADDR, Uint128::new(30));
+
+    // Returns the contracts called by the swaps of the collect
+    let mut collect = |filter: Option<AssetTypeFilter>| {
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("keeper", &[]),
+            ExecuteMsg::Collect {
+                assets: [&ufee, &token]
+                    .into_iter()
+                    .map(|info| AssetWithLimit {
+                        info: info.clone(),
+                        limit: None,
+                        limit_semantics: None,
+                    })
+                    .collect(),
+                asset_type_filter: filter,
+                expected_routes: None,
+                deadline: None,
+                min_roids_out: None,
+            },
+        )
+        .unwrap();
+        res.messages
+            .into_iter()
+            .filter(|msg| msg.id == SWAP_REPLY_ID)
+            .map(|msg| match msg.msg {
+                CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => contract_addr,
+                _ => panic!("unexpected message"),
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        collect(Some(AssetTypeFilter::NativeOnly)),
+        vec!["fee-roids"]
+    );
+    // CW20 tokens are sent to their pair
+    assert_eq!(collect(Some(AssetTypeFilter::Cw20Only)), vec!["fee-token"]);
+    assert_eq!(
+        collect(Some(AssetTypeFilter::Both)),
+        vec!["fee-roids", "fee-token"]
+    );
+    assert_eq!(collect(None), vec!["fee-roids", "fee-token"]);
+}
+
+#[test]
+fn max_collect_now() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee"), coin(100, "ujunk"), coin(100, "uwide")]);
+    instantiate_maker(deps.as_mut());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uwide = native_asset_info("uwide".to_string());
+    let token = token_asset_info(Addr::unchecked("fee-token"));
+    deps.querier.with_pair_rate(
+        &[ufee.clone(), roids()],
+        "fee-roids",
+        Decimal::from_ratio(2u128, 1u128),
+    );
+    deps.querier
+        .with_pair(&[token.clone(), roids()], "token-roids");
+    deps.querier
+        .with_token_balance("fee-token", MOCK_CONTRACT_ADDR, Uint128::new(30));
+    deps.querier
+        .with_pair_spread(&[uwide, roids()], "wide-roids", Decimal::percent(10));
+
+    // The CW20 fee token is discovered through its bridge
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateBridges {
+            add: Some(vec![(token.clone(), roids())]),
+            remove: None,
+        },
+    )
+    .unwrap();
+
+    // ujunk has no route and uwide exceeds the 5% max spread
+    let res: MaxCollectNowResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::MaxCollectNow {}).unwrap()).unwrap();
+    assert_eq!(res.roids_amount, Uint128::new(230));
+    assert_eq!(
+        res.assets,
+        vec![
+            Asset {
+                info: ufee.clone(),
+                amount: Uint128::new(100),
+            },
+            Asset {
+                info: token,
+                amount: Uint128::new(30),
+            },
+        ]
+    );
+
+    // A single collect swaps one asset and stays within the value budget of the window
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: None,
+            collect_cooldown: None,
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: None,
+            dust_threshold: None,
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: None,
+            lenient_burn: None,
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: None,
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: Some(Uint128::new(150)),
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: Some(1),
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+    let res: MaxCollectNowResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::MaxCollectNow {}).unwrap()).unwrap();
+    assert_eq!(res.roids_amount, Uint128::new(150));
+    assert_eq!(
+        res.assets,
+        vec![Asset {
+            info: ufee,
+            amount: Uint128::new(75),
+        }]
+    );
+}
+
+#[test]
+fn keeper_commitment() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    let start = mock_env().block.time.seconds();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper1", &[]),
+        ExecuteMsg::CommitCollect {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::KeeperCommitDisabled {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: None,
+            collect_cooldown: None,
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: None,
+            dust_threshold: None,
+            keeper_commit_window_secs: Some(60),
+            min_receive_buffer_bps: None,
+            lenient_burn: None,
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: None,
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: None,
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper1", &[]),
+        ExecuteMsg::CommitCollect {},
+    )
+    .unwrap();
+    let commitment: Option<KeeperCommitment> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::KeeperCommitment {}).unwrap())
+            .unwrap();
+    assert_eq!(
+        commitment,
+        Some(KeeperCommitment {
+            keeper: Addr::unchecked("keeper1"),
+            expires_at: start + 60,
+        })
+    );
+
+    let collect = ExecuteMsg::Collect {
+        assets: vec![],
+        asset_type_filter: None,
+        expected_routes: None,
+        deadline: None,
+        min_roids_out: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper2", &[]),
+        collect.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CollectReserved {
+            keeper: Addr::unchecked("keeper1"),
+            expires_at: start + 60,
+        }
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper1", &[]),
+        collect.clone(),
+    )
+    .unwrap();
+
+    // A lapsed commitment reopens the collect to everyone but can't be renewed by its keeper
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper1", &[]),
+        ExecuteMsg::CommitCollect {},
+    )
+    .unwrap();
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(start + 60);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper1", &[]),
+        ExecuteMsg::CommitCollect {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::CommitmentLapsed {});
+    execute(deps.as_mut(), env, mock_info("keeper2", &[]), collect).unwrap();
+}
+
+#[test]
+fn query_balances_with_zero() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    let query_balances = |include_zero| -> BalancesResponse {
+        from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Balances {
+                    assets: vec![ufee.clone(), uatom.clone()],
+                    include_zero,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+
+    let fee_balance = Asset {
+        info: ufee.clone(),
+        amount: Uint128::new(100),
+    };
+    assert_eq!(query_balances(None).balances, vec![fee_balance.clone()]);
+    assert_eq!(
+        query_balances(Some(true)).balances,
+        vec![
+            fee_balance,
+            Asset {
+                info: uatom.clone(),
+                amount: Uint128::zero(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn query_all_balances() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee"), coin(50, "uatom")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(20));
+    deps.querier
+        .with_token_balance("fee-token", MOCK_CONTRACT_ADDR, Uint128::new(30));
+    deps.querier
+        .with_token_balance("junk-token", MOCK_CONTRACT_ADDR, Uint128::zero());
+    deps.querier.with_pair(
+        &[token_asset_info(Addr::unchecked("fee-token")), roids()],
+        "fee-roids",
+    );
+    deps.querier.with_pair(
+        &[
+            token_asset_info(Addr::unchecked("junk-token")),
+            native_asset_info("uluna".to_string()),
+        ],
+        "junk-luna",
+    );
+
+    let query_all_balances = |start_after, limit| -> Vec<Asset> {
+        from_json::<BalancesResponse>(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::AllBalances { start_after, limit },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+        .balances
+    };
+
+    // Zero balances are left out
+    assert_eq!(
+        query_all_balances(None, Some(2)),
+        vec![
+            Asset {
+                info: token_asset_info(Addr::unchecked("fee-token")),
+                amount: Uint128::new(30),
+            },
+            Asset {
+                info: roids(),
+                amount: Uint128::new(20),
+            },
+        ]
+    );
+    assert_eq!(
+        query_all_balances(Some(roids()), None),
+        vec![
+            Asset {
+                info: native_asset_info("uatom".to_string()),
+                amount: Uint128::new(50),
+            },
+            Asset {
+                info: native_asset_info("ufee".to_string()),
+                amount: Uint128::new(100),
+            },
+        ]
+    );
+}
+
+#[test]
+fn min_receive_buffer() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    // The pool was skewed earlier in the block
+    let ufee = native_asset_info("ufee".to_string());
+    deps.querier
+        .with_pair_spread(&[ufee.clone(), roids()], "fee-roids", Decimal::percent(10));
+
+    let update_buffer = |min_receive_buffer_bps| ExecuteMsg::UpdateConfig {
+        basic_asset: None,
+        max_spread: None,
+        collect_cooldown: None,
+        swap_deadline_secs: None,
+        auto_pause_threshold: None,
+        burn_every_n_collects: None,
+        dust_threshold: None,
+        keeper_commit_window_secs: None,
+        min_receive_buffer_bps: Some(min_receive_buffer_bps),
+        lenient_burn: None,
+        max_bridges: None,
+        strict_collect: None,
+        post_unpause_grace_secs: None,
+        burn_retry_limit: None,
+        learn_routes: None,
+        keeper_fee_bps: None,
+        guardian: None,
+        seize_delay_secs: None,
+        min_collect_amount: None,
+        quarantine_failed_swaps: None,
+        max_collect_value_per_window: None,
+        bridges_execution_max_depth: None,
+        tokenfactory_burn: None,
+        max_assets_per_tx: None,
+        belief_price_source: None,
+        on_spread_exceeded: None,
+        reserve_amount: None,
+        auction_fallback: None,
+        operator: None,
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_buffer(101),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::IncorrectMinReceiveBuffer { max: 100 });
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_buffer(50),
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Collect {
+            assets: vec![AssetWithLimit {
+                info: ufee.clone(),
+                limit: None,
+                limit_semantics: None,
+            }],
+            asset_type_filter: None,
+            expected_routes: None,
+            deadline: None,
+            min_roids_out: None,
+        },
+    )
+    .unwrap();
+
+    // The buffer doesn't replace the max spread with a belief price taken from the skewed pool
+    let (belief_price, max_spread) = match &res.messages[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => match from_json(msg).unwrap() {
+            PairExecuteMsg::Swap {
+                belief_price,
+                max_spread,
+                ..
+            } => (belief_price, max_spread),
+            _ => panic!("unexpected pair message"),
+        },
+        _ => panic!("unexpected message"),
+    };
+    assert_eq!(belief_price, None);
+    assert_eq!(max_spread, Some(Decimal::percent(5)));
+
+    // The pair reverts the swap
+    assert_max_spread(
+        belief_price,
+        max_spread,
+        Uint128::new(100),
+        Uint128::new(90),
+        Uint128::new(10),
+    )
+    .unwrap_err();
+}
+
+#[test]
+fn blocked_burn() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(500));
+
+    let blocked_burn = Reply {
+        id: BURN_REPLY_ID,
+        result: SubMsgResult::Err("transfers are paused".to_string()),
+    };
+
+    // Hard failures are surfaced by default
+    let err = reply(deps.as_mut(), mock_env(), blocked_burn.clone()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::BurnBlocked {
+            reason: "transfers are paused".to_string()
+        }
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: None,
+            collect_cooldown: None,
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: None,
+            dust_threshold: None,
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: None,
+            lenient_burn: Some(true),
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: None,
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: None,
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+
+    // Only the burned amount is pending, not the whole balance
+    BURNING_ROIDS
+        .save(deps.as_mut().storage, &Uint128::new(300))
+        .unwrap();
+    let res = reply(deps.as_mut(), mock_env(), blocked_burn).unwrap();
+    assert!(res
+        .attributes
+        .contains(&attr("burn_blocked", "transfers are paused")));
+    assert!(res.attributes.contains(&attr("pending_roids", "300")));
+    assert!(!BURNING_ROIDS.exists(deps.as_ref().storage));
+    let pending: Option<Uint128> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::PendingBurn {}).unwrap()).unwrap();
+    assert_eq!(pending, Some(Uint128::new(300)));
+
+    // A later successful burn clears the pending ROIDS
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: BURN_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )
+    .unwrap();
+    let pending: Option<Uint128> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::PendingBurn {}).unwrap()).unwrap();
+    assert_eq!(pending, None);
+}
+
+#[test]
+fn simulate_with_config() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uluna = native_asset_info("uluna".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), uluna.clone()], "fee-luna");
+    deps.querier.with_pair(&[uluna, roids()], "luna-roids");
+    deps.querier.with_pair_rate(
+        &[ufee.clone(), uatom.clone()],
+        "fee-atom",
+        Decimal::from_ratio(3u128, 1u128),
+    );
+    deps.querier
+        .with_pair(&[uatom.clone(), roids()], "atom-roids");
+
+    let assets = vec![AssetWithLimit {
+        info: ufee,
+        limit: None,
+        limit_semantics: None,
+    }];
+    let simulate = |overrides: ConfigOverrides| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SimulateWithConfig {
+                overrides,
+                assets: assets.clone(),
+            },
+        )
+        .map(|res| {
+            from_json::<SimulateCollectResponse>(res)
+                .unwrap()
+                .roids_amount
+        })
+    };
+
+    assert_eq!(simulate(ConfigOverrides::default()), Ok(Uint128::new(100)));
+    assert_eq!(
+        simulate(ConfigOverrides {
+            default_bridge: Some(uatom),
+            max_spread: None,
+        }),
+        Ok(Uint128::new(300))
+    );
+    assert_eq!(
+        simulate(ConfigOverrides {
+            default_bridge: None,
+            max_spread: Some(Decimal::percent(60)),
+        }),
+        Err(ContractError::IncorrectMaxSpread {})
+    );
+
+    // Nothing was persisted
+    let config: ConfigResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(
+        config.default_bridge,
+        Some(native_asset_info("uluna".to_string()))
+    );
+}
+
+#[test]
+fn recent_collectors() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(700));
+    let start = mock_env().block.time.seconds();
+
+    let recent_collectors = |deps: Deps, limit| -> Vec<CollectorRecord> {
+        from_json(query(deps, mock_env(), QueryMsg::RecentCollectors { limit }).unwrap()).unwrap()
+    };
+    assert_eq!(recent_collectors(deps.as_ref(), None), vec![]);
+
+    for (caller, ts) in [("keeper1", start), ("keeper2", start + 5)] {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(ts);
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(caller, &[]),
+            ExecuteMsg::Collect {
+                assets: vec![],
+                asset_type_filter: None,
+                expected_routes: None,
+                deadline: None,
+                min_roids_out: None,
+            },
+        )
+        .unwrap();
+    }
+
+    assert_eq!(
+        recent_collectors(deps.as_ref(), None),
+        vec![
+            CollectorRecord {
+                caller: Addr::unchecked("keeper2"),
+                timestamp: start + 5,
+                roids_realized: Uint128::new(700),
+            },
+            CollectorRecord {
+                caller: Addr::unchecked("keeper1"),
+                timestamp: start,
+                roids_realized: Uint128::new(700),
+            },
+        ]
+    );
+    assert_eq!(recent_collectors(deps.as_ref(), Some(1)).len(), 1);
+}
+
+#[test]
+fn collect_deadline() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    deps.querier.with_pair(
+        &[native_asset_info("ufee".to_string()), roids()],
+        "fee-roids",
+    );
+
+    let env = mock_env();
+    let collect = |deadline| ExecuteMsg::Collect {
+        assets: vec![AssetWithLimit {
+            info: native_asset_info("ufee".to_string()),
+            limit: None,
+            limit_semantics: None,
+        }],
+        asset_type_filter: None,
+        expected_routes: None,
+        deadline: Some(deadline),
+        min_roids_out: None,
+    };
+
+    let deadline = env.block.time.seconds() - 1;
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &[]),
+        collect(deadline),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::CollectDeadlineExceeded { deadline });
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &[]),
+        collect(env.block.time.seconds()),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 2);
+}
+
+#[test]
+fn collect_deadline_bound() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    deps.querier.with_pair(
+        &[native_asset_info("ufee".to_string()), roids()],
+        "fee-roids",
+    );
+
+    let update_deadline = |swap_deadline_secs| ExecuteMsg::UpdateConfig {
+        basic_asset: None,
+        max_spread: None,
+        collect_cooldown: None,
+        swap_deadline_secs: Some(swap_deadline_secs),
+        auto_pause_threshold: None,
+        burn_every_n_collects: None,
+        dust_threshold: None,
+        keeper_commit_window_secs: None,
+        min_receive_buffer_bps: None,
+        lenient_burn: None,
+        max_bridges: None,
+        strict_collect: None,
+        post_unpause_grace_secs: None,
+        burn_retry_limit: None,
+        learn_routes: None,
+        keeper_fee_bps: None,
+        guardian: None,
+        seize_delay_secs: None,
+        min_collect_amount: None,
+        quarantine_failed_swaps: None,
+        max_collect_value_per_window: None,
+        bridges_execution_max_depth: None,
+        tokenfactory_burn: None,
+        max_assets_per_tx: None,
+        belief_price_source: None,
+        on_spread_exceeded: None,
+        reserve_amount: None,
+        auction_fallback: None,
+        operator: None,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_deadline(60),
+    )
+    .unwrap();
+
+    let env = mock_env();
+    let now = env.block.time.seconds();
+    let collect = |deadline| ExecuteMsg::Collect {
+        assets: vec![AssetWithLimit {
+            info: native_asset_info("ufee".to_string()),
+            limit: None,
+            limit_semantics: None,
+        }],
+        asset_type_filter: None,
+        expected_routes: None,
+        deadline,
+        min_roids_out: None,
+    };
+
+    // The deadline must be set by the caller, the contract can't tell how long the tx waited
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &[]),
+        collect(None),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::MissingCollectDeadline {});
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &[]),
+        collect(Some(now + 61)),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CollectDeadlineTooFar {
+            deadline: now + 61,
+            max: now + 60,
+        }
+    );
+
+    // A collect signed with the max deadline goes through until the deadline passes
+    let mut late_env = env.clone();
+    late_env.block.time = env.block.time.plus_seconds(61);
+    let err = execute(
+        deps.as_mut(),
+        late_env,
+        mock_info("keeper", &[]),
+        collect(Some(now + 60)),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CollectDeadlineExceeded { deadline: now + 60 }
+    );
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &[]),
+        collect(Some(now + 60)),
+    )
+    .unwrap();
+
+    // Zero lifts the bound
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_deadline(0),
+    )
+    .unwrap();
+    let config: ConfigResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config.swap_deadline_secs, None);
+    execute(deps.as_mut(), env, mock_info("keeper", &[]), collect(None)).unwrap();
+}
+
+#[test]
+fn chunked_collect() {
+    let mut deps = mock_dependencies(&[coin(100, "uatom"), coin(100, "ufee"), coin(100, "uluna")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    let assets: Vec<_> = ["uatom", "ufee", "uluna"]
+        .into_iter()
+        .map(|denom| {
+            let info = native_asset_info(denom.to_string());
+            deps.querier
+                .with_pair(&[info.clone(), roids()], &format!("{denom}-roids"));
+            AssetWithLimit {
+                info,
+                limit: None,
+                limit_semantics: None,
+            }
+        })
+        .collect();
+
+    let update_config = ExecuteMsg::UpdateConfig {
+        basic_asset: None,
+        max_spread: None,
+        collect_cooldown: None,
+        swap_deadline_secs: None,
+        auto_pause_threshold: None,
+        burn_every_n_collects: None,
+        dust_threshold: None,
+        keeper_commit_window_secs: None,
+        min_receive_buffer_bps: None,
+        lenient_burn: None,
+        max_bridges: None,
+        strict_collect: None,
+        post_unpause_grace_secs: None,
+        burn_retry_limit: None,
+        learn_routes: None,
+        keeper_fee_bps: None,
+        guardian: None,
+        seize_delay_secs: None,
+        min_collect_amount: None,
+        quarantine_failed_swaps: None,
+        max_collect_value_per_window: None,
+        bridges_execution_max_depth: None,
+        tokenfactory_burn: None,
+        max_assets_per_tx: Some(2),
+        belief_price_source: None,
+        on_spread_exceeded: None,
+        reserve_amount: None,
+        auction_fallback: None,
+        operator: None,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_config,
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::TuneRisk {
+            max_spread: Decimal::percent(5),
+            collect_cooldown: 60,
+        },
+    )
+    .unwrap();
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(60);
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::ContinueCollect {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoPendingCollect {});
+
+    // The first two assets are swapped, the last one is left for a continuation
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Collect {
+            assets,
+            asset_type_filter: None,
+            expected_routes: None,
+            deadline: None,
+            min_roids_out: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 3);
+    assert!(res.attributes.contains(&attr("pending_assets", "1")));
+
+    // Continuations aren't subject to the cooldown
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::ContinueCollect {},
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "uluna-roids".to_string(),
+            msg: to_json_binary(&PairExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: native_asset_info("uluna".to_string()),
+                    amount: Uint128::new(100),
+                },
+                ask_asset_info: Some(roids()),
+                belief_price: None,
+                max_spread: Some(Decimal::percent(5)),
+                to: None,
+            })
+            .unwrap(),
+            funds: vec![coin(100, "uluna")],
+        })
+    );
+    assert!(!res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "pending_assets"));
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::ContinueCollect {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoPendingCollect {});
+}
+
+#[test]
+fn cooldown_exempt() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::TuneRisk {
+            max_spread: Decimal::percent(5),
+            collect_cooldown: 60,
+        },
+    )
+    .unwrap();
+
+    let update_exempt = ExecuteMsg::UpdateCooldownExempt {
+        add: Some(vec!["dao_bot".to_string()]),
+        remove: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("dao_bot", &[]),
+        update_exempt.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_exempt,
+    )
+    .unwrap();
+    let exempt: Vec<Addr> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::CooldownExempt {}).unwrap()).unwrap();
+    assert_eq!(exempt, vec![Addr::unchecked("dao_bot")]);
+
+    let collect = ExecuteMsg::Collect {
+        assets: vec![],
+        asset_type_filter: None,
+        expected_routes: None,
+        deadline: None,
+        min_roids_out: None,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        collect.clone(),
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        collect.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Cooldown { .. }));
+
+    // The exempt caller collects during the cooldown
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("dao_bot", &[]),
+        collect,
+    )
+    .unwrap();
+}
+
+#[test]
+fn blacklist() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    let ufee = native_asset_info("ufee".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+
+    let update_blacklist =
+        |add: Vec<AssetInfo>, remove: Vec<AssetInfo>| ExecuteMsg::UpdateBlacklist {
+            add: Some(add),
+            remove: Some(remove),
+        };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        update_blacklist(vec![ufee.clone()], vec![]),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_blacklist(vec![ufee.clone()], vec![]),
+    )
+    .unwrap();
+    let blacklist: Vec<AssetInfo> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Blacklist {}).unwrap()).unwrap();
+    assert_eq!(blacklist, vec![ufee.clone()]);
+
+    let collect = ExecuteMsg::Collect {
+        assets: vec![AssetWithLimit {
+            info: ufee.clone(),
+            limit: None,
+            limit_semantics: None,
+        }],
+        asset_type_filter: None,
+        expected_routes: None,
+        deadline: None,
+        min_roids_out: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        collect.clone(),
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+    assert!(res
+        .attributes
+        .contains(&attr("skipped_blacklisted", "ufee")));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_blacklist(vec![], vec![ufee.clone()]),
+    )
+    .unwrap();
+    let res = execute(deps.as_mut(), mock_env(), mock_info("keeper", &[]), collect).unwrap();
+    assert_eq!(res.messages.len(), 2);
+}
+
+#[test]
+fn external_dexes() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    let ufee = native_asset_info("ufee".to_string());
+    let uluna = native_asset_info("uluna".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    deps.querier
+        .with_pair(&[uluna.clone(), roids()], "luna-roids");
+
+    let collect = ExecuteMsg::Collect {
+        assets: vec![AssetWithLimit {
+            info: ufee.clone(),
+            limit: None,
+            limit_semantics: None,
+        }],
+        asset_type_filter: None,
+        expected_routes: None,
+        deadline: None,
+        min_roids_out: None,
+    };
+
+    // The fee token has no liquidity in the factory
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        collect.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::CannotSwap(ufee.clone()));
+
+    let set_external_dex = |ask_asset: AssetInfo| ExecuteMsg::ProposeConfigChange {
+        factory_contract: None,
+        roids_token: None,
+        asteroid_contract: None,
+        config_change_delay_secs: None,
+        router_contract: None,
+        second_receiver_params: None,
+        dev_fund_config: None,
+        distribution_mode: None,
+        bridge_call: None,
+        external_dexes: Some(vec![(
+            ufee.clone(),
+            ExternalDex {
+                adapter: "adapter".to_string(),
+                ask_asset,
+            },
+        )]),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        set_external_dex(uluna.clone()),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // The external DEX can't swap the fee token to itself
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        set_external_dex(ufee.clone()),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidExternalDex(ufee.clone()));
+
+    // External DEXes receive fee tokens, so they only apply after the config change delay
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        set_external_dex(uluna.clone()),
+    )
+    .unwrap();
+    let dexes: Vec<(String, ExternalDex)> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::ExternalDexes {}).unwrap()).unwrap();
+    assert_eq!(dexes, vec![]);
+
+    // The external DEX must swap to an asset with a route to ROIDS
+    let err = change_config(deps.as_mut(), set_external_dex(uatom)).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CannotSwap(native_asset_info("uatom".to_string()))
+    );
+
+    change_config(deps.as_mut(), set_external_dex(uluna.clone())).unwrap();
+    let dexes: Vec<(String, ExternalDex)> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::ExternalDexes {}).unwrap()).unwrap();
+    assert_eq!(
+        dexes,
+        vec![(
+            "ufee".to_string(),
+            ExternalDex {
+                adapter: "adapter".to_string(),
+                ask_asset: uluna.clone(),
+            }
+        )]
+    );
+
+    // The fee token is swapped through the adapter and its output is bridged to ROIDS
+    let res = execute(deps.as_mut(), mock_env(), mock_info("keeper", &[]), collect).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "adapter".to_string(),
+            msg: to_json_binary(&PairExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: ufee,
+                    amount: Uint128::new(100),
+                },
+                ask_asset_info: Some(uluna.clone()),
+                belief_price: None,
+                max_spread: Some(Decimal::percent(5)),
+                to: None,
+            })
+            .unwrap(),
+            funds: vec![coin(100, "ufee")],
+        })
+    );
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+            msg: to_json_binary(&ExecuteMsg::SwapBridgeAssets {
+                assets: vec![uluna],
+                depth: 0,
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+}
+
+#[test]
+fn collect_min_roids_out() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    deps.querier.with_pair(
+        &[native_asset_info("ufee".to_string()), roids()],
+        "fee-roids",
+    );
+
+    let collect = |assets| ExecuteMsg::Collect {
+        assets,
+        asset_type_filter: None,
+        expected_routes: None,
+        deadline: None,
+        min_roids_out: Some(Uint128::new(100)),
+    };
+
+    // Nothing to swap
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        collect(vec![]),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MinRoidsOutNotMet {
+            min: Uint128::new(100),
+            actual: Uint128::zero(),
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        collect(vec![AssetWithLimit {
+            info: native_asset_info("ufee".to_string()),
+            limit: None,
+            limit_semantics: None,
+        }]),
+    )
+    .unwrap();
+    assert_eq!(res.messages[1].id, COLLECT_SETTLED_REPLY_ID);
+    assert_eq!(res.messages[1].reply_on, ReplyOn::Success);
+
+    // The swap realizes less than the minimum
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(90));
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::DistributeAstro {},
+    )
+    .unwrap();
+    let settled = Reply {
+        id: COLLECT_SETTLED_REPLY_ID,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    let err = reply(deps.as_mut(), mock_env(), settled).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MinRoidsOutNotMet {
+            min: Uint128::new(100),
+            actual: Uint128::new(90),
+        }
+    );
+}
+
+#[test]
+fn collect_with_expected_routes() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uluna = native_asset_info("uluna".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), uatom.clone()], "fee-atom");
+    deps.querier
+        .with_pair(&[uatom.clone(), roids()], "atom-roids");
+    deps.querier
+        .with_pair(&[ufee.clone(), uluna.clone()], "fee-luna");
+    deps.querier
+        .with_pair(&[uluna.clone(), roids()], "luna-roids");
+
+    let update_bridge = |bridge: &AssetInfo| ExecuteMsg::UpdateBridges {
+        add: Some(vec![(ufee.clone(), bridge.clone())]),
+        remove: None,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_bridge(&uatom),
+    )
+    .unwrap();
+
+    // The keeper plans the collect with ufee routed through uatom
+    let collect = ExecuteMsg::Collect {
+        assets: vec![AssetWithLimit {
+            info: ufee.clone(),
+            limit: None,
+            limit_semantics: None,
+        }],
+        asset_type_filter: None,
+        expected_routes: Some(vec![(ufee.clone(), vec![uatom.clone(), roids()])]),
+        deadline: None,
+        min_roids_out: None,
+    };
+
+    // The bridge changes before the collect is executed
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_bridge(&uluna),
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        collect.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::RouteChanged(ufee.clone()));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_bridge(&uatom),
+    )
+    .unwrap();
+    execute(deps.as_mut(), mock_env(), mock_info("keeper", &[]), collect).unwrap();
+}
+
+#[test]
+fn flush_roids() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(300));
+
+    // ROIDS transferred to the Maker directly is distributed on demand
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::FlushRoids {},
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("roids_from_swaps", "0")));
+    assert!(res
+        .attributes
+        .contains(&attr("roids_received_directly", "300")));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::FlushRoids {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::FlushCooldown {
+            next_flush_ts: mock_env().block.time.seconds() + 60
+        }
+    );
+
+    // Directly received ROIDS is told apart from ROIDS swapped during a collect
+    let ufee = native_asset_info("ufee".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Collect {
+            assets: vec![AssetWithLimit {
+                info: ufee,
+                limit: None,
+                limit_semantics: None,
+            }],
+            asset_type_filter: None,
+            expected_routes: None,
+            deadline: None,
+            min_roids_out: None,
+        },
+    )
+    .unwrap();
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(400));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::DistributeAstro {},
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("roids_from_swaps", "100")));
+    assert!(res
+        .attributes
+        .contains(&attr("roids_received_directly", "300")));
+}
+
+#[test]
+fn concentrated_pair_max_spread() {
+    let mut deps = mock_dependencies(&[coin(1000, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    deps.querier
+        .with_pair_spread(&[ufee.clone(), roids()], "fee-roids", Decimal::permille(55));
+
+    // The 5.5% spread exceeds the default 5% max spread of an xyk pair
+    let res: MaxCollectNowResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::MaxCollectNow {}).unwrap()).unwrap();
+    assert_eq!(res.roids_amount, Uint128::zero());
+
+    // Concentrated pairs check the spread net of their fee, so the max spread is translated
+    deps.querier.with_pair_type(
+        &[ufee.clone(), roids()],
+        PairType::Custom("concentrated".to_string()),
+    );
+    let res: MaxCollectNowResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::MaxCollectNow {}).unwrap()).unwrap();
+    assert_eq!(res.roids_amount, Uint128::new(945));
+
+    let res: EffectiveConfigResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::EffectiveConfig {}).unwrap()).unwrap();
+    assert_eq!(
+        res.concentrated_max_spread,
+        Decimal::from_str("0.0595").unwrap()
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Collect {
+            assets: vec![AssetWithLimit {
+                info: ufee.clone(),
+                limit: None,
+                limit_semantics: None,
+            }],
+            asset_type_filter: None,
+            expected_routes: None,
+            deadline: None,
+            min_roids_out: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "fee-roids".to_string(),
+            msg: to_json_binary(&PairExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: ufee,
+                    amount: Uint128::new(1000),
+                },
+                ask_asset_info: Some(roids()),
+                belief_price: None,
+                max_spread: Some(Decimal::from_str("0.0595").unwrap()),
+                to: None,
+            })
+            .unwrap(),
+            funds: vec![coin(1000, "ufee")],
+        })
+    );
+}
+
+#[test]
+fn tune_risk() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let tune = |max_spread: Decimal, collect_cooldown: u64| ExecuteMsg::TuneRisk {
+        max_spread,
+        collect_cooldown,
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("random", &[]),
+        tune(Decimal::percent(3), 300),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        tune(Decimal::permille(5), 30),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::RiskyParams {
+            max_spread: Decimal::permille(5),
+            collect_cooldown: 30,
+        }
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        tune(Decimal::zero(), 300),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::IncorrectMaxSpread {});
+
+    // A tight spread is fine with a long enough cooldown
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        tune(Decimal::permille(5), 300),
+    )
+    .unwrap();
+    assert_eq!(res.events.len(), 1);
+    assert_eq!(res.events[0].ty, "tune_risk");
+
+    let config = CONFIG.load(deps.as_ref().storage).unwrap();
+    assert_eq!(config.max_spread, Decimal::permille(5));
+    assert_eq!(config.collect_cooldown, Some(300));
+}
+
+#[test]
+fn supply_impact() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let res: SupplyImpactResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::SupplyImpact {}).unwrap()).unwrap();
+    assert_eq!(res.total_burned, Uint128::zero());
+    assert_eq!(res.net_removed, Uint128::zero());
+
+    let burn_reply = |result| Reply {
+        id: BURN_REPLY_ID,
+        result,
+    };
+    let burn_ok = || {
+        SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        })
+    };
+
+    BURNING_ROIDS
+        .save(deps.as_mut().storage, &Uint128::new(300))
+        .unwrap();
+    reply(deps.as_mut(), mock_env(), burn_reply(burn_ok())).unwrap();
+
+    // A blocked burn isn't counted
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: None,
+            collect_cooldown: None,
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: None,
+            dust_threshold: None,
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: None,
+            lenient_burn: Some(true),
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: None,
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: None,
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(200));
+    BURNING_ROIDS
+        .save(deps.as_mut().storage, &Uint128::new(200))
+        .unwrap();
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        burn_reply(SubMsgResult::Err("transfers are paused".to_string())),
+    )
+    .unwrap();
+    reply(deps.as_mut(), mock_env(), burn_reply(burn_ok())).unwrap();
+
+    let res: SupplyImpactResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::SupplyImpact {}).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        SupplyImpactResponse {
+            total_burned: Uint128::new(300),
+            returned_to_circulation: Uint128::zero(),
+            net_removed: Uint128::new(300),
+        }
+    );
+}
+
+#[test]
+fn statistics() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let res: StatisticsResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Statistics {}).unwrap()).unwrap();
+    assert_eq!(res.total_roids, Uint128::zero());
+    assert_eq!(res.distributions, 0);
+    assert_eq!(res.last_distribution_ts, None);
+
+    let burn_ok = || Reply {
+        id: BURN_REPLY_ID,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+
+    BURNING_ROIDS
+        .save(deps.as_mut().storage, &Uint128::new(300))
+        .unwrap();
+    reply(deps.as_mut(), mock_env(), burn_ok()).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(60);
+    BURNING_ROIDS
+        .save(deps.as_mut().storage, &Uint128::new(200))
+        .unwrap();
+    reply(deps.as_mut(), env.clone(), burn_ok()).unwrap();
+
+    // A confirmation without a pending amount isn't counted
+    reply(deps.as_mut(), env.clone(), burn_ok()).unwrap();
+
+    let res: StatisticsResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Statistics {}).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        StatisticsResponse {
+            total_roids: Uint128::new(500),
+            collects: 0,
+            distributions: 2,
+            last_distribution_amount: Uint128::new(200),
+            last_distribution_ts: Some(env.block.time.seconds()),
+        }
+    );
+}
+
+#[test]
+fn swap_events() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uluna = native_asset_info("uluna".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), uluna.clone()], "fee-luna");
+    deps.querier.with_pair(&[uluna, roids()], "luna-roids");
+
+    // Every swap replies so its event can be emitted, the distribution doesn't
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Collect {
+            assets: vec![AssetWithLimit {
+                info: ufee,
+                limit: None,
+                limit_semantics: None,
+            }],
+            asset_type_filter: None,
+            expected_routes: None,
+            deadline: None,
+            min_roids_out: None,
+        },
+    )
+    .unwrap();
+    let (distribute_msg, swap_msgs) = res.messages.split_last().unwrap();
+    assert!(!swap_msgs.is_empty());
+    assert!(swap_msgs.iter().all(|msg| msg.id == SWAP_REPLY_ID));
+    assert_ne!(distribute_msg.id, SWAP_REPLY_ID);
+
+    let swap_event = |pool: &str, offer: &str, ask: &str, return_amount: u128| {
+        Event::new("wasm").add_attributes([
+            attr("_contract_address", pool),
+            attr("action", "swap"),
+            attr("offer_asset", offer),
+            attr("ask_asset", ask),
+            attr("offer_amount", "100"),
+            attr("return_amount", return_amount.to_string()),
+            attr("spread_amount", "0"),
+        ])
+    };
+    let swap_reply = |events: Vec<Event>| Reply {
+        id: SWAP_REPLY_ID,
+        result: SubMsgResult::Ok(SubMsgResponse { events, data: None }),
+    };
+    let maker_swaps = |res: &Response| {
+        res.events
+            .iter()
+            .filter(|event| event.ty == "astroport/maker/swap")
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    // A swap through several pairs emits a single event from the offer to the final return
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        swap_reply(vec![
+            swap_event("fee-luna", "ufee", "uluna", 100),
+            Event::new("transfer").add_attribute("amount", "100uluna"),
+            swap_event("luna-roids", "uluna", ROIDS, 250),
+        ]),
+    )
+    .unwrap();
+    assert_eq!(
+        maker_swaps(&res),
+        vec![Event::new("astroport/maker/swap").add_attributes([
+            attr("asset", "ufee"),
+            attr("ask_asset", ROIDS),
+            attr("amount_in", "100"),
+            attr("amount_out", "250"),
+            attr("pool", "fee-luna"),
+        ])]
+    );
+
+    // Responses without pair swap events emit nothing
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        swap_reply(vec![
+            Event::new("transfer").add_attribute("amount", "100uluna")
+        ]),
+    )
+    .unwrap();
+    assert!(maker_swaps(&res).is_empty());
+}
+
+#[test]
+fn worst_hop_slippage() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Collect {
+            assets: vec![AssetWithLimit {
+                info: ufee,
+                limit: None,
+                limit_semantics: None,
+            }],
+            asset_type_filter: None,
+            expected_routes: None,
+            deadline: None,
+            min_roids_out: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages[0].id, SWAP_REPLY_ID);
+    assert_eq!(res.messages[0].reply_on, ReplyOn::Success);
+
+    let swap_reply = |offer: &str, ask: &str, return_amount: u128, spread_amount: u128| Reply {
+        id: SWAP_REPLY_ID,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![Event::new("wasm").add_attributes([
+                attr("_contract_address", "fee-roids"),
+                attr("action", "swap"),
+                attr("offer_asset", offer),
+                attr("ask_asset", ask),
+                attr("offer_amount", "100"),
+                attr("return_amount", return_amount.to_string()),
+                attr("spread_amount", spread_amount.to_string()),
+            ])],
+            data: None,
+        }),
+    };
+
+    let res = reply(deps.as_mut(), mock_env(), swap_reply("ufee", ROIDS, 98, 2)).unwrap();
+    assert!(res.events[0].attributes.contains(&attr("slippage", "0.02")));
+    assert_eq!(
+        res.events[1],
+        Event::new("astroport/maker/swap").add_attributes([
+            attr("asset", "ufee"),
+            attr("ask_asset", ROIDS),
+            attr("amount_in", "100"),
+            attr("amount_out", "98"),
+            attr("pool", "fee-roids"),
+        ])
+    );
+
+    reply(deps.as_mut(), mock_env(), swap_reply("uatom", ROIDS, 99, 1)).unwrap();
+
+    let worst: Option<HopSlippage> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::WorstHop {}).unwrap()).unwrap();
+    assert_eq!(
+        worst,
+        Some(HopSlippage {
+            hop: 0,
+            offer_asset: "ufee".to_string(),
+            ask_asset: ROIDS.to_string(),
+            slippage: Decimal::percent(2),
+        })
+    );
+}
+
+#[test]
+fn asset_stats() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let swap_reply = |offer: &str, offer_amount: u128| Reply {
+        id: SWAP_REPLY_ID,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![Event::new("wasm").add_attributes([
+                attr("action", "swap"),
+                attr("offer_asset", offer),
+                attr("ask_asset", ROIDS),
+                attr("offer_amount", offer_amount.to_string()),
+                attr("return_amount", "10"),
+                attr("spread_amount", "0"),
+            ])],
+            data: None,
+        }),
+    };
+
+    reply(deps.as_mut(), mock_env(), swap_reply("ufee", 100)).unwrap();
+    reply(deps.as_mut(), mock_env(), swap_reply("ufee", 50)).unwrap();
+    reply(deps.as_mut(), mock_env(), swap_reply("uatom", 7)).unwrap();
+
+    let query_stats = |deps: Deps, denom: &str| -> AssetStats {
+        from_json(
+            query(
+                deps,
+                mock_env(),
+                QueryMsg::AssetStats {
+                    asset_info: native_asset_info(denom.to_string()),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+
+    assert_eq!(
+        query_stats(deps.as_ref(), "ufee"),
+        AssetStats {
+            total_swapped: Uint128::new(150),
+            swaps: 2,
+            deposited: Uint128::zero(),
+        }
+    );
+    assert_eq!(
+        query_stats(deps.as_ref(), "uatom"),
+        AssetStats {
+            total_swapped: Uint128::new(7),
+            swaps: 1,
+            deposited: Uint128::zero(),
+        }
+    );
+    assert_eq!(query_stats(deps.as_ref(), "uluna"), AssetStats::default());
+}
+
+#[test]
+fn donate() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("donor", &[]),
+        ExecuteMsg::Donate {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::EmptyDonation {});
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("donor", &[coin(100, "uluna")]),
+        ExecuteMsg::Donate {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.events,
+        vec![Event::new("donation").add_attributes([
+            attr("donor", "donor"),
+            attr("asset", "uluna"),
+            attr("amount", "100"),
+        ])]
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("donor", &[coin(50, "uluna")]),
+        ExecuteMsg::Donate {},
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ROIDS, &[]),
+        ExecuteMsg::Receive(cw20::Cw20ReceiveMsg {
+            sender: "donor".to_string(),
+            amount: Uint128::new(10),
+            msg: to_json_binary(&Cw20HookMsg::Donate {}).unwrap(),
+        }),
+    )
+    .unwrap();
+
+    let donations: Vec<Asset> = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Donations {
+                donor: "donor".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        donations,
+        vec![
+            Asset {
+                info: roids(),
+                amount: Uint128::new(10),
+            },
+            Asset {
+                info: native_asset_info("uluna".to_string()),
+                amount: Uint128::new(150),
+            },
+        ]
+    );
+}
+
+#[test]
+fn collect_deposit() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let fee_token = token_asset_info(Addr::unchecked("fee-token"));
+    deps.querier
+        .with_pair(&[fee_token.clone(), roids()], "fee-roids");
+    deps.querier
+        .with_token_balance("fee-token", MOCK_CONTRACT_ADDR, Uint128::new(100));
+
+    let deposit = |swap: Option<bool>| {
+        ExecuteMsg::Receive(cw20::Cw20ReceiveMsg {
+            sender: "integrator".to_string(),
+            amount: Uint128::new(50),
+            msg: to_json_binary(&Cw20HookMsg::CollectDeposit { swap }).unwrap(),
+        })
+    };
+
+    // The deposit is only recorded
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("fee-token", &[]),
+        deposit(None),
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+    assert!(res.attributes.contains(&attr("action", "collect_deposit")));
+
+    // The deposit is collected right away
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("fee-token", &[]),
+        deposit(Some(true)),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "fee-token".to_string(),
+            msg: to_json_binary(&cw20::Cw20ExecuteMsg::Send {
+                contract: "fee-roids".to_string(),
+                amount: Uint128::new(100),
+                msg: to_json_binary(&astroport::pair::Cw20HookMsg::Swap {
+                    ask_asset_info: Some(roids()),
+                    belief_price: None,
+                    max_spread: Some(Decimal::percent(5)),
+                    to: None,
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+
+    let stats: AssetStats = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AssetStats {
+                asset_info: fee_token,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(stats.deposited, Uint128::new(100));
+}
+
+#[test]
+fn positive_slippage() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(10));
+
+    let ufee = native_asset_info("ufee".to_string());
+    deps.querier.with_pair_rate(
+        &[ufee.clone(), roids()],
+        "fee-roids",
+        Decimal::from_ratio(2u128, 1u128),
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: None,
+            collect_cooldown: None,
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: None,
+            dust_threshold: None,
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: Some(50),
+            lenient_burn: None,
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: None,
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: None,
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Collect {
+            assets: vec![AssetWithLimit {
+                info: ufee,
+                limit: None,
+                limit_semantics: None,
+            }],
+            asset_type_filter: None,
+            expected_routes: None,
+            deadline: None,
+            min_roids_out: None,
+        },
+    )
+    .unwrap();
+
+    // The pool moved favorably: the swap returned 250 ROIDS instead of the simulated 200
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(260));
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: SWAP_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![Event::new("wasm").add_attributes([
+                    attr("action", "swap"),
+                    attr("offer_asset", "ufee"),
+                    attr("ask_asset", ROIDS),
+                    attr("offer_amount", "100"),
+                    attr("return_amount", "250"),
+                    attr("spread_amount", "0"),
+                ])],
+                data: None,
+            }),
+        },
+    )
+    .unwrap();
+    assert!(res.events[0].attributes.contains(&attr("slippage", "0")));
+
+    // The whole surplus is attributed to the swaps and burned with them
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::DistributeAstro {},
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("roids_amount", "260")));
+    assert!(res.attributes.contains(&attr("roids_from_swaps", "250")));
+    assert!(res
+        .attributes
+        .contains(&attr("roids_received_directly", "10")));
+    let data: DistributeResponseData = from_json(res.data.unwrap()).unwrap();
+    assert_eq!(data.amount, Uint128::new(260));
+}
+
+#[test]
+fn max_bridges() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let uluna = native_asset_info("uluna".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    let uosmo = native_asset_info("uosmo".to_string());
+    deps.querier
+        .with_pair(&[uluna.clone(), roids()], "luna-roids");
+    deps.querier
+        .with_pair(&[uatom.clone(), uluna.clone()], "atom-luna");
+    deps.querier
+        .with_pair(&[uosmo.clone(), uluna.clone()], "osmo-luna");
+
+    let set_max_bridges = |max_bridges| ExecuteMsg::UpdateConfig {
+        basic_asset: None,
+        max_spread: None,
+        collect_cooldown: None,
+        swap_deadline_secs: None,
+        auto_pause_threshold: None,
+        burn_every_n_collects: None,
+        dust_threshold: None,
+        keeper_commit_window_secs: None,
+        min_receive_buffer_bps: None,
+        lenient_burn: None,
+        max_bridges: Some(max_bridges),
+        strict_collect: None,
+        post_unpause_grace_secs: None,
+        burn_retry_limit: None,
+        learn_routes: None,
+        keeper_fee_bps: None,
+        guardian: None,
+        seize_delay_secs: None,
+        min_collect_amount: None,
+        quarantine_failed_swaps: None,
+        max_collect_value_per_window: None,
+        bridges_execution_max_depth: None,
+        tokenfactory_burn: None,
+        max_assets_per_tx: None,
+        belief_price_source: None,
+        on_spread_exceeded: None,
+        reserve_amount: None,
+        auction_fallback: None,
+        operator: None,
+    };
+    let add_bridges = |assets: Vec<AssetInfo>| ExecuteMsg::UpdateBridges {
+        add: Some(
+            assets
+                .into_iter()
+                .map(|asset| (asset, uluna.clone()))
+                .collect(),
+        ),
+        remove: None,
+    };
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        set_max_bridges(1),
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        add_bridges(vec![uatom.clone(), uosmo.clone()]),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::MaxBridgesExceeded { max: 1 });
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        add_bridges(vec![uatom]),
+    )
+    .unwrap();
+
+    // Routes are counted along with the bridges
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateRoutes {
+            add: Some(vec![(uluna.clone(), vec![roids()])]),
+            remove: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::MaxBridgesExceeded { max: 1 });
+
+    // Removing the limit allows more bridges
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        set_max_bridges(0),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        add_bridges(vec![uosmo]),
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        set_max_bridges(1),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::MaxBridgesTooLow { max: 1, count: 2 });
+
+    let res: ConfigResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(res.max_bridges, None);
+}
+
+#[test]
+fn bridge_cycle() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), uatom.clone()], "fee-atom");
+    deps.querier
+        .with_pair(&[uatom.clone(), roids()], "atom-roids");
+
+    let update_bridges = |deps: DepsMut, add: Vec<(AssetInfo, AssetInfo)>| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::UpdateBridges {
+                add: Some(add),
+                remove: None,
+            },
+        )
+    };
+
+    // Each bridge on its own reaches ROIDS through the other one
+    let err = update_bridges(
+        deps.as_mut(),
+        vec![(ufee.clone(), uatom.clone()), (uatom.clone(), ufee.clone())],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::BridgeCycle("ufee -> uatom -> ufee".to_string())
+    );
+
+    update_bridges(deps.as_mut(), vec![(ufee.clone(), uatom.clone())]).unwrap();
+    let err = update_bridges(deps.as_mut(), vec![(uatom.clone(), ufee.clone())]).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::BridgeCycle("uatom -> ufee -> uatom".to_string())
+    );
+}
+
+#[test]
+fn bridges_execution_max_depth() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    set_router(deps.as_mut(), "router").unwrap();
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    let uosmo = native_asset_info("uosmo".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), uatom.clone()], "fee-atom");
+    deps.querier
+        .with_pair(&[uatom.clone(), uosmo.clone()], "atom-osmo");
+    deps.querier
+        .with_pair(&[uosmo.clone(), roids()], "osmo-roids");
+
+    let set_depth = |depth| ExecuteMsg::UpdateConfig {
+        basic_asset: None,
+        max_spread: None,
+        collect_cooldown: None,
+        swap_deadline_secs: None,
+        auto_pause_threshold: None,
+        burn_every_n_collects: None,
+        dust_threshold: None,
+        keeper_commit_window_secs: None,
+        min_receive_buffer_bps: None,
+        lenient_burn: None,
+        max_bridges: None,
+        strict_collect: None,
+        post_unpause_grace_secs: None,
+        burn_retry_limit: None,
+        learn_routes: None,
+        keeper_fee_bps: None,
+        guardian: None,
+        seize_delay_secs: None,
+        min_collect_amount: None,
+        quarantine_failed_swaps: None,
+        max_collect_value_per_window: None,
+        bridges_execution_max_depth: Some(depth),
+        tokenfactory_burn: None,
+        max_assets_per_tx: None,
+        belief_price_source: None,
+        on_spread_exceeded: None,
+        reserve_amount: None,
+        auction_fallback: None,
+        operator: None,
+    };
+
+    for depth in [0, 11] {
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            set_depth(depth),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::IncorrectBridgesExecutionDepth { min: 1, max: 10 }
+        );
+    }
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        set_depth(1),
+    )
+    .unwrap();
+    let res: EffectiveConfigResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::EffectiveConfig {}).unwrap()).unwrap();
+    assert_eq!(res.bridges_execution_max_depth, 1);
+
+    // The first hop is made by collect itself, so a depth of 1 allows two hops
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateRoutes {
+            add: Some(vec![(
+                ufee.clone(),
+                vec![uatom.clone(), uosmo.clone(), roids()],
+            )]),
+            remove: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::MaxBridgeDepth(3));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateRoutes {
+            add: Some(vec![(uatom.clone(), vec![uosmo.clone(), roids()])]),
+            remove: None,
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn swap_to_intermediate() {
+    let mut deps = mock_dependencies(&[coin(100, "ustuck")]);
+    instantiate_maker(deps.as_mut());
+
+    let ustuck = native_asset_info("ustuck".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    deps.querier.with_pair_rate(
+        &[ustuck.clone(), uatom.clone()],
+        "stuck-atom",
+        Decimal::from_ratio(3u128, 1u128),
+    );
+
+    let swap = |intermediate: &AssetInfo| ExecuteMsg::SwapToIntermediate {
+        asset: ustuck.clone(),
+        intermediate: intermediate.clone(),
+        max_spread: Decimal::percent(10),
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("random", &[]),
+        swap(&uatom),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let uosmo = native_asset_info("uosmo".to_string());
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        swap(&uosmo),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidBridgeNoPool(ustuck.to_string(), uosmo.to_string())
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        swap(&uatom),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "stuck-atom".to_string(),
+            msg: to_json_binary(&PairExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: ustuck.clone(),
+                    amount: Uint128::new(100),
+                },
+                ask_asset_info: Some(uatom),
+                belief_price: None,
+                max_spread: Some(Decimal::percent(10)),
+                to: None,
+            })
+            .unwrap(),
+            funds: vec![coin(100, "ustuck")],
+        })
+    );
+    assert!(res.attributes.contains(&attr("offer_amount", "100")));
+    assert!(res
+        .attributes
+        .contains(&attr("expected_return_amount", "300")));
+}
+
+#[test]
+fn collect_reports_cooldown_state() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    let start = mock_env().block.time.seconds();
+
+    let res = collect_at(deps.as_mut(), start).unwrap();
+    assert!(res
+        .attributes
+        .contains(&attr("last_collect_ts", start.to_string())));
+    assert!(!res.attributes.iter().any(|a| a.key == "next_collect_ts"));
+
+    update_cooldown(deps.as_mut(), mock_env(), 60);
+    let res = collect_at(deps.as_mut(), start + 60).unwrap();
+    assert!(res
+        .attributes
+        .contains(&attr("last_collect_ts", (start + 60).to_string())));
+    assert!(res
+        .attributes
+        .contains(&attr("next_collect_ts", (start + 120).to_string())));
+}
+
+#[test]
+fn can_onboard() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let uluna = native_asset_info("uluna".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    let ufee = native_asset_info("ufee".to_string());
+    let unew = native_asset_info("unew".to_string());
+    deps.querier
+        .with_pair(&[uluna.clone(), roids()], "luna-roids");
+    deps.querier
+        .with_pair(&[uatom.clone(), uluna.clone()], "atom-luna");
+    deps.querier
+        .with_pair(&[ufee.clone(), uluna.clone()], "fee-luna");
+    deps.querier
+        .with_pair(&[unew.clone(), uatom.clone()], "new-atom");
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateBridges {
+            add: Some(vec![(uatom.clone(), uluna.clone())]),
+            remove: None,
+        },
+    )
+    .unwrap();
+
+    let can_onboard = |deps: Deps, asset: AssetInfo| -> CanOnboardResponse {
+        from_json(query(deps, mock_env(), QueryMsg::CanOnboard { asset }).unwrap()).unwrap()
+    };
+
+    // The default bridge already routes the fee token
+    assert_eq!(
+        can_onboard(deps.as_ref(), ufee),
+        CanOnboardResponse {
+            route: Some(vec![uluna, roids()]),
+            candidate_bridges: vec![],
+        }
+    );
+
+    // The new token only has a pair with an asset which is itself bridged to ROIDS
+    assert_eq!(
+        can_onboard(deps.as_ref(), unew),
+        CanOnboardResponse {
+            route: None,
+            candidate_bridges: vec![uatom],
+        }
+    );
+
+    let res = can_onboard(deps.as_ref(), native_asset_info("uunknown".to_string()));
+    assert_eq!(res.route, None);
+    assert!(res.candidate_bridges.is_empty());
+}
+
+#[test]
+fn rebasing_assets() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateRebasingAssets {
+            assets: vec![ufee.clone(), ufee.clone()],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::DuplicatedAsset {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateRebasingAssets {
+            assets: vec![ufee.clone()],
+        },
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Collect {
+            assets: vec![AssetWithLimit {
+                info: ufee.clone(),
+                limit: None,
+                limit_semantics: None,
+            }],
+            asset_type_filter: None,
+            expected_routes: None,
+            deadline: None,
+            min_roids_out: None,
+        },
+    )
+    .unwrap();
+
+    // 1% of the balance is left to absorb rebases
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "fee-roids".to_string(),
+            msg: to_json_binary(&PairExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: ufee,
+                    amount: Uint128::new(99),
+                },
+                ask_asset_info: Some(roids()),
+                belief_price: None,
+                max_spread: Some(Decimal::percent(5)),
+                to: None,
+            })
+            .unwrap(),
+            funds: vec![coin(99, "ufee")],
+        })
+    );
+}
+
+#[test]
+fn realized_prices() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee"), coin(50, "uatom")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    let uluna = native_asset_info("uluna".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+    deps.querier
+        .with_pair(&[uatom.clone(), uluna.clone()], "atom-luna");
+    deps.querier
+        .with_pair(&[uluna.clone(), roids()], "luna-roids");
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Collect {
+            assets: vec![
+                AssetWithLimit {
+                    info: ufee.clone(),
+                    limit: None,
+                    limit_semantics: None,
+                },
+                AssetWithLimit {
+                    info: uatom.clone(),
+                    limit: None,
+                    limit_semantics: None,
+                },
+            ],
+            asset_type_filter: None,
+            expected_routes: None,
+            deadline: None,
+            min_roids_out: None,
+        },
+    )
+    .unwrap();
+
+    let swap_reply = |offer: &str, ask: &str, offer_amount: u128, return_amount: u128| Reply {
+        id: SWAP_REPLY_ID,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![Event::new("wasm").add_attributes([
+                attr("action", "swap"),
+                attr("offer_asset", offer),
+                attr("ask_asset", ask),
+                attr("offer_amount", offer_amount.to_string()),
+                attr("return_amount", return_amount.to_string()),
+                attr("spread_amount", "0"),
+            ])],
+            data: None,
+        }),
+    };
+
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        swap_reply("ufee", ROIDS, 100, 250),
+    )
+    .unwrap();
+    let prices: Vec<(AssetInfo, Decimal)> = from_json(res.data.unwrap()).unwrap();
+    assert_eq!(
+        prices,
+        vec![(ufee.clone(), Decimal::from_ratio(5u128, 2u128))]
+    );
+
+    // The bridged asset is priced once it reaches ROIDS
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        swap_reply("uatom", "uluna", 50, 200),
+    )
+    .unwrap();
+    assert_eq!(res.data, None);
+
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        swap_reply("uluna", ROIDS, 200, 100),
+    )
+    .unwrap();
+    assert!(res.events[1]
+        .attributes
+        .contains(&attr("realized_roids", "100")));
+    let prices: Vec<(AssetInfo, Decimal)> = from_json(res.data.unwrap()).unwrap();
+    assert_eq!(
+        prices,
+        vec![
+            (ufee, Decimal::from_ratio(5u128, 2u128)),
+            (uatom, Decimal::percent(200)),
+        ]
+    );
+}
+
+#[test]
+fn strict_collect() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+
+    let update = |deps: DepsMut, auto_pause_threshold: u32, strict_collect: bool| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::UpdateConfig {
+                basic_asset: None,
+                max_spread: None,
+                collect_cooldown: None,
+                swap_deadline_secs: None,
+                auto_pause_threshold: Some(auto_pause_threshold),
+                burn_every_n_collects: None,
+                dust_threshold: None,
+                keeper_commit_window_secs: None,
+                min_receive_buffer_bps: None,
+                lenient_burn: None,
+                max_bridges: None,
+                strict_collect: Some(strict_collect),
+                post_unpause_grace_secs: None,
+                burn_retry_limit: None,
+                learn_routes: None,
+                keeper_fee_bps: None,
+                guardian: None,
+                seize_delay_secs: None,
+                min_collect_amount: None,
+                quarantine_failed_swaps: None,
+                max_collect_value_per_window: None,
+                bridges_execution_max_depth: None,
+                tokenfactory_burn: None,
+                max_assets_per_tx: None,
+                belief_price_source: None,
+                on_spread_exceeded: None,
+                reserve_amount: None,
+                auction_fallback: None,
+                operator: None,
+            },
+        )
+        .unwrap();
+    };
+    let collect = |deps: DepsMut| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("keeper", &[]),
+            ExecuteMsg::Collect {
+                assets: vec![AssetWithLimit {
+                    info: ufee.clone(),
+                    limit: None,
+                    limit_semantics: None,
+                }],
+                asset_type_filter: None,
+                expected_routes: None,
+                deadline: None,
+                min_roids_out: None,
+            },
+        )
+        .unwrap()
+    };
+
+    update(deps.as_mut(), 2, true);
+
+    let res: ConfigResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert!(res.strict_collect);
+
+    // Failures aren't caught by the circuit breaker so they revert the collect
+    let res = collect(deps.as_mut());
+    assert!(res
+        .messages
+        .iter()
+        .all(|msg| msg.reply_on != ReplyOn::Error && msg.reply_on != ReplyOn::Always));
+    assert_eq!(res.messages[0].reply_on, ReplyOn::Success);
+
+    // Only swap failures are caught, a failing distribution still reverts the collect
+    update(deps.as_mut(), 2, false);
+    let res = collect(deps.as_mut());
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(res.messages[0].id, SWAP_REPLY_ID);
+    assert_eq!(res.messages[0].reply_on, ReplyOn::Always);
+    assert_eq!(res.messages[1].reply_on, ReplyOn::Never);
+
+    // A zero threshold disables the circuit breaker
+    update(deps.as_mut(), 0, false);
+    let res: ConfigResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(res.auto_pause_threshold, None);
+    let res = collect(deps.as_mut());
+    assert_eq!(res.messages[0].reply_on, ReplyOn::Success);
+}
+
+#[test]
+fn routes_changed_since() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let uluna = native_asset_info("uluna".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    let uosmo = native_asset_info("uosmo".to_string());
+    deps.querier
+        .with_pair(&[uluna.clone(), roids()], "luna-roids");
+    deps.querier
+        .with_pair(&[uatom.clone(), uluna.clone()], "atom-luna");
+    deps.querier
+        .with_pair(&[uosmo.clone(), uatom.clone()], "osmo-atom");
+
+    let at_height = |height| {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    };
+    let add_bridge = |asset: &AssetInfo, bridge: &AssetInfo| ExecuteMsg::UpdateBridges {
+        add: Some(vec![(asset.clone(), bridge.clone())]),
+        remove: None,
+    };
+    let routes_changed_since = |deps: Deps, height| -> RoutesChangedSinceResponse {
+        from_json(query(deps, mock_env(), QueryMsg::RoutesChangedSince { height }).unwrap())
+            .unwrap()
+    };
+
+    execute(
+        deps.as_mut(),
+        at_height(100),
+        mock_info(OWNER, &[]),
+        add_bridge(&uatom, &uluna),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        at_height(200),
+        mock_info(OWNER, &[]),
+        add_bridge(&uosmo, &uatom),
+    )
+    .unwrap();
+
+    assert_eq!(
+        routes_changed_since(deps.as_ref(), 150),
+        RoutesChangedSinceResponse {
+            assets: vec![uosmo.to_string()],
+            all_routes_changed: false,
+        }
+    );
+
+    // Assets bridged through a changed asset are affected as well
+    execute(
+        deps.as_mut(),
+        at_height(300),
+        mock_info(OWNER, &[]),
+        add_bridge(&uatom, &uluna),
+    )
+    .unwrap();
+    assert_eq!(
+        routes_changed_since(deps.as_ref(), 250),
+        RoutesChangedSinceResponse {
+            assets: vec![uatom.to_string(), uosmo.to_string()],
+            all_routes_changed: false,
+        }
+    );
+
+    execute(
+        deps.as_mut(),
+        at_height(400),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: Some(uatom),
+            max_spread: None,
+            collect_cooldown: None,
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: None,
+            dust_threshold: None,
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: None,
+            lenient_burn: None,
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: None,
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: None,
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+    let res = routes_changed_since(deps.as_ref(), 350);
+    assert!(res.assets.is_empty());
+    assert!(res.all_routes_changed);
+}
+
+#[test]
+fn reclaim_roids() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, FACTORY, Uint128::new(100));
+
+    let reclaim = |amount: u128| ExecuteMsg::ReclaimRoids {
+        from: FACTORY.to_string(),
+        amount: Uint128::new(amount),
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("random", &[]),
+        reclaim(100),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // Collects must be paused first
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        reclaim(100),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotPaused {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::SetPaused { paused: true },
+    )
+    .unwrap();
+
+    // Pausing doesn't open the reclaim to anyone else
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("random", &[]),
+        reclaim(100),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    for amount in [0, 101] {
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            reclaim(amount),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidReclaimAmount {
+                from: FACTORY.to_string(),
+                amount: Uint128::new(amount),
+                balance: Uint128::new(100),
+            }
+        );
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        reclaim(100),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ROIDS.to_string(),
+            msg: to_json_binary(&cw20::Cw20ExecuteMsg::TransferFrom {
+                owner: FACTORY.to_string(),
+                recipient: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Uint128::new(100),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+    assert!(res.attributes.contains(&attr("action", "reclaim_roids")));
+}
+
+#[test]
+fn post_unpause_grace() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    let start = mock_env().block.time.seconds();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: None,
+            collect_cooldown: None,
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: None,
+            dust_threshold: None,
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: None,
+            lenient_burn: None,
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: Some(300),
+            burn_retry_limit: None,
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: None,
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+
+    // Collects are immediately possible if the Maker was never paused
+    collect_at(deps.as_mut(), start).unwrap();
+
+    for paused in [true, false] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::SetPaused { paused },
+        )
+        .unwrap();
+    }
+
+    let err = collect_at(deps.as_mut(), start + 299).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::PostUnpauseGrace {
+            resume_ts: start + 300
+        }
+    );
+    collect_at(deps.as_mut(), start + 300).unwrap();
+}
+
+#[test]
+fn refresh_decimals() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let bridge_token = token_asset_info(Addr::unchecked("bridge-token"));
+    deps.querier.with_token_decimals(ROIDS, 6);
+    deps.querier.with_token_decimals("bridge-token", 18);
+    deps.querier
+        .with_pair(&[ufee.clone(), bridge_token.clone()], "fee-bridge");
+    deps.querier
+        .with_pair(&[bridge_token.clone(), roids()], "bridge-roids");
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateBridges {
+            add: Some(vec![(ufee, bridge_token.clone())]),
+            remove: None,
+        },
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("random", &[]),
+        ExecuteMsg::RefreshDecimals {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // The native default bridge can't be resolved without a coin registry
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::RefreshDecimals {},
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("refreshed", "2")));
+    assert!(res.attributes.contains(&attr("failed", "1")));
+
+    let storage = deps.as_ref().storage;
+    assert_eq!(DECIMALS.load(storage, roids().to_string()).unwrap(), 6);
+    assert_eq!(
+        DECIMALS.load(storage, bridge_token.to_string()).unwrap(),
+        18
+    );
+}
+
+#[test]
+fn assign_roles() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    // Every role is held by the owner until it is handed over
+    let res: PermissionsResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Permissions {}).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        PermissionsResponse {
+            owner: Addr::unchecked(OWNER),
+            roles: vec![
+                (Role::BridgeManager, Addr::unchecked(OWNER)),
+                (Role::Pauser, Addr::unchecked(OWNER)),
+            ],
+        }
+    );
+
+    let assign = |role: Role, address: &str| ExecuteMsg::AssignRole {
+        role,
+        address: address.to_string(),
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("random", &[]),
+        assign(Role::Pauser, "random"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        assign(Role::Pauser, "timelock"),
+    )
+    .unwrap();
+
+    // The owner renounced the pauser role but still manages bridges
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::SetPaused { paused: true },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        assign(Role::Pauser, OWNER),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("timelock", &[]),
+        ExecuteMsg::SetPaused { paused: true },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateBridges {
+            add: None,
+            remove: Some(vec![]),
+        },
+    )
+    .unwrap();
+
+    let res: PermissionsResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Permissions {}).unwrap()).unwrap();
+    assert_eq!(
+        res.roles,
+        vec![
+            (Role::BridgeManager, Addr::unchecked(OWNER)),
+            (Role::Pauser, Addr::unchecked("timelock")),
+        ]
+    );
+
+    // Handing a role back to the owner makes it follow ownership again
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("timelock", &[]),
+        assign(Role::Pauser, OWNER),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::SetPaused { paused: false },
+    )
+    .unwrap();
+}
+
+#[test]
+fn burn_retries() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(500));
+    let start = mock_env().block.time.seconds();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: None,
+            collect_cooldown: None,
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: Some(10),
+            dust_threshold: None,
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: None,
+            lenient_burn: Some(true),
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: Some(2),
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: None,
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+
+    let blocked_burn = Reply {
+        id: BURN_REPLY_ID,
+        result: SubMsgResult::Err("bridge unavailable".to_string()),
+    };
+
+    // Nothing is pending, ROIDS accumulates until the burn frequency is reached
+    let res = collect_at(deps.as_mut(), start).unwrap();
+    assert!(res.messages.is_empty());
+
+    let res = reply(deps.as_mut(), mock_env(), blocked_burn.clone()).unwrap();
+    assert!(res.attributes.contains(&attr("failed_burn_attempts", "1")));
+
+    // The pending ROIDS is burned on the next collects until the retry budget runs out
+    for attempt in 2..=3 {
+        let res = collect_at(deps.as_mut(), start + attempt).unwrap();
+        assert!(res.attributes.contains(&attr("burn_retry", "true")));
+
+        let res = reply(deps.as_mut(), mock_env(), blocked_burn.clone()).unwrap();
+        assert_eq!(res.events.is_empty(), attempt < 3);
+    }
+    let res = reply(deps.as_mut(), mock_env(), blocked_burn.clone()).unwrap();
+    assert!(res.events.is_empty());
+
+    let res = collect_at(deps.as_mut(), start + 4).unwrap();
+    assert!(!res.attributes.contains(&attr("burn_retry", "true")));
+
+    // A successful burn resets the retry budget
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: BURN_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )
+    .unwrap();
+    reply(deps.as_mut(), mock_env(), blocked_burn).unwrap();
+    let res = collect_at(deps.as_mut(), start + 5).unwrap();
+    assert!(res.attributes.contains(&attr("burn_retry", "true")));
+}
+
+#[test]
+fn effective_config() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let res: EffectiveConfigResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::EffectiveConfig {}).unwrap()).unwrap();
+    assert_eq!(res.max_spread, Decimal::percent(5));
+    assert_eq!(
+        res.concentrated_max_spread,
+        Decimal::from_str("0.0595").unwrap()
+    );
+    assert_eq!(res.collect_cooldown, 0);
+    assert_eq!(res.burn_every_n_collects, 1);
+    assert_eq!(res.bridges_execution_max_depth, 5);
+    assert_eq!(res.burn_destination, "asteroid");
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: Some(Decimal::percent(10)),
+            collect_cooldown: Some(60),
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: Some(3),
+            dust_threshold: None,
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: None,
+            lenient_burn: None,
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: None,
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: None,
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+
+    let res: EffectiveConfigResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::EffectiveConfig {}).unwrap()).unwrap();
+    assert_eq!(res.max_spread, Decimal::percent(10));
+    assert_eq!(
+        res.concentrated_max_spread,
+        Decimal::from_str("0.109").unwrap()
+    );
+    assert_eq!(res.collect_cooldown, 60);
+    assert_eq!(res.burn_every_n_collects, 3);
+}
+
+#[test]
+fn burn_every_n_collects() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(500));
+    let start = mock_env().block.time.seconds();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: None,
+            collect_cooldown: None,
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: Some(3),
+            dust_threshold: None,
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: None,
+            lenient_burn: None,
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: None,
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: None,
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+
+    // The first two collects hold the ROIDS
+    let res = collect_at(deps.as_mut(), start).unwrap();
+    assert!(res.messages.is_empty());
+
+    // A permissionless flush respects the burn frequency
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::FlushRoids {},
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+    assert!(res.attributes.contains(&attr("collects_until_burn", "2")));
+
+    let res = collect_at(deps.as_mut(), start + 1).unwrap();
+    assert!(res.messages.is_empty());
+
+    // Without ROIDS to burn, the batch isn't over
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    let res = collect_at(deps.as_mut(), start + 2).unwrap();
+    assert!(res.messages.is_empty());
+
+    // The next collect burns everything accumulated
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(500));
+    let res = collect_at(deps.as_mut(), start + 3).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ROIDS.to_string(),
+            msg: to_json_binary(&cw20::Cw20ExecuteMsg::Transfer {
+                recipient: "asteroid".to_string(),
+                amount: Uint128::new(500),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+
+    // A new batch starts
+    let res = collect_at(deps.as_mut(), start + 4).unwrap();
+    assert!(res.messages.is_empty());
+}
+
+#[test]
+fn learn_routes() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee"), coin(50, "uatom")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    let uluna = native_asset_info("uluna".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+    deps.querier
+        .with_pair(&[uatom.clone(), uluna.clone()], "atom-luna");
+    deps.querier.with_pair(&[uluna, roids()], "luna-roids");
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: None,
+            collect_cooldown: None,
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: None,
+            dust_threshold: None,
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: None,
+            lenient_burn: None,
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: None,
+            learn_routes: Some(true),
+            keeper_fee_bps: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: None,
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Collect {
+            assets: [&ufee, &uatom]
+                .into_iter()
+                .map(|info| AssetWithLimit {
+                    info: info.clone(),
+                    limit: None,
+                    limit_semantics: None,
+                })
+                .collect(),
+            asset_type_filter: None,
+            expected_routes: None,
+            deadline: None,
+            min_roids_out: None,
+        },
+    )
+    .unwrap();
+    // Routes are only learned once their swaps succeed
+    assert!(!res.attributes.iter().any(|a| a.key == "learned_route"));
+    assert!(!ROUTES.has(deps.as_ref().storage, "uatom".to_string()));
+
+    let swap_reply = |offer: &str, ask: &str| Reply {
+        id: SWAP_REPLY_ID,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![Event::new("wasm").add_attributes([
+                attr("action", "swap"),
+                attr("offer_asset", offer),
+                attr("ask_asset", ask),
+                attr("offer_amount", "50"),
+                attr("return_amount", "50"),
+                attr("spread_amount", "0"),
+            ])],
+            data: None,
+        }),
+    };
+
+    let res = reply(deps.as_mut(), mock_env(), swap_reply("uatom", "uluna")).unwrap();
+    assert!(res
+        .attributes
+        .contains(&attr("learned_route", "uatom:uluna")));
+    assert!(!ROUTES.has(deps.as_ref().storage, "ufee".to_string()));
+    assert_eq!(
+        PAIR_TYPES
+            .load(deps.as_ref().storage, "atom-luna".to_string())
+            .unwrap(),
+        PairType::Xyk {}
+    );
+
+    // The next hop of the bridged amount doesn't learn anything
+    let res = reply(deps.as_mut(), mock_env(), swap_reply("uluna", ROIDS)).unwrap();
+    assert!(!res.attributes.iter().any(|a| a.key == "learned_route"));
+
+    let res = reply(deps.as_mut(), mock_env(), swap_reply("ufee", ROIDS)).unwrap();
+    assert!(res
+        .attributes
+        .contains(&attr("learned_route", "ufee:roids-token")));
+
+    let res: BridgesResponse = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Bridges {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    // Fee tokens are compared by key as the mock API accepts denoms as addresses
+    let by_key = |bridges: Vec<BridgeInfo>| {
+        bridges
+            .into_iter()
+            .map(|bridge| (bridge.asset.to_string(), bridge.route))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        by_key(res.bridges),
+        vec![
+            (
+                "uatom".to_string(),
+                vec![native_asset_info("uluna".to_string())]
+            ),
+            ("ufee".to_string(), vec![roids()]),
+        ]
+    );
+
+    // Bridges are paginated by fee token
+    let res: BridgesResponse = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Bridges {
+                start_after: Some(uatom),
+                limit: Some(1),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        by_key(res.bridges),
+        vec![("ufee".to_string(), vec![roids()])]
+    );
+}
+
+#[test]
+fn bridges_pagination() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let uluna = native_asset_info("uluna".to_string());
+    for i in 0..35 {
+        ROUTES
+            .save(
+                deps.as_mut().storage,
+                format!("ufee{i:02}"),
+                &vec![uluna.clone()],
+            )
+            .unwrap();
+    }
+    // Routes of several hops aren't bridges
+    ROUTES
+        .save(
+            deps.as_mut().storage,
+            "ufee05a".to_string(),
+            &vec![uluna.clone(), roids()],
+        )
+        .unwrap();
+
+    let bridges = |deps: Deps, start_after: Option<&str>, limit: Option<u32>| {
+        let res: BridgesResponse = from_json(
+            query(
+                deps,
+                mock_env(),
+                QueryMsg::Bridges {
+                    start_after: start_after.map(|denom| native_asset_info(denom.to_string())),
+                    limit,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res.bridges
+            .into_iter()
+            .map(|bridge| bridge.asset.to_string())
+            .collect::<Vec<_>>()
+    };
+    let expected =
+        |range: std::ops::Range<u32>| range.map(|i| format!("ufee{i:02}")).collect::<Vec<_>>();
+
+    // 10 bridges are returned by default, at most 30
+    assert_eq!(bridges(deps.as_ref(), None, None), expected(0..10));
+    assert_eq!(bridges(deps.as_ref(), None, Some(100)), expected(0..30));
+
+    // Pages start after the fee token and skip the full routes
+    assert_eq!(
+        bridges(deps.as_ref(), Some("ufee03"), Some(4)),
+        expected(4..8)
+    );
+    assert_eq!(
+        bridges(deps.as_ref(), Some("ufee30"), None),
+        expected(31..35)
+    );
+    assert!(bridges(deps.as_ref(), Some("ufee34"), None).is_empty());
+}
+
+#[test]
+fn swap_preview() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uluna = native_asset_info("uluna".to_string());
+    deps.querier.with_pair_rate(
+        &[ufee.clone(), uluna.clone()],
+        "fee-luna",
+        Decimal::from_ratio(3u128, 1u128),
+    );
+    deps.querier
+        .with_pair(&[uluna.clone(), roids()], "luna-roids");
+
+    let res: SwapPreviewResponse = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SwapPreview {
+                asset: ufee,
+                amount: Uint128::new(100),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        SwapPreviewResponse {
+            route: vec![uluna, roids()],
+            expected_roids: Uint128::new(300),
+            // The default max spread of 5% is applied on both swaps
+            conservative_roids: Uint128::new(270),
+        }
+    );
+}
+
+#[test]
+fn limit_semantics() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+
+    let simulate = |limit: u128, limit_semantics: Option<LimitSemantics>| {
+        let res: SimulateCollectResponse = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::SimulateCollect {
+                    assets: vec![AssetWithLimit {
+                        info: ufee.clone(),
+                        limit: Some(Uint128::new(limit)),
+                        limit_semantics,
+                    }],
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res.roids_amount.u128()
+    };
+
+    // A cap limits the swapped amount, a floor skips balances below it
+    assert_eq!(simulate(40, None), 40);
+    assert_eq!(simulate(400, Some(LimitSemantics::Cap)), 100);
+    assert_eq!(simulate(40, Some(LimitSemantics::Floor)), 100);
+    assert_eq!(simulate(400, Some(LimitSemantics::Floor)), 0);
+}
+
+#[test]
+fn collect_id() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    let start = mock_env().block.time.seconds();
+
+    for id in ["1", "2"] {
+        let res = collect_at(deps.as_mut(), start).unwrap();
+        assert!(res.attributes.contains(&attr("collect_id", id)));
+    }
+
+    // Replies and their events are tagged with the collect they belong to
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: SWAP_REPLY_ID,
+            result: SubMsgResult::Err("swap failed".to_string()),
+        },
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("collect_id", "2")));
+}
+
+#[test]
+fn distribution_mode() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(1000));
+
+    let update_mode = |distribution_mode| ExecuteMsg::ProposeConfigChange {
+        factory_contract: None,
+        roids_token: None,
+        asteroid_contract: None,
+        config_change_delay_secs: None,
+        router_contract: None,
+        second_receiver_params: None,
+        dev_fund_config: None,
+        distribution_mode: Some(distribution_mode),
+        bridge_call: None,
+        external_dexes: None,
+    };
+    let distribute = |deps: DepsMut| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(MOCK_CONTRACT_ADDR, &[]),
+            ExecuteMsg::DistributeAstro {},
+        )
+        .unwrap()
+    };
+
+    // ROIDS is bridged to the Hub by default
+    let res = distribute(deps.as_mut());
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].id, BURN_REPLY_ID);
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ROIDS.to_string(),
+            msg: to_json_binary(&cw20::Cw20ExecuteMsg::Transfer {
+                recipient: "asteroid".to_string(),
+                amount: Uint128::new(1000),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+
+    // The distribution mode decides where fees end up, so it only changes after the delay
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_mode(DistributionMode::Burn),
+    )
+    .unwrap();
+    let res = distribute(deps.as_mut());
+    assert!(res
+        .attributes
+        .contains(&attr("distribution_mode", "bridge_to_hub")));
+
+    change_config(deps.as_mut(), update_mode(DistributionMode::Burn)).unwrap();
+    let res = distribute(deps.as_mut());
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ROIDS.to_string(),
+            msg: to_json_binary(&cw20::Cw20ExecuteMsg::Burn {
+                amount: Uint128::new(1000),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+    assert!(res.attributes.contains(&attr("distribution_mode", "burn")));
+
+    // ROIDS below the threshold stays in the Maker
+    change_config(
+        deps.as_mut(),
+        update_mode(DistributionMode::Hold {
+            threshold: Uint128::new(2000),
+        }),
+    )
+    .unwrap();
+    let res = distribute(deps.as_mut());
+    assert!(res.messages.is_empty());
+    assert!(res.attributes.contains(&attr("held_roids", "1000")));
+
+    // A permissionless flush respects the threshold, only the owner can release the ROIDS early
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::FlushRoids {},
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+    assert!(res.attributes.contains(&attr("held_roids", "1000")));
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(60);
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info(OWNER, &[]),
+        ExecuteMsg::FlushRoids {},
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert!(res.attributes.contains(&attr("roids_amount", "1000")));
+
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(2000));
+    let res = distribute(deps.as_mut());
+    assert_eq!(res.messages.len(), 1);
+    assert!(res.attributes.contains(&attr("roids_amount", "2000")));
+}
+
+#[test]
+fn reserve_amount() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(1000));
+
+    let update_reserve = |reserve_amount| ExecuteMsg::UpdateConfig {
+        basic_asset: None,
+        max_spread: None,
+        collect_cooldown: None,
+        swap_deadline_secs: None,
+        auto_pause_threshold: None,
+        burn_every_n_collects: None,
+        dust_threshold: None,
+        keeper_commit_window_secs: None,
+        min_receive_buffer_bps: None,
+        lenient_burn: None,
+        max_bridges: None,
+        strict_collect: None,
+        post_unpause_grace_secs: None,
+        burn_retry_limit: None,
+        learn_routes: None,
+        keeper_fee_bps: None,
+        guardian: None,
+        seize_delay_secs: None,
+        min_collect_amount: None,
+        quarantine_failed_swaps: None,
+        max_collect_value_per_window: None,
+        bridges_execution_max_depth: None,
+        tokenfactory_burn: None,
+        max_assets_per_tx: None,
+        belief_price_source: None,
+        on_spread_exceeded: None,
+        reserve_amount: Some(Uint128::new(reserve_amount)),
+        auction_fallback: None,
+        operator: None,
+    };
+    let distribute = |deps: DepsMut| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(MOCK_CONTRACT_ADDR, &[]),
+            ExecuteMsg::DistributeAstro {},
+        )
+        .unwrap()
+    };
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_reserve(300),
+    )
+    .unwrap();
+
+    // Only the balance above the reserve is distributed
+    let res = distribute(deps.as_mut());
+    assert!(res.attributes.contains(&attr("reserved_roids", "300")));
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ROIDS.to_string(),
+            msg: to_json_binary(&cw20::Cw20ExecuteMsg::Transfer {
+                recipient: "asteroid".to_string(),
+                amount: Uint128::new(700),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+
+    // A balance within the reserve stays in the Maker
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(300));
+    let res = distribute(deps.as_mut());
+    assert!(res.messages.is_empty());
+
+    // Zero disables the reserve
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_reserve(0),
+    )
+    .unwrap();
+    let res = distribute(deps.as_mut());
+    assert_eq!(res.messages.len(), 1);
+    assert!(res.attributes.contains(&attr("roids_amount", "300")));
+}
+
+#[test]
+fn bridge_call() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(1000));
+
+    let res: BridgeCallResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::BridgeCall {}).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        BridgeCallResponse {
+            contract: Addr::unchecked("asteroid"),
+            msg: None,
+        }
+    );
+
+    change_config(
+        deps.as_mut(),
+        ExecuteMsg::ProposeConfigChange {
+            factory_contract: None,
+            roids_token: None,
+            asteroid_contract: None,
+            config_change_delay_secs: None,
+            router_contract: None,
+            second_receiver_params: None,
+            dev_fund_config: None,
+            distribution_mode: None,
+            bridge_call: Some(BridgeCall {
+                destination: "cosmos1burn".to_string(),
+                memo: Some("burn".to_string()),
+            }),
+            external_dexes: None,
+        },
+    )
+    .unwrap();
+
+    let payload = to_json_binary(&AsteroidBridgeExecuteMsg::Bridge {
+        destination: "cosmos1burn".to_string(),
+        memo: Some("burn".to_string()),
+    })
+    .unwrap();
+    let res: BridgeCallResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::BridgeCall {}).unwrap()).unwrap();
+    assert_eq!(res.msg, Some(payload.clone()));
+
+    // ROIDS is sent to the bridge along with the payload
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::DistributeAstro {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ROIDS.to_string(),
+            msg: to_json_binary(&cw20::Cw20ExecuteMsg::Send {
+                contract: "asteroid".to_string(),
+                amount: Uint128::new(1000),
+                msg: payload,
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+}
+
+#[test]
+fn ibc_transfer_distribution() {
+    let mut deps = mock_dependencies(&[coin(1000, "uroids")]);
+    instantiate_maker(deps.as_mut());
+
+    let ibc_transfer = DistributionMode::IbcTransfer {
+        channel: "channel-0".to_string(),
+        receiver: "cosmos1burn".to_string(),
+        timeout_secs: 600,
+        memo: r#"{"inscription":"urn:inscription:burn"}"#.to_string(),
+    };
+    let update = |distribution_mode| ExecuteMsg::ProposeConfigChange {
+        factory_contract: None,
+        roids_token: None,
+        asteroid_contract: None,
+        config_change_delay_secs: None,
+        router_contract: None,
+        second_receiver_params: None,
+        dev_fund_config: None,
+        distribution_mode: Some(distribution_mode),
+        bridge_call: None,
+        external_dexes: None,
+    };
+
+    // A CW20 ROIDS can't be sent over ICS20
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update(ibc_transfer.clone()),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidIbcTransfer {});
+
+    change_roids(deps.as_mut(), native_asset_info("uroids".to_string())).unwrap();
+
+    // The callback is added to the memo, so it must be a JSON object
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update(DistributionMode::IbcTransfer {
+            channel: "channel-0".to_string(),
+            receiver: "cosmos1burn".to_string(),
+            timeout_secs: 600,
+            memo: "urn:inscription:burn".to_string(),
+        }),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidIbcTransfer {});
+
+    change_config(deps.as_mut(), update(ibc_transfer)).unwrap();
+
+    let env = mock_env();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::DistributeAstro {},
+    )
+    .unwrap();
+    assert_eq!(res.messages[0].id, BURN_REPLY_ID);
+    assert_eq!(
+        res.messages[0].msg,
+        astroport::ibc_transfer::ibc_transfer_msg(
+            MOCK_CONTRACT_ADDR,
+            "channel-0",
+            "cosmos1burn",
+            coin(1000, "uroids"),
+            env.block.time.plus_seconds(600),
+            format!(
+                r#"{{"ibc_callback":"{MOCK_CONTRACT_ADDR}","inscription":"urn:inscription:burn"}}"#
+            ),
+        )
+    );
+    assert!(res
+        .attributes
+        .contains(&attr("distribution_mode", "ibc_transfer")));
+
+    let sent = |mut deps: DepsMut, sequence: u8| {
+        execute(
+            deps.branch(),
+            mock_env(),
+            mock_info(MOCK_CONTRACT_ADDR, &[]),
+            ExecuteMsg::DistributeAstro {},
+        )
+        .unwrap();
+        reply(
+            deps,
+            mock_env(),
+            Reply {
+                id: BURN_REPLY_ID,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    // MsgTransferResponse { sequence }
+                    data: Some(Binary::from(vec![8, sequence])),
+                }),
+            },
+        )
+        .unwrap()
+    };
+    let burned = |deps: Deps| -> SupplyImpactResponse {
+        from_json(query(deps, mock_env(), QueryMsg::SupplyImpact {}).unwrap()).unwrap()
+    };
+
+    // A sent transfer isn't burned yet
+    let res = sent(deps.as_mut(), 7);
+    assert!(res.attributes.contains(&attr("ibc_sequence", "7")));
+    assert_eq!(burned(deps.as_ref()).total_burned, Uint128::zero());
+
+    // A timed out transfer is refunded and distributed again
+    let res = sudo(
+        deps.as_mut(),
+        mock_env(),
+        SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcTimeout {
+            channel: "channel-0".to_string(),
+            sequence: 7,
+        }),
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("refunded_roids", "1000")));
+    assert_eq!(burned(deps.as_ref()).total_burned, Uint128::zero());
+
+    // Only the acknowledged transfer counts as burned
+    sent(deps.as_mut(), 8);
+    let ack = |success| {
+        SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+            channel: "channel-0".to_string(),
+            sequence: 8,
+            ack: String::new(),
+            success,
+        })
+    };
+    sudo(deps.as_mut(), mock_env(), ack(true)).unwrap();
+    assert_eq!(burned(deps.as_ref()).total_burned, Uint128::new(1000));
+
+    // A transfer is settled once
+    let res = sudo(deps.as_mut(), mock_env(), ack(true)).unwrap();
+    assert!(res.attributes.is_empty());
+    assert_eq!(burned(deps.as_ref()).total_burned, Uint128::new(1000));
+}
+
+#[test]
+fn belief_price_source() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    let ufee = native_asset_info("ufee".to_string());
+    deps.querier
+        .with_pair_rate(&[ufee.clone(), roids()], "fee-roids", Decimal::percent(200));
+    deps.querier
+        .with_oracle("fee-oracle", roids(), Decimal::percent(160));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdatePriceOracles {
+            add: Some(vec![(
+                ufee.clone(),
+                PriceOracle {
+                    contract: "fee-oracle".to_string(),
+                    max_deviation_bps: 5000,
+                },
+            )]),
+            remove: None,
+        },
+    )
+    .unwrap();
+
+    let update_source = |belief_price_source| ExecuteMsg::UpdateConfig {
+        basic_asset: None,
+        max_spread: None,
+        collect_cooldown: None,
+        swap_deadline_secs: None,
+        auto_pause_threshold: None,
+        burn_every_n_collects: None,
+        dust_threshold: None,
+        keeper_commit_window_secs: None,
+        min_receive_buffer_bps: None,
+        lenient_burn: None,
+        max_bridges: None,
+        strict_collect: None,
+        post_unpause_grace_secs: None,
+        burn_retry_limit: None,
+        learn_routes: None,
+        keeper_fee_bps: None,
+        guardian: None,
+        seize_delay_secs: None,
+        min_collect_amount: None,
+        quarantine_failed_swaps: None,
+        max_collect_value_per_window: None,
+        bridges_execution_max_depth: None,
+        tokenfactory_burn: None,
+        max_assets_per_tx: None,
+        belief_price_source: Some(belief_price_source),
+        on_spread_exceeded: None,
+        reserve_amount: None,
+        auction_fallback: None,
+        operator: None,
+    };
+    let collect_belief_price = |deps: DepsMut| -> Option<Decimal> {
+        let res = execute(
+            deps,
+            mock_env(),
+            mock_info("keeper", &[]),
+            ExecuteMsg::Collect {
+                assets: vec![AssetWithLimit {
+                    info: ufee.clone(),
+                    limit: None,
+                    limit_semantics: None,
+                }],
+                asset_type_filter: None,
+                expected_routes: None,
+                deadline: None,
+                min_roids_out: None,
+            },
+        )
+        .unwrap();
+        match &res.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => match from_json(msg).unwrap() {
+                PairExecuteMsg::Swap {
+                    belief_price,
+                    max_spread,
+                    ..
+                } => {
+                    assert_eq!(max_spread, Some(Decimal::percent(5)));
+                    belief_price
+                }
+                _ => panic!("Unexpected message"),
+            },
+            _ => panic!("Unexpected message"),
+        }
+    };
+
+    // No belief price by default
+    assert_eq!(collect_belief_price(deps.as_mut()), None);
+
+    // The TWAP values 100 ufee at 160 ROIDS
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_source(BeliefPriceSource::Oracle),
+    )
+    .unwrap();
+    assert_eq!(
+        collect_belief_price(deps.as_mut()),
+        Some(Decimal::from_ratio(100u128, 160u128))
+    );
+
+    // The pool is manipulated to return 130 ROIDS, within the max oracle deviation
+    deps.querier
+        .with_pair_rate(&[ufee.clone(), roids()], "fee-roids", Decimal::percent(130));
+    let belief_price = collect_belief_price(deps.as_mut());
+    assert_eq!(belief_price, Some(Decimal::from_ratio(100u128, 160u128)));
+
+    // The pair measures the spread against the TWAP and reverts the swap
+    assert_max_spread(
+        belief_price,
+        Some(Decimal::percent(5)),
+        Uint128::new(100),
+        Uint128::new(130),
+        Uint128::zero(),
+    )
+    .unwrap_err();
+}
+
+#[test]
+fn tokenfactory_burn() {
+    let denom = format!("factory/{MOCK_CONTRACT_ADDR}/uroids");
+    let mut deps = mock_dependencies(&[coin(1000, &denom)]);
+    instantiate_maker(deps.as_mut());
+
+    let update = |tokenfactory_burn| ExecuteMsg::UpdateConfig {
+        basic_asset: None,
+        max_spread: None,
+        collect_cooldown: None,
+        swap_deadline_secs: None,
+        auto_pause_threshold: None,
+        burn_every_n_collects: None,
+        dust_threshold: None,
+        keeper_commit_window_secs: None,
+        min_receive_buffer_bps: None,
+        lenient_burn: None,
+        max_bridges: None,
+        strict_collect: None,
+        post_unpause_grace_secs: None,
+        burn_retry_limit: None,
+        learn_routes: None,
+        keeper_fee_bps: None,
+        guardian: None,
+        seize_delay_secs: None,
+        min_collect_amount: None,
+        quarantine_failed_swaps: None,
+        max_collect_value_per_window: None,
+        bridges_execution_max_depth: None,
+        tokenfactory_burn,
+        max_assets_per_tx: None,
+        belief_price_source: None,
+        on_spread_exceeded: None,
+        reserve_amount: None,
+        auction_fallback: None,
+        operator: None,
+    };
+
+    // The CW20 ROIDS can't be burned through the TokenFactory
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update(Some(true)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidTokenFactoryBurn {});
+
+    // Neither can a denom administered by someone else
+    change_roids(
+        deps.as_mut(),
+        native_asset_info("factory/creator/uroids".to_string()),
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update(Some(true)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidTokenFactoryBurn {});
+
+    change_roids(deps.as_mut(), native_asset_info(denom.clone())).unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update(Some(true)),
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::DistributeAstro {},
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(
+        res.messages[0].msg,
+        tf_burn_msg(MOCK_CONTRACT_ADDR, coin(1000, &denom))
+    );
+
+    // ROIDS can't be replaced with a token the Maker can't burn while the flag is set
+    let err = change_roids(deps.as_mut(), roids()).unwrap_err();
+    assert_eq!(err, ContractError::InvalidTokenFactoryBurn {});
+}
+
+#[test]
+fn second_receiver() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let update_second_receiver = |cut_bps: u16| ExecuteMsg::ProposeConfigChange {
+        factory_contract: None,
+        roids_token: None,
+        asteroid_contract: None,
+        config_change_delay_secs: None,
+        router_contract: None,
+        second_receiver_params: Some(SecondReceiverParams {
+            address: "grants".to_string(),
+            cut_bps,
+        }),
+        dev_fund_config: None,
+        distribution_mode: None,
+        bridge_call: None,
+        external_dexes: None,
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &[]),
+        update_second_receiver(1000),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_second_receiver(MAX_SECOND_RECEIVER_CUT_BPS + 1),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::IncorrectSecondReceiverCut {
+            max: MAX_SECOND_RECEIVER_CUT_BPS
+        }
+    );
+
+    change_config(deps.as_mut(), update_second_receiver(1000)).unwrap();
+    let cfg: ConfigResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(
+        cfg.second_receiver_cfg,
+        Some(SecondReceiverConfig {
+            address: Addr::unchecked("grants"),
+            cut_bps: 1000,
+        })
+    );
+
+    // The second receiver gets 10% of the distribution, the rest is burned
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(1000));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::DistributeAstro {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ROIDS.to_string(),
+            msg: to_json_binary(&cw20::Cw20ExecuteMsg::Transfer {
+                recipient: "grants".to_string(),
+                amount: Uint128::new(100),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+    assert!(res.attributes.contains(&attr("second_receiver_cut", "100")));
+    assert!(res.attributes.contains(&attr("roids_amount", "900")));
+
+    // The cut is put back into circulation and isn't burned
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: BURN_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )
+    .unwrap();
+    let res: SupplyImpactResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::SupplyImpact {}).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        SupplyImpactResponse {
+            total_burned: Uint128::new(900),
+            returned_to_circulation: Uint128::new(100),
+            net_removed: Uint128::new(800),
+        }
+    );
+
+    // A zero cut removes the second receiver
+    change_config(deps.as_mut(), update_second_receiver(0)).unwrap();
+    let cfg: ConfigResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(cfg.second_receiver_cfg, None);
+}
+
+#[test]
+fn dev_fund() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let uusd = native_asset_info("uusd".to_string());
+    let update_dev_fund = |share: Decimal, asset: AssetInfo| ExecuteMsg::ProposeConfigChange {
+        factory_contract: None,
+        roids_token: None,
+        asteroid_contract: None,
+        config_change_delay_secs: None,
+        router_contract: None,
+        second_receiver_params: None,
+        dev_fund_config: Some(DevFundConfig {
+            address: "devfund".to_string(),
+            share,
+            asset,
+        }),
+        distribution_mode: None,
+        bridge_call: None,
+        external_dexes: None,
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_dev_fund(Decimal::percent(101), uusd.clone()),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::IncorrectDevFundShare {});
+
+    // The asset must have a direct pair with ROIDS
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_dev_fund(Decimal::percent(5), uusd.clone()),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidBridgeNoPool(ROIDS.to_string(), "uusd".to_string())
+    );
+
+    deps.querier
+        .with_pair(&[roids(), uusd.clone()], "roids-uusd");
+    change_config(
+        deps.as_mut(),
+        update_dev_fund(Decimal::percent(5), uusd.clone()),
+    )
+    .unwrap();
+
+    // 5% of the ROIDS is swapped to uusd and sent to the dev fund, the rest is burned
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(1000));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::DistributeAstro {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ROIDS.to_string(),
+            msg: to_json_binary(&cw20::Cw20ExecuteMsg::Send {
+                contract: "roids-uusd".to_string(),
+                amount: Uint128::new(50),
+                msg: to_json_binary(&astroport::pair::Cw20HookMsg::Swap {
+                    ask_asset_info: Some(uusd),
+                    belief_price: None,
+                    max_spread: Some(Decimal::percent(5)),
+                    to: Some("devfund".to_string()),
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+    assert!(res.attributes.contains(&attr("roids_amount", "950")));
+
+    // Without a swap the share is sent as ROIDS
+    change_config(
+        deps.as_mut(),
+        update_dev_fund(Decimal::percent(10), roids()),
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::DistributeAstro {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ROIDS.to_string(),
+            msg: to_json_binary(&cw20::Cw20ExecuteMsg::Transfer {
+                recipient: "devfund".to_string(),
+                amount: Uint128::new(100),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+}
+
+#[test]
+fn keeper_fee() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+
+    let update_keeper_fee = |keeper_fee_bps: u16| ExecuteMsg::UpdateConfig {
+        basic_asset: None,
+        max_spread: None,
+        collect_cooldown: None,
+        swap_deadline_secs: None,
+        auto_pause_threshold: None,
+        burn_every_n_collects: None,
+        dust_threshold: None,
+        keeper_commit_window_secs: None,
+        min_receive_buffer_bps: None,
+        lenient_burn: None,
+        max_bridges: None,
+        strict_collect: None,
+        post_unpause_grace_secs: None,
+        burn_retry_limit: None,
+        learn_routes: None,
+        keeper_fee_bps: Some(keeper_fee_bps),
+        guardian: None,
+        seize_delay_secs: None,
+        min_collect_amount: None,
+        quarantine_failed_swaps: None,
+        max_collect_value_per_window: None,
+        bridges_execution_max_depth: None,
+        tokenfactory_burn: None,
+        max_assets_per_tx: None,
+        belief_price_source: None,
+        on_spread_exceeded: None,
+        reserve_amount: None,
+        auction_fallback: None,
+        operator: None,
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_keeper_fee(1001),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::IncorrectKeeperFee { max: 1000 });
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_keeper_fee(100),
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Collect {
+            assets: vec![AssetWithLimit {
+                info: ufee,
+                limit: None,
+                limit_semantics: None,
+            }],
+            asset_type_filter: None,
+            expected_routes: None,
+            deadline: None,
+            min_roids_out: None,
+        },
+    )
+    .unwrap();
+
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: SWAP_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![Event::new("wasm").add_attributes([
+                    attr("_contract_address", "fee-roids"),
+                    attr("action", "swap"),
+                    attr("offer_asset", "ufee"),
+                    attr("ask_asset", ROIDS),
+                    attr("offer_amount", "100"),
+                    attr("return_amount", "600"),
+                    attr("spread_amount", "0"),
+                ])],
+                data: None,
+            }),
+        },
+    )
+    .unwrap();
+
+    // The keeper gets 1% of the ROIDS realized by its swaps, not of ROIDS received otherwise
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(1000));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::DistributeAstro {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ROIDS.to_string(),
+            msg: to_json_binary(&cw20::Cw20ExecuteMsg::Transfer {
+                recipient: "keeper".to_string(),
+                amount: Uint128::new(6),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+    assert!(res.attributes.contains(&attr("keeper_fee", "6")));
+    assert!(res.attributes.contains(&attr("roids_amount", "994")));
+
+    // The keeper fee is put back into circulation
+    let res: SupplyImpactResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::SupplyImpact {}).unwrap()).unwrap();
+    assert_eq!(res.returned_to_circulation, Uint128::new(6));
+
+    // The keeper is paid once, a later distribution pays no fee
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::DistributeAstro {},
+    )
+    .unwrap();
+    assert!(!res.attributes.iter().any(|attr| attr.key == "keeper_fee"));
+}
+
+#[test]
+fn bridge_proposals() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), uatom.clone()], "fee-atom");
+    deps.querier
+        .with_pair(&[uatom.clone(), roids()], "atom-roids");
+    set_router(deps.as_mut(), "router").unwrap();
+
+    let propose = |deps: DepsMut, route: Vec<AssetInfo>| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::ProposeBridge {
+                asset: ufee.clone(),
+                route,
+            },
+        )
+    };
+    let review = |deps: DepsMut, sender: &str, approve: bool| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::ReviewBridgeProposal {
+                asset: ufee.clone(),
+                approve,
+            },
+        )
+    };
+    let proposals = |deps: Deps| -> Vec<BridgeProposal> {
+        from_json(query(deps, mock_env(), QueryMsg::BridgeProposals {}).unwrap()).unwrap()
+    };
+
+    // Routes are validated against the factory
+    let err = propose(deps.as_mut(), vec![roids()]).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidBridgeNoPool(ufee.to_string(), roids().to_string())
+    );
+
+    propose(deps.as_mut(), vec![uatom.clone(), roids()]).unwrap();
+    assert_eq!(
+        proposals(deps.as_ref()),
+        vec![BridgeProposal {
+            asset: ufee.clone(),
+            route: vec![uatom.clone(), roids()],
+            proposer: Addr::unchecked("anyone"),
+        }]
+    );
+    let err = propose(deps.as_mut(), vec![uatom.clone(), roids()]).unwrap_err();
+    assert_eq!(err, ContractError::BridgeProposalExists(ufee.clone()));
+
+    let err = review(deps.as_mut(), "anyone", true).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // A rejected proposal is dropped without setting the route
+    review(deps.as_mut(), OWNER, false).unwrap();
+    assert!(proposals(deps.as_ref()).is_empty());
+    let routes: Vec<(String, Vec<AssetInfo>)> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Routes {}).unwrap()).unwrap();
+    assert!(routes.is_empty());
+    let err = review(deps.as_mut(), OWNER, true).unwrap_err();
+    assert_eq!(err, ContractError::NoBridgeProposal(ufee.clone()));
+
+    propose(deps.as_mut(), vec![uatom.clone(), roids()]).unwrap();
+    review(deps.as_mut(), OWNER, true).unwrap();
+    assert!(proposals(deps.as_ref()).is_empty());
+    let routes: Vec<(String, Vec<AssetInfo>)> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Routes {}).unwrap()).unwrap();
+    assert_eq!(
+        routes,
+        vec![(ufee.to_string(), vec![uatom.clone(), roids()])]
+    );
+}
+
+#[test]
+fn update_routes() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    let uosmo = native_asset_info("uosmo".to_string());
+    let uusd = native_asset_info("uusd".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), uatom.clone()], "fee-atom");
+    deps.querier
+        .with_pair(&[uatom.clone(), uosmo.clone()], "atom-osmo");
+    deps.querier
+        .with_pair(&[uosmo.clone(), roids()], "osmo-roids");
+    deps.querier
+        .with_pair(&[uatom.clone(), uusd.clone()], "atom-usd");
+    deps.querier
+        .with_pair(&[uusd.clone(), roids()], "usd-roids");
+
+    let update_routes =
+        |deps: DepsMut, add: Vec<(AssetInfo, Vec<AssetInfo>)>, remove: Vec<AssetInfo>| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(OWNER, &[]),
+                ExecuteMsg::UpdateRoutes {
+                    add: Some(add),
+                    remove: Some(remove),
+                },
+            )
+        };
+
+    let err = update_routes(
+        deps.as_mut(),
+        vec![(ufee.clone(), vec![uatom.clone(), uosmo.clone()])],
+        vec![],
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidRoute(ufee.clone()));
+
+    let err = update_routes(
+        deps.as_mut(),
+        vec![(ufee.clone(), vec![uosmo.clone(), roids()])],
+        vec![],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidBridgeNoPool(ufee.to_string(), uosmo.to_string())
+    );
+
+    // Only the router swaps a whole route at once
+    let err = update_routes(
+        deps.as_mut(),
+        vec![(ufee.clone(), vec![uatom.clone(), uosmo.clone(), roids()])],
+        vec![],
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::RouterRequired(ufee.clone()));
+
+    set_router(deps.as_mut(), "router").unwrap();
+    update_routes(
+        deps.as_mut(),
+        vec![(ufee.clone(), vec![uatom.clone(), uosmo.clone(), roids()])],
+        vec![],
+    )
+    .unwrap();
+
+    // Only the fee token is routed, the assets on its route keep their own routes
+    let routes: Vec<(String, Vec<AssetInfo>)> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Routes {}).unwrap()).unwrap();
+    assert_eq!(
+        routes,
+        vec![(
+            "ufee".to_string(),
+            vec![uatom.clone(), uosmo.clone(), roids()]
+        )]
+    );
+    update_routes(
+        deps.as_mut(),
+        vec![(uatom.clone(), vec![uusd.clone(), roids()])],
+        vec![],
+    )
+    .unwrap();
+
+    let can_onboard = |deps: Deps, asset: AssetInfo| -> CanOnboardResponse {
+        from_json(query(deps, mock_env(), QueryMsg::CanOnboard { asset }).unwrap()).unwrap()
+    };
+    assert_eq!(
+        can_onboard(deps.as_ref(), ufee.clone()).route,
+        Some(vec![uatom.clone(), uosmo.clone(), roids()])
+    );
+    assert_eq!(
+        can_onboard(deps.as_ref(), uatom.clone()).route,
+        Some(vec![uusd.clone(), roids()])
+    );
+
+    // The router can't be removed while routes need it
+    let err = set_router(deps.as_mut(), "").unwrap_err();
+    assert_eq!(err, ContractError::RouterInUse {});
+
+    // Removing a route leaves nothing behind
+    update_routes(deps.as_mut(), vec![], vec![ufee, uatom]).unwrap();
+    let routes: Vec<(String, Vec<AssetInfo>)> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Routes {}).unwrap()).unwrap();
+    assert!(routes.is_empty());
+    set_router(deps.as_mut(), "").unwrap();
+}
+
+#[test]
+fn stored_route_swapped_at_once() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    let uosmo = native_asset_info("uosmo".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), uatom.clone()], "fee-atom");
+    deps.querier
+        .with_pair(&[uatom.clone(), uosmo.clone()], "atom-osmo");
+    deps.querier
+        .with_pair(&[uosmo.clone(), roids()], "osmo-roids");
+    // The assets on the route have routes of their own which the fee token doesn't take
+    deps.querier
+        .with_pair(&[uatom.clone(), roids()], "atom-roids");
+
+    set_router(deps.as_mut(), "router").unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateRoutes {
+            add: Some(vec![(
+                ufee.clone(),
+                vec![uatom.clone(), uosmo.clone(), roids()],
+            )]),
+            remove: None,
+        },
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Collect {
+            assets: vec![AssetWithLimit {
+                info: ufee.clone(),
+                limit: None,
+                limit_semantics: None,
+            }],
+            asset_type_filter: None,
+            expected_routes: None,
+            deadline: None,
+            min_roids_out: None,
+        },
+    )
+    .unwrap();
+
+    // The stored route is swapped in a single router swap, no bridge swaps follow
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "router".to_string(),
+            msg: to_json_binary(&astroport::router::ExecuteMsg::ExecuteSwapOperations {
+                operations: vec![
+                    astroport::router::SwapOperation::AstroSwap {
+                        offer_asset_info: ufee,
+                        ask_asset_info: uatom.clone(),
+                    },
+                    astroport::router::SwapOperation::AstroSwap {
+                        offer_asset_info: uatom,
+                        ask_asset_info: uosmo.clone(),
+                    },
+                    astroport::router::SwapOperation::AstroSwap {
+                        offer_asset_info: uosmo,
+                        ask_asset_info: roids(),
+                    },
+                ],
+                minimum_receive: None,
+                to: None,
+                max_spread: Some(Decimal::percent(5)),
+            })
+            .unwrap(),
+            funds: vec![coin(100, "ufee")],
+        })
+    );
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+            msg: to_json_binary(&ExecuteMsg::DistributeAstro {}).unwrap(),
+            funds: vec![],
+        })
+    );
+}
+
+#[test]
+fn router_swaps() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uluna = native_asset_info("uluna".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), uluna.clone()], "fee-luna");
+    deps.querier
+        .with_pair(&[uluna.clone(), roids()], "luna-roids");
+
+    change_config(
+        deps.as_mut(),
+        ExecuteMsg::ProposeConfigChange {
+            factory_contract: None,
+            roids_token: None,
+            asteroid_contract: None,
+            config_change_delay_secs: None,
+            router_contract: Some("router".to_string()),
+            second_receiver_params: None,
+            dev_fund_config: None,
+            distribution_mode: None,
+            bridge_call: None,
+            external_dexes: None,
+        },
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Collect {
+            assets: vec![AssetWithLimit {
+                info: ufee.clone(),
+                limit: None,
+                limit_semantics: None,
+            }],
+            asset_type_filter: None,
+            expected_routes: None,
+            deadline: None,
+            min_roids_out: None,
+        },
+    )
+    .unwrap();
+
+    // The whole route is swapped at once and ROIDS is distributed right after
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "router".to_string(),
+            msg: to_json_binary(&astroport::router::ExecuteMsg::ExecuteSwapOperations {
+                operations: vec![
+                    astroport::router::SwapOperation::AstroSwap {
+                        offer_asset_info: ufee,
+                        ask_asset_info: uluna.clone(),
+                    },
+                    astroport::router::SwapOperation::AstroSwap {
+                        offer_asset_info: uluna,
+                        ask_asset_info: roids(),
+                    },
+                ],
+                minimum_receive: None,
+                to: None,
+                max_spread: Some(Decimal::percent(5)),
+            })
+            .unwrap(),
+            funds: vec![coin(100, "ufee")],
+        })
+    );
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+            msg: to_json_binary(&ExecuteMsg::DistributeAstro {}).unwrap(),
+            funds: vec![],
+        })
+    );
+}
+
+#[test]
+fn router_swaps_min_received() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uluna = native_asset_info("uluna".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), uluna.clone()], "fee-luna");
+    deps.querier.with_pair(&[uluna, roids()], "luna-roids");
+
+    change_config(
+        deps.as_mut(),
+        ExecuteMsg::ProposeConfigChange {
+            factory_contract: None,
+            roids_token: None,
+            asteroid_contract: None,
+            config_change_delay_secs: None,
+            router_contract: Some("router".to_string()),
+            second_receiver_params: None,
+            dev_fund_config: None,
+            distribution_mode: None,
+            bridge_call: None,
+            external_dexes: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateMinReceived {
+            add: Some(vec![(
+                ufee.clone(),
+                MinReceived::Price(Decimal::percent(90)),
+            )]),
+            remove: None,
+        },
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Collect {
+            assets: vec![AssetWithLimit {
+                info: ufee.clone(),
+                limit: None,
+                limit_semantics: None,
+            }],
+            asset_type_filter: None,
+            expected_routes: None,
+            deadline: None,
+            min_roids_out: None,
+        },
+    )
+    .unwrap();
+
+    // The router emits a swap event per pair along the route
+    let swap_event = |offer: &str, ask: &str, offer_amount: u128, return_amount: u128| {
+        Event::new("wasm").add_attributes([
+            attr("action", "swap"),
+            attr("offer_asset", offer),
+            attr("ask_asset", ask),
+            attr("offer_amount", offer_amount.to_string()),
+            attr("return_amount", return_amount.to_string()),
+            attr("spread_amount", "0"),
+        ])
+    };
+    let router_reply = |return_amount: u128| Reply {
+        id: SWAP_REPLY_ID,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![
+                swap_event("ufee", "uluna", 100, 95),
+                swap_event("uluna", ROIDS, 95, return_amount),
+            ],
+            data: None,
+        }),
+    };
+
+    // The ROIDS returned by the last pair of the route is checked against the minimum
+    let err = reply(deps.as_mut(), mock_env(), router_reply(85)).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MinReceivedNotMet {
+            asset: ufee,
+            min_received: Uint128::new(90),
+            received: Uint128::new(85),
+        }
+    );
+
+    let res = reply(deps.as_mut(), mock_env(), router_reply(92)).unwrap();
+    let realized = res
+        .events
+        .iter()
+        .find(|event| event.ty == "effective_price")
+        .unwrap();
+    assert!(realized.attributes.contains(&attr("realized_roids", "92")));
+}
+
+#[test]
+fn router_swaps_per_hop_checks() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uluna = native_asset_info("uluna".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), uluna.clone()], "fee-luna");
+    deps.querier
+        .with_pair(&[uluna.clone(), roids()], "luna-roids");
+
+    change_config(
+        deps.as_mut(),
+        ExecuteMsg::ProposeConfigChange {
+            factory_contract: None,
+            roids_token: None,
+            asteroid_contract: None,
+            config_change_delay_secs: None,
+            router_contract: Some("router".to_string()),
+            second_receiver_params: None,
+            dev_fund_config: None,
+            distribution_mode: None,
+            bridge_call: None,
+            external_dexes: None,
+        },
+    )
+    .unwrap();
+    // The bridge asset is swapped with a tighter spread than the fee token
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateSpreads {
+            add: Some(vec![(uluna.clone(), Decimal::percent(2))]),
+            remove: None,
+        },
+    )
+    .unwrap();
+
+    let collect = ExecuteMsg::Collect {
+        assets: vec![AssetWithLimit {
+            info: ufee.clone(),
+            limit: None,
+            limit_semantics: None,
+        }],
+        asset_type_filter: None,
+        expected_routes: None,
+        deadline: None,
+        min_roids_out: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        collect.clone(),
+    )
+    .unwrap();
+
+    // The router applies the tightest max spread of the route to every hop
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "router".to_string(),
+            msg: to_json_binary(&astroport::router::ExecuteMsg::ExecuteSwapOperations {
+                operations: vec![
+                    astroport::router::SwapOperation::AstroSwap {
+                        offer_asset_info: ufee,
+                        ask_asset_info: uluna.clone(),
+                    },
+                    astroport::router::SwapOperation::AstroSwap {
+                        offer_asset_info: uluna.clone(),
+                        ask_asset_info: roids(),
+                    },
+                ],
+                minimum_receive: None,
+                to: None,
+                max_spread: Some(Decimal::percent(2)),
+            })
+            .unwrap(),
+            funds: vec![coin(100, "ufee")],
+        })
+    );
+
+    // The TWAP prices the bridge asset 50% above its pool with ROIDS
+    deps.querier
+        .with_oracle("luna-oracle", roids(), Decimal::percent(150));
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdatePriceOracles {
+            add: Some(vec![(
+                uluna,
+                PriceOracle {
+                    contract: "luna-oracle".to_string(),
+                    max_deviation_bps: 500,
+                },
+            )]),
+            remove: None,
+        },
+    )
+    .unwrap();
+
+    // The deviation of the second hop skips the fee token
+    let res = execute(deps.as_mut(), mock_env(), mock_info("keeper", &[]), collect).unwrap();
+    assert!(res
+        .attributes
+        .contains(&attr("skipped_oracle_deviation", "ufee:3333")));
+}
+
+#[test]
+fn router_swaps_hop_slippage() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let swap_event = |offer: &str, ask: &str, return_amount: u128, spread_amount: u128| {
+        Event::new("wasm").add_attributes([
+            attr("action", "swap"),
+            attr("offer_asset", offer),
+            attr("ask_asset", ask),
+            attr("offer_amount", "100"),
+            attr("return_amount", return_amount.to_string()),
+            attr("spread_amount", spread_amount.to_string()),
+        ])
+    };
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: SWAP_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![
+                    swap_event("ufee", "uluna", 100, 0),
+                    swap_event("uluna", ROIDS, 90, 10),
+                ],
+                data: None,
+            }),
+        },
+    )
+    .unwrap();
+
+    // Every pair of the router swap is recorded as a hop of its own
+    let hops: Vec<_> = res
+        .events
+        .iter()
+        .filter(|event| event.ty == "hop_slippage")
+        .collect();
+    assert_eq!(hops.len(), 2);
+    assert!(hops[1].attributes.contains(&attr("hop", "1")));
+    assert!(hops[1].attributes.contains(&attr("slippage", "0.1")));
+
+    let worst: Option<HopSlippage> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::WorstHop {}).unwrap()).unwrap();
+    assert_eq!(
+        worst,
+        Some(HopSlippage {
+            hop: 1,
+            offer_asset: "uluna".to_string(),
+            ask_asset: ROIDS.to_string(),
+            slippage: Decimal::percent(10),
+        })
+    );
+}
+
+#[test]
+fn collect_all() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee"), coin(50, "ujunk")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+    // Held but without a route to ROIDS
+    deps.querier.with_pair(
+        &[
+            native_asset_info("ujunk".to_string()),
+            native_asset_info("uother".to_string()),
+        ],
+        "junk-other",
+    );
+    // Routable but not held
+    deps.querier.with_pair(
+        &[native_asset_info("uluna".to_string()), roids()],
+        "luna-roids",
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::CollectAll { limit: None },
+    )
+    .unwrap();
+
+    // Only the held routable fee token is swapped, followed by the distribution
+    assert_eq!(res.messages.len(), 2);
+    match &res.messages[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            funds,
+            ..
+        }) => {
+            assert_eq!(contract_addr, "fee-roids");
+            assert_eq!(funds, &vec![coin(100, "ufee")]);
+        }
+        _ => panic!("Unexpected message"),
+    }
+}
+
+#[test]
+fn sudo_collect() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    deps.querier.with_pair(
+        &[native_asset_info("ufee".to_string()), roids()],
+        "fee-roids",
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::TuneRisk {
+            max_spread: Decimal::percent(5),
+            collect_cooldown: 60,
+        },
+    )
+    .unwrap();
+
+    let res = sudo(deps.as_mut(), mock_env(), SudoMsg::Collect {}).unwrap();
+    assert_eq!(res.messages.len(), 2);
+    match &res.messages[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => {
+            assert_eq!(contract_addr, "fee-roids")
+        }
+        _ => panic!("Unexpected message"),
+    }
+
+    // The next cron tick within the cooldown is skipped without an error
+    let res = sudo(deps.as_mut(), mock_env(), SudoMsg::Collect {}).unwrap();
+    assert!(res.messages.is_empty());
+    assert!(res.attributes.contains(&attr("skipped", "cooldown")));
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(60);
+    let res = sudo(deps.as_mut(), env, SudoMsg::Collect {}).unwrap();
+    assert_eq!(res.messages.len(), 2);
+}
+
+#[test]
+fn min_received() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+
+    let update_min_received = |min_received| ExecuteMsg::UpdateMinReceived {
+        add: Some(vec![(ufee.clone(), min_received)]),
+        remove: None,
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        update_min_received(MinReceived::Price(Decimal::percent(200))),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_min_received(MinReceived::Price(Decimal::zero())),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidMinReceived(ufee.clone()));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_min_received(MinReceived::Price(Decimal::percent(200))),
+    )
+    .unwrap();
+
+    let res: Vec<(String, MinReceived)> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::MinReceived {}).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        vec![(
+            "ufee".to_string(),
+            MinReceived::Price(Decimal::percent(200))
+        )]
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Collect {
+            assets: vec![AssetWithLimit {
+                info: ufee.clone(),
+                limit: None,
+                limit_semantics: None,
+            }],
+            asset_type_filter: None,
+            expected_routes: None,
+            deadline: None,
+            min_roids_out: None,
+        },
+    )
+    .unwrap();
+
+    let swap_reply = |return_amount: u128| Reply {
+        id: SWAP_REPLY_ID,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![Event::new("wasm").add_attributes([
+                attr("action", "swap"),
+                attr("offer_asset", "ufee"),
+                attr("ask_asset", ROIDS),
+                attr("offer_amount", "100"),
+                attr("return_amount", return_amount.to_string()),
+                attr("spread_amount", "0"),
+            ])],
+            data: None,
+        }),
+    };
+
+    // A sandwiched swap within the max spread still reverts the collect
+    let err = reply(deps.as_mut(), mock_env(), swap_reply(150)).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MinReceivedNotMet {
+            asset: ufee,
+            min_received: Uint128::new(200),
+            received: Uint128::new(150),
+        }
+    );
+
+    reply(deps.as_mut(), mock_env(), swap_reply(250)).unwrap();
+}
+
+#[test]
+fn price_oracles() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee"), coin(100, "uatom")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+    deps.querier
+        .with_pair(&[uatom.clone(), roids()], "atom-roids");
+    // The TWAP prices ufee 20% above the pool
+    deps.querier
+        .with_oracle("fee-oracle", roids(), Decimal::percent(120));
+
+    let update_oracles = |max_deviation_bps| ExecuteMsg::UpdatePriceOracles {
+        add: Some(vec![(
+            ufee.clone(),
+            PriceOracle {
+                contract: "fee-oracle".to_string(),
+                max_deviation_bps,
+            },
+        )]),
+        remove: None,
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        update_oracles(500),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_oracles(0),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidPriceOracle(ufee.clone()));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_oracles(500),
+    )
+    .unwrap();
+    let res: Vec<(String, PriceOracle)> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::PriceOracles {}).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        vec![(
+            "ufee".to_string(),
+            PriceOracle {
+                contract: "fee-oracle".to_string(),
+                max_deviation_bps: 500,
+            }
+        )]
+    );
+
+    let collect = |deps: DepsMut| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("keeper", &[]),
+            ExecuteMsg::Collect {
+                assets: vec![
+                    AssetWithLimit {
+                        info: ufee.clone(),
+                        limit: None,
+                        limit_semantics: None,
+                    },
+                    AssetWithLimit {
+                        info: uatom.clone(),
+                        limit: None,
+                        limit_semantics: None,
+                    },
+                ],
+                asset_type_filter: None,
+                expected_routes: None,
+                deadline: None,
+                min_roids_out: None,
+            },
+        )
+    };
+
+    // ufee deviates 1666 bps from its oracle and is left for a later collect
+    let res = collect(deps.as_mut()).unwrap();
+    assert!(res
+        .attributes
+        .contains(&attr("skipped_oracle_deviation", "ufee:1666")));
+    let swapped: Vec<_> = res
+        .messages
+        .iter()
+        .filter_map(|msg| match &msg.msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => Some(contract_addr.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert!(swapped.contains(&"atom-roids"));
+    assert!(!swapped.contains(&"fee-roids"));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdatePriceOracles {
+            add: None,
+            remove: Some(vec![ufee.clone()]),
+        },
+    )
+    .unwrap();
+    let res: Vec<(String, PriceOracle)> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::PriceOracles {}).unwrap()).unwrap();
+    assert!(res.is_empty());
+}
+
+#[test]
+fn update_spreads() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    deps.querier
+        .with_pair_spread(&[ufee.clone(), roids()], "fee-roids", Decimal::percent(10));
+
+    // The 10% spread exceeds the default 5% max spread
+    let max_collect_now = |deps: Deps| {
+        let res: MaxCollectNowResponse =
+            from_json(query(deps, mock_env(), QueryMsg::MaxCollectNow {}).unwrap()).unwrap();
+        res.roids_amount
+    };
+    assert_eq!(max_collect_now(deps.as_ref()), Uint128::zero());
+
+    let update_spreads = |max_spread| ExecuteMsg::UpdateSpreads {
+        add: Some(vec![(ufee.clone(), max_spread)]),
+        remove: None,
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        update_spreads(Decimal::percent(15)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_spreads(Decimal::percent(60)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::IncorrectMaxSpread {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_spreads(Decimal::percent(15)),
+    )
+    .unwrap();
+
+    let res: Vec<(String, Decimal)> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Spreads {}).unwrap()).unwrap();
+    assert_eq!(res, vec![("ufee".to_string(), Decimal::percent(15))]);
+
+    // The illiquid fee token is swapped with its own tolerance
+    assert_eq!(max_collect_now(deps.as_ref()), Uint128::new(90));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateSpreads {
+            add: None,
+            remove: Some(vec![ufee]),
+        },
+    )
+    .unwrap();
+    assert_eq!(max_collect_now(deps.as_ref()), Uint128::zero());
+}
+
+#[test]
+fn guardian_pause() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: None,
+            collect_cooldown: None,
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: None,
+            dust_threshold: None,
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: None,
+            lenient_burn: None,
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: None,
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: Some("guardian".to_string()),
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: None,
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Pause {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("guardian", &[]),
+        ExecuteMsg::Pause {},
+    )
+    .unwrap();
+
+    // Collects in flight are stopped along with new ones
+    for msg in [
+        ExecuteMsg::SwapBridgeAssets {
+            assets: vec![native_asset_info("uluna".to_string())],
+            depth: 1,
+        },
+        ExecuteMsg::DistributeAstro {},
+    ] {
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MOCK_CONTRACT_ADDR, &[]),
+            msg,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Paused {});
+    }
+    let err = collect_at(deps.as_mut(), mock_env().block.time.seconds()).unwrap_err();
+    assert_eq!(err, ContractError::Paused {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("guardian", &[]),
+        ExecuteMsg::Unpause {},
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::DistributeAstro {},
+    )
+    .unwrap();
+}
+
+#[test]
+fn seize() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee"), coin(50, "ujunk")]);
+    instantiate_maker(deps.as_mut());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let ujunk = native_asset_info("ujunk".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+
+    let propose_seize = |info| ExecuteMsg::ProposeSeize {
+        assets: vec![Asset {
+            info,
+            amount: Uint128::new(50),
+        }],
+        recipient: "recipient".to_string(),
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        propose_seize(ujunk.clone()),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // Routable assets are collected instead
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        propose_seize(ufee),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SeizeRoutable(native_asset_info("ufee".to_string()))
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        propose_seize(ujunk.clone()),
+    )
+    .unwrap();
+
+    let executable_at = mock_env().block.time.seconds() + DEFAULT_SEIZE_DELAY_SECS;
+    let proposal: Option<SeizeProposal> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::SeizeProposal {}).unwrap()).unwrap();
+    assert_eq!(
+        proposal,
+        Some(SeizeProposal {
+            assets: vec![Asset {
+                info: ujunk,
+                amount: Uint128::new(50),
+            }],
+            recipient: Addr::unchecked("recipient"),
+            executable_at,
+        })
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::Seize {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::SeizeNotReady { executable_at });
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(executable_at);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::Seize {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "recipient".to_string(),
+            amount: vec![coin(50, "ujunk")],
+        })
+    );
+
+    // A proposal is executed only once
+    let err = execute(
+        deps.as_mut(),
+        env,
+        mock_info(OWNER, &[]),
+        ExecuteMsg::Seize {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoSeizeProposal {});
+}
+
+#[test]
+fn min_collect_amount() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee"), coin(5, "uatom")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+    deps.querier
+        .with_pair(&[uatom.clone(), roids()], "atom-roids");
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: None,
+            collect_cooldown: None,
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: None,
+            dust_threshold: None,
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: None,
+            lenient_burn: None,
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: None,
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: Some(Uint128::new(10)),
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: None,
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+
+    let assets = vec![
+        AssetWithLimit {
+            info: ufee,
+            limit: None,
+            limit_semantics: None,
+        },
+        AssetWithLimit {
+            info: uatom,
+            limit: None,
+            limit_semantics: None,
+        },
+    ];
+
+    let res: SimulateCollectResponse = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SimulateCollect {
+                assets: assets.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.roids_amount, Uint128::new(100));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Collect {
+            assets,
+            asset_type_filter: None,
+            expected_routes: None,
+            deadline: None,
+            min_roids_out: None,
+        },
+    )
+    .unwrap();
+
+    // Only the fee token above the min collect amount is swapped
+    assert!(res.attributes.contains(&attr("skipped_dust", "uatom")));
+    assert_eq!(res.messages.len(), 2);
+    match &res.messages[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Execute { funds, .. }) => {
+            assert_eq!(funds, &vec![coin(100, "ufee")])
+        }
+        _ => panic!("Unexpected message"),
+    }
+}
+
+#[test]
+fn max_collect_value_per_window() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee"), coin(50, "uatom")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+    deps.querier
+        .with_pair(&[uatom.clone(), roids()], "atom-roids");
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: None,
+            collect_cooldown: None,
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: None,
+            dust_threshold: None,
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: None,
+            lenient_burn: None,
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: None,
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: Some(Uint128::new(120)),
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+
+    let collect_msg = ExecuteMsg::Collect {
+        assets: vec![
+            AssetWithLimit {
+                info: ufee,
+                limit: None,
+                limit_semantics: None,
+            },
+            AssetWithLimit {
+                info: uatom,
+                limit: None,
+                limit_semantics: None,
+            },
+        ],
+        asset_type_filter: None,
+        expected_routes: None,
+        deadline: None,
+        min_roids_out: None,
+    };
+    let swapped_funds = |res: &Response| -> Vec<Coin> {
+        res.messages
+            .iter()
+            .filter_map(|msg| match &msg.msg {
+                CosmosMsg::Wasm(WasmMsg::Execute { funds, .. }) if !funds.is_empty() => {
+                    Some(funds[0].clone())
+                }
+                _ => None,
+            })
+            .collect()
+    };
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        collect_msg.clone(),
+    )
+    .unwrap();
+
+    // uatom only gets what is left of the budget, the rest waits for the next window
+    assert!(res.attributes.contains(&attr("deferred", "uatom:30")));
+    assert_eq!(
+        swapped_funds(&res),
+        vec![coin(100, "ufee"), coin(20, "uatom")]
+    );
+
+    // A second collect within the same window has no budget left
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        collect_msg.clone(),
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("deferred", "ufee:100")));
+    assert!(res.attributes.contains(&attr("deferred", "uatom:50")));
+    assert!(swapped_funds(&res).is_empty());
+
+    // Without a cooldown the window lasts for a day
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(86400);
+    let res = execute(deps.as_mut(), env, mock_info("keeper", &[]), collect_msg).unwrap();
+    assert!(res.attributes.contains(&attr("deferred", "uatom:30")));
+    assert_eq!(
+        swapped_funds(&res),
+        vec![coin(100, "ufee"), coin(20, "uatom")]
+    );
+}
+
+#[test]
+fn force_collect() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee"), coin(50, "uatom")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    let start = mock_env().block.time.seconds();
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+    deps.querier
+        .with_pair(&[uatom.clone(), roids()], "atom-roids");
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: None,
+            collect_cooldown: Some(600),
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: None,
+            dust_threshold: None,
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: None,
+            lenient_burn: None,
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: None,
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: Some("guardian".to_string()),
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: Some(Uint128::new(120)),
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+
+    let assets = vec![
+        AssetWithLimit {
+            info: ufee,
+            limit: None,
+            limit_semantics: None,
+        },
+        AssetWithLimit {
+            info: uatom,
+            limit: None,
+            limit_semantics: None,
+        },
+    ];
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::ForceCollect {
+            assets: assets.clone(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // Keepers are in the cooldown
+    collect_at(deps.as_mut(), start).unwrap();
+    let err = collect_at(deps.as_mut(), start).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Cooldown {
+            next_collect_ts: start + 600
+        }
+    );
+
+    // The guardian swaps everything at once, ignoring the value budget
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("guardian", &[]),
+        ExecuteMsg::ForceCollect { assets },
+    )
+    .unwrap();
+    assert!(!res.attributes.iter().any(|attr| attr.key == "deferred"));
+    let funds: Vec<_> = res
+        .messages
+        .iter()
+        .filter_map(|msg| match &msg.msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { funds, .. }) if !funds.is_empty() => {
+                Some(funds[0].clone())
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(funds, vec![coin(100, "ufee"), coin(50, "uatom")]);
+
+    // The cooldown of the keepers is left untouched
+    let status: CollectStatusResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::CollectStatus {}).unwrap()).unwrap();
+    assert_eq!(status.last_collect_ts, start);
+    assert_eq!(status.next_allowed_ts, start + 600);
+}
+
+#[test]
+fn skip_on_spread_exceeded() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee"), coin(50, "uatom")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+    deps.querier.with_pair_spread(
+        &[uatom.clone(), roids()],
+        "atom-roids",
+        Decimal::percent(10),
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: None,
+            collect_cooldown: None,
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: None,
+            dust_threshold: None,
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: None,
+            lenient_burn: None,
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: None,
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: None,
+            max_collect_value_per_window: None,
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: Some(FailOrSkip::Skip),
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("on_spread_exceeded", "skip")));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Collect {
+            assets: vec![
+                AssetWithLimit {
+                    info: ufee,
+                    limit: None,
+                    limit_semantics: None,
+                },
+                AssetWithLimit {
+                    info: uatom,
+                    limit: None,
+                    limit_semantics: None,
+                },
+            ],
+            asset_type_filter: None,
+            expected_routes: None,
+            deadline: None,
+            min_roids_out: None,
+        },
+    )
+    .unwrap();
+
+    // uatom waits for a later collect while ufee is swapped
+    assert!(res
+        .attributes
+        .contains(&attr("skipped_spread_exceeded", "uatom")));
+    let funds: Vec<_> = res
+        .messages
+        .iter()
+        .filter_map(|msg| match &msg.msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { funds, .. }) if !funds.is_empty() => {
+                Some(funds[0].clone())
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(funds, vec![coin(100, "ufee")]);
+}
+
+#[test]
+fn auctions() {
+    let mut deps = mock_dependencies(&[coin(1000, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    let start = mock_env().block.time.seconds();
+    let env_at = |ts: u64| {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(ts);
+        env
+    };
+
+    let ufee = native_asset_info("ufee".to_string());
+    let start_auction = |amount: u128, start_price: u64| ExecuteMsg::StartAuction {
+        asset: Asset {
+            info: native_asset_info("ufee".to_string()),
+            amount: Uint128::new(amount),
+        },
+        start_price: Decimal::percent(start_price),
+        end_price: Decimal::one(),
+        duration_secs: 100,
+    };
+    let bid = |amount: u128| {
+        ExecuteMsg::Receive(cw20::Cw20ReceiveMsg {
+            sender: "bidder".to_string(),
+            amount: Uint128::new(amount),
+            msg: to_json_binary(&Cw20HookMsg::BidAuction {
+                asset_info: native_asset_info("ufee".to_string()),
+            })
+            .unwrap(),
+        })
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        start_auction(1000, 200),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // The price must decline
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        start_auction(1000, 50),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidAuction {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        start_auction(2000, 200),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AuctionExceedsBalance(ufee.clone()));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        start_auction(1000, 200),
+    )
+    .unwrap();
+
+    // Collects leave the auctioned asset alone
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Collect {
+            assets: vec![AssetWithLimit {
+                info: ufee.clone(),
+                limit: None,
+                limit_semantics: None,
+            }],
+            asset_type_filter: None,
+            expected_routes: None,
+            deadline: None,
+            min_roids_out: None,
+        },
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("skipped_auctioned", "ufee")));
+
+    let res: Option<AuctionResponse> = from_json(
+        query(
+            deps.as_ref(),
+            env_at(start + 50),
+            QueryMsg::Auction {
+                asset_info: ufee.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.unwrap().price, Decimal::percent(150));
+
+    // Only ROIDS pays for auctions
+    let err = execute(
+        deps.as_mut(),
+        env_at(start + 50),
+        mock_info("fee-token", &[]),
+        bid(300),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidAuctionPayment {});
+
+    let res = execute(
+        deps.as_mut(),
+        env_at(start + 50),
+        mock_info(ROIDS, &[]),
+        bid(300),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "bidder".to_string(),
+            amount: vec![coin(200, "ufee")],
+        })]
+    );
+
+    // The price stays at the end price, the excess payment is refunded
+    let res = execute(
+        deps.as_mut(),
+        env_at(start + 200),
+        mock_info(ROIDS, &[]),
+        bid(1000),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(BankMsg::Send {
+                to_address: "bidder".to_string(),
+                amount: vec![coin(800, "ufee")],
+            }),
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: ROIDS.to_string(),
+                msg: to_json_binary(&cw20::Cw20ExecuteMsg::Transfer {
+                    recipient: "bidder".to_string(),
+                    amount: Uint128::new(200),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+
+    // The auction sold out
+    let res: Option<AuctionResponse> = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Auction { asset_info: ufee },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, None);
+}
+
+#[test]
+fn auction_fallback() {
+    let mut deps = mock_dependencies(&[coin(1000, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let collect = ExecuteMsg::Collect {
+        assets: vec![AssetWithLimit {
+            info: ufee.clone(),
+            limit: None,
+            limit_semantics: None,
+        }],
+        asset_type_filter: None,
+        expected_routes: None,
+        deadline: None,
+        min_roids_out: None,
+    };
+    let update_fallback = |duration_secs| ExecuteMsg::UpdateConfig {
+        basic_asset: None,
+        max_spread: None,
+        collect_cooldown: None,
+        swap_deadline_secs: None,
+        auto_pause_threshold: None,
+        burn_every_n_collects: None,
+        dust_threshold: None,
+        keeper_commit_window_secs: None,
+        min_receive_buffer_bps: None,
+        lenient_burn: None,
+        max_bridges: None,
+        strict_collect: None,
+        post_unpause_grace_secs: None,
+        burn_retry_limit: None,
+        learn_routes: None,
+        keeper_fee_bps: None,
+        guardian: None,
+        seize_delay_secs: None,
+        min_collect_amount: None,
+        quarantine_failed_swaps: None,
+        max_collect_value_per_window: None,
+        bridges_execution_max_depth: None,
+        tokenfactory_burn: None,
+        max_assets_per_tx: None,
+        belief_price_source: None,
+        on_spread_exceeded: None,
+        reserve_amount: None,
+        auction_fallback: Some(AuctionFallback {
+            start_price: Decimal::percent(200),
+            end_price: Decimal::percent(50),
+            duration_secs,
+        }),
+        operator: None,
+    };
+
+    // Without the fallback a fee token without a route fails the collect
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        collect.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::CannotSwap(ufee.clone()));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_fallback(600),
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        collect.clone(),
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("auctioned", "1000ufee")));
+
+    let start = mock_env().block.time.seconds();
+    let res: Option<AuctionResponse> = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Auction {
+                asset_info: ufee.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.unwrap().auction,
+        Auction {
+            asset: Asset {
+                info: ufee,
+                amount: Uint128::new(1000),
+            },
+            start_price: Decimal::percent(200),
+            end_price: Decimal::percent(50),
+            start_ts: start,
+            end_ts: start + 600,
+        }
+    );
+
+    // The running auction is left alone by later collects
+    let res = execute(deps.as_mut(), mock_env(), mock_info("keeper", &[]), collect).unwrap();
+    assert!(res.attributes.contains(&attr("skipped_auctioned", "ufee")));
+
+    // A zero duration removes the fallback
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_fallback(0),
+    )
+    .unwrap();
+    let config: ConfigResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config.auction_fallback, None);
+}
+
+#[test]
+fn quarantine_failed_swaps() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee"), coin(50, "uatom")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), roids()], "fee-roids");
+    deps.querier
+        .with_pair(&[uatom.clone(), roids()], "atom-roids");
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            basic_asset: None,
+            max_spread: None,
+            collect_cooldown: None,
+            swap_deadline_secs: None,
+            auto_pause_threshold: None,
+            burn_every_n_collects: None,
+            dust_threshold: None,
+            keeper_commit_window_secs: None,
+            min_receive_buffer_bps: None,
+            lenient_burn: None,
+            max_bridges: None,
+            strict_collect: None,
+            post_unpause_grace_secs: None,
+            burn_retry_limit: None,
+            learn_routes: None,
+            keeper_fee_bps: None,
+            guardian: None,
+            seize_delay_secs: None,
+            min_collect_amount: None,
+            quarantine_failed_swaps: Some(true),
+            max_collect_value_per_window: None,
+            bridges_execution_max_depth: None,
+            tokenfactory_burn: None,
+            max_assets_per_tx: None,
+            belief_price_source: None,
+            on_spread_exceeded: None,
+            reserve_amount: None,
+            auction_fallback: None,
+            operator: None,
+        },
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Collect {
+            assets: vec![
+                AssetWithLimit {
+                    info: ufee.clone(),
+                    limit: None,
+                    limit_semantics: None,
+                },
+                AssetWithLimit {
+                    info: uatom.clone(),
+                    limit: None,
+                    limit_semantics: None,
+                },
+            ],
+            asset_type_filter: None,
+            expected_routes: None,
+            deadline: None,
+            min_roids_out: None,
+        },
+    )
+    .unwrap();
+    let swaps: Vec<_> = res.messages[..2]
+        .iter()
+        .map(|msg| (msg.id, msg.reply_on.clone()))
+        .collect();
+    assert_eq!(
+        swaps,
+        vec![(1000, ReplyOn::Always), (1001, ReplyOn::Always)]
+    );
+
+    // The failing pool doesn't revert the collect, its asset is queued instead
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: 1001,
+            result: SubMsgResult::Err("pool paused".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(res.events[0].ty, "swap_quarantined");
+
+    let failed: Vec<FailedSwap> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::FailedAssets {}).unwrap()).unwrap();
+    assert_eq!(
+        failed,
+        vec![FailedSwap {
+            asset: uatom.clone(),
+            error: "pool paused".to_string(),
+            collect_id: 1,
+        }]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::RetryFailed {},
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 2);
+    match &res.messages[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Execute { funds, .. }) => {
+            assert_eq!(funds, &vec![coin(50, "uatom")])
+        }
+        _ => panic!("Unexpected message"),
+    }
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::RetryFailed {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoFailedAssets {});
+}
+
+#[test]
+fn route() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uluna = native_asset_info("uluna".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), uluna.clone()], "fee-luna");
+    deps.querier
+        .with_pair(&[uluna.clone(), roids()], "luna-roids");
+
+    // ufee has no bridge and falls back to the default bridge
+    let res: RouteResponse = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Route {
+                asset_info: ufee.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.hops,
+        vec![
+            RouteHop {
+                offer_asset: ufee,
+                ask_asset: uluna.clone(),
+                pair: Addr::unchecked("fee-luna"),
+                pair_type: PairType::Xyk {},
+            },
+            RouteHop {
+                offer_asset: uluna,
+                ask_asset: roids(),
+                pair: Addr::unchecked("luna-roids"),
+                pair_type: PairType::Xyk {},
+            },
+        ]
+    );
+
+    query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Route {
+            asset_info: native_asset_info("ujunk".to_string()),
+        },
+    )
+    .unwrap_err();
+}
+
+#[test]
+fn config_change_timelock() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let propose = |asteroid_contract: Option<&str>, config_change_delay_secs| {
+        ExecuteMsg::ProposeConfigChange {
+            factory_contract: None,
+            roids_token: None,
+            asteroid_contract: asteroid_contract.map(|addr| addr.to_string()),
+            config_change_delay_secs,
+            router_contract: None,
+            second_receiver_params: None,
+            dev_fund_config: None,
+            distribution_mode: None,
+            bridge_call: None,
+            external_dexes: None,
+        }
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("attacker", &[]),
+        propose(Some("new-asteroid"), None),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        propose(None, None),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::EmptyConfigChange {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        propose(None, Some(0)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::IncorrectConfigChangeDelay {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::ApplyConfigChange {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoConfigChange {});
+
+    let now = mock_env().block.time.seconds();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        propose(Some("new-asteroid"), Some(3600)),
+    )
+    .unwrap();
+
+    let pending: Option<ConfigChange> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::PendingConfigChange {}).unwrap())
+            .unwrap();
+    assert_eq!(
+        pending,
+        Some(ConfigChange {
+            factory_contract: None,
+            roids_token: None,
+            asteroid_contract: Some(Addr::unchecked("new-asteroid")),
+            config_change_delay_secs: Some(3600),
+            router_contract: None,
+            second_receiver_params: None,
+            dev_fund_config: None,
+            distribution_mode: None,
+            bridge_call: None,
+            external_dexes: None,
+            executable_at: now + DEFAULT_CONFIG_CHANGE_DELAY_SECS,
+        })
+    );
+
+    // The change can't be applied before its delay passed
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(now + DEFAULT_CONFIG_CHANGE_DELAY_SECS - 1);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::ApplyConfigChange {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ConfigChangeNotReady {
+            executable_at: now + DEFAULT_CONFIG_CHANGE_DELAY_SECS
+        }
+    );
+
+    env.block.time = env.block.time.plus_seconds(1);
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info(OWNER, &[]),
+        ExecuteMsg::ApplyConfigChange {},
+    )
+    .unwrap();
+
+    let config: ConfigResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config.asteroid_contract, Addr::unchecked("new-asteroid"));
+    assert_eq!(config.config_change_delay_secs, Some(3600));
+
+    let pending: Option<ConfigChange> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::PendingConfigChange {}).unwrap())
+            .unwrap();
+    assert_eq!(pending, None);
+
+    // The next proposal waits for the new delay and can be dropped
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        propose(Some("other-asteroid"), None),
+    )
+    .unwrap();
+    let pending: Option<ConfigChange> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::PendingConfigChange {}).unwrap())
+            .unwrap();
+    assert_eq!(pending.unwrap().executable_at, now + 3600);
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("attacker", &[]),
+        ExecuteMsg::DropConfigChange {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::DropConfigChange {},
+    )
+    .unwrap();
+    let pending: Option<ConfigChange> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::PendingConfigChange {}).unwrap())
+            .unwrap();
+    assert_eq!(pending, None);
+}
+
+#[test]
+fn config_history() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let fee_token = native_asset_info("ufee".to_string());
+    let uluna = native_asset_info("uluna".to_string());
+    deps.querier
+        .with_pair(&[fee_token.clone(), uluna.clone()], "fee-luna");
+    deps.querier
+        .with_pair(&[uluna.clone(), roids()], "luna-roids");
+
+    let history = |deps: Deps, start_after, limit| -> Vec<ConfigHistoryEntry> {
+        from_json(
+            query(
+                deps,
+                mock_env(),
+                QueryMsg::ConfigHistory { start_after, limit },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!(history(deps.as_ref(), None, None), vec![]);
+
+    update_cooldown(deps.as_mut(), mock_env(), 60);
+
+    // Failed updates aren't recorded
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("random", &[]),
+        ExecuteMsg::UpdateBridges {
+            add: Some(vec![(fee_token.clone(), uluna.clone())]),
+            remove: None,
+        },
+    )
+    .unwrap_err();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateBridges {
+            add: Some(vec![(fee_token.clone(), uluna.clone())]),
+            remove: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateBridges {
+            add: None,
+            remove: Some(vec![fee_token.clone()]),
+        },
+    )
+    .unwrap();
+
+    let height = mock_env().block.height;
+    assert_eq!(
+        history(deps.as_ref(), None, None),
+        vec![
+            ConfigHistoryEntry {
+                id: 0,
+                height,
+                sender: Addr::unchecked(OWNER),
+                action: "set_config".to_string(),
+                changes: vec![attr("collect_cooldown", "60")],
+            },
+            ConfigHistoryEntry {
+                id: 1,
+                height,
+                sender: Addr::unchecked(OWNER),
+                action: "update_bridges".to_string(),
+                changes: vec![attr("ufee", "uluna")],
+            },
+            ConfigHistoryEntry {
+                id: 2,
+                height,
+                sender: Addr::unchecked(OWNER),
+                action: "update_bridges".to_string(),
+                changes: vec![attr("ufee", "none")],
+            },
+        ]
+    );
+
+    // Entries are paginated by ID
+    let page = history(deps.as_ref(), Some(0), Some(1));
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].id, 1);
+}
+
+#[test]
+fn operator() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let fee_token = native_asset_info("ufee".to_string());
+    let uluna = native_asset_info("uluna".to_string());
+    deps.querier
+        .with_pair(&[fee_token.clone(), uluna.clone()], "fee-luna");
+    deps.querier
+        .with_pair(&[uluna.clone(), roids()], "luna-roids");
+
+    let set_operator = |operator: &str| ExecuteMsg::UpdateConfig {
+        basic_asset: None,
+        max_spread: None,
+        collect_cooldown: None,
+        swap_deadline_secs: None,
+        auto_pause_threshold: None,
+        burn_every_n_collects: None,
+        dust_threshold: None,
+        keeper_commit_window_secs: None,
+        min_receive_buffer_bps: None,
+        lenient_burn: None,
+        max_bridges: None,
+        strict_collect: None,
+        post_unpause_grace_secs: None,
+        burn_retry_limit: None,
+        learn_routes: None,
+        keeper_fee_bps: None,
+        guardian: None,
+        seize_delay_secs: None,
+        min_collect_amount: None,
+        quarantine_failed_swaps: None,
+        max_collect_value_per_window: None,
+        bridges_execution_max_depth: None,
+        tokenfactory_burn: None,
+        max_assets_per_tx: None,
+        belief_price_source: None,
+        on_spread_exceeded: None,
+        reserve_amount: None,
+        auction_fallback: None,
+        operator: Some(operator.to_string()),
+    };
+    let update_bridges = ExecuteMsg::UpdateBridges {
+        add: Some(vec![(fee_token.clone(), uluna.clone())]),
+        remove: None,
+    };
+    let update_spreads = ExecuteMsg::UpdateSpreads {
+        add: Some(vec![(fee_token.clone(), Decimal::percent(10))]),
+        remove: None,
+    };
+
+    for msg in [update_bridges.clone(), update_spreads.clone()] {
+        let err = execute(deps.as_mut(), mock_env(), mock_info("operator", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+    }
+
+    // Only the owner can set the operator
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("operator", &[]),
+        set_operator("operator"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        set_operator("operator"),
+    )
+    .unwrap();
+    let config: ConfigResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config.operator, Some(Addr::unchecked("operator")));
+
+    for msg in [update_bridges.clone(), update_spreads.clone()] {
+        execute(deps.as_mut(), mock_env(), mock_info("operator", &[]), msg).unwrap();
+    }
+
+    // The operator can't update the config
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("operator", &[]),
+        set_operator("other"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // An empty string removes the operator
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        set_operator(""),
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("operator", &[]),
+        update_spreads,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn export_import_bridges() {
+    let ufee = native_asset_info("ufee".to_string());
+    let uosmo = native_asset_info("uosmo".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    let uluna = native_asset_info("uluna".to_string());
+    let setup = || {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_maker(deps.as_mut());
+        set_router(deps.as_mut(), "router").unwrap();
+        deps.querier
+            .with_pair(&[ufee.clone(), uatom.clone()], "fee-atom");
+        deps.querier
+            .with_pair(&[uosmo.clone(), uatom.clone()], "osmo-atom");
+        deps.querier
+            .with_pair(&[uatom.clone(), uluna.clone()], "atom-luna");
+        deps.querier
+            .with_pair(&[uluna.clone(), roids()], "luna-roids");
+        deps
+    };
+    // Fee tokens are compared by key as the mock API accepts denoms as addresses
+    let export = |deps: Deps| -> Vec<(String, Vec<AssetInfo>)> {
+        let entries: Vec<BridgeInfo> =
+            from_json(query(deps, mock_env(), QueryMsg::ExportBridges {}).unwrap()).unwrap();
+        entries
+            .into_iter()
+            .map(|entry| (entry.asset.to_string(), entry.route))
+            .collect()
+    };
+    let import = |deps: DepsMut, sender: &str, entries: Vec<BridgeInfo>| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::ImportBridges { entries },
+        )
+    };
+
+    let mut deps = setup();
+    let entries = vec![
+        BridgeInfo {
+            asset: ufee.clone(),
+            route: vec![uatom.clone()],
+        },
+        BridgeInfo {
+            asset: uatom.clone(),
+            route: vec![uluna.clone()],
+        },
+        BridgeInfo {
+            asset: uosmo.clone(),
+            route: vec![uatom.clone(), uluna.clone(), roids()],
+        },
+    ];
+
+    let err = import(deps.as_mut(), "random", entries.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // A bridge can go through a bridge imported after it
+    let res = import(deps.as_mut(), OWNER, entries).unwrap();
+    assert!(res.attributes.contains(&attr("imported", "3")));
+
+    // Routes of several hops are exported along with the bridges
+    let exported = export(deps.as_ref());
+    assert_eq!(
+        exported,
+        vec![
+            ("uatom".to_string(), vec![uluna.clone()]),
+            ("ufee".to_string(), vec![uatom.clone()]),
+            (
+                "uosmo".to_string(),
+                vec![uatom.clone(), uluna.clone(), roids()]
+            ),
+        ]
+    );
+
+    // The exported table loads into a new deployment as is
+    let entries: Vec<BridgeInfo> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::ExportBridges {}).unwrap()).unwrap();
+    let mut new_deps = setup();
+    import(new_deps.as_mut(), OWNER, entries).unwrap();
+    assert_eq!(export(new_deps.as_ref()), exported);
+
+    // Bridges without a pool are rejected
+    let err = import(
+        new_deps.as_mut(),
+        OWNER,
+        vec![BridgeInfo {
+            asset: native_asset_info("ujunk".to_string()),
+            route: vec![uluna.clone()],
+        }],
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidBridgeNoPool(..)));
+
+    // ROIDS itself can't be bridged or routed
+    let err = import(
+        new_deps.as_mut(),
+        OWNER,
+        vec![BridgeInfo {
+            asset: roids(),
+            route: vec![uluna.clone()],
+        }],
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidBridge(roids(), uluna.clone()));
+    let err = import(
+        new_deps.as_mut(),
+        OWNER,
+        vec![BridgeInfo {
+            asset: roids(),
+            route: vec![uluna, roids()],
+        }],
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidRoute(roids()));
+
+    // Routes of several hops are validated like in UpdateRoutes
+    let err = import(
+        new_deps.as_mut(),
+        OWNER,
+        vec![BridgeInfo {
+            asset: ufee,
+            route: vec![uatom, uosmo],
+        }],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidRoute(native_asset_info("ufee".to_string()))
+    );
+}
+
+#[test]
+fn distribution_hooks() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let update_hooks = |add: &[&str], remove: &[&str]| ExecuteMsg::UpdateDistributionHooks {
+        add: Some(add.iter().map(|addr| addr.to_string()).collect()),
+        remove: Some(remove.iter().map(|addr| addr.to_string()).collect()),
+    };
+    let hooks = |deps: Deps| -> Vec<Addr> {
+        from_json(query(deps, mock_env(), QueryMsg::DistributionHooks {}).unwrap()).unwrap()
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("random", &[]),
+        update_hooks(&["hook1"], &[]),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_hooks(&["hook1", "hook2"], &[]),
+    )
+    .unwrap();
+    assert_eq!(
+        hooks(deps.as_ref()),
+        vec![Addr::unchecked("hook1"), Addr::unchecked("hook2")]
+    );
+
+    // Each hook is called after the burn with its own gas limit
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(1000));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::DistributeAstro {},
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 3);
+    assert_eq!(res.messages[0].id, BURN_REPLY_ID);
+    for (msg, hook) in res.messages[1..].iter().zip(["hook1", "hook2"]) {
+        assert_eq!(
+            *msg,
+            SubMsg {
+                id: DISTRIBUTION_HOOK_REPLY_ID,
+                msg: WasmMsg::Execute {
+                    contract_addr: hook.to_string(),
+                    msg: to_json_binary(&DistributionHookMsg::AfterDistribute {
+                        amount: Uint128::new(1000)
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }
+                .into(),
+                gas_limit: Some(300_000),
+                reply_on: ReplyOn::Error,
+            }
+        );
+    }
+
+    // A failing hook doesn't revert the distribution
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: DISTRIBUTION_HOOK_REPLY_ID,
+            result: SubMsgResult::Err("out of gas".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(res.events[0].ty, "distribution_hook_failed");
+
+    // Hooks aren't called when nothing is distributed
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::DistributeAstro {},
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_hooks(&[], &["hook2"]),
+    )
+    .unwrap();
+    assert_eq!(hooks(deps.as_ref()), vec![Addr::unchecked("hook1")]);
+
+    let many: Vec<_> = (0..10).map(|i| format!("hook{i}x")).collect();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        update_hooks(&many.iter().map(|s| s.as_str()).collect::<Vec<_>>(), &[]),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::MaxDistributionHooksExceeded { max: 10 });
+}
+
+#[test]
+fn pre_upgrade_rewards() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(100));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("random", &[]),
+        ExecuteMsg::EnableRewards { blocks: 10 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::EnableRewards { blocks: 0 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::IncorrectPreUpgradeBlocks {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::EnableRewards { blocks: 10 },
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::EnableRewards { blocks: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::RewardsAlreadyEnabled {});
+
+    let distribute_at = |deps: DepsMut, blocks: u64| -> Uint128 {
+        let mut env = mock_env();
+        env.block.height += blocks;
+        let res = execute(
+            deps,
+            env,
+            mock_info(MOCK_CONTRACT_ADDR, &[]),
+            ExecuteMsg::DistributeAstro {},
+        )
+        .unwrap();
+        from_json::<DistributeResponseData>(res.data.unwrap())
+            .unwrap()
+            .amount
+    };
+
+    // Nothing is released within the block rewards were enabled in
+    assert_eq!(distribute_at(deps.as_mut(), 0), Uint128::zero());
+
+    // A tenth of the pre-upgrade ROIDS is released per block
+    assert_eq!(distribute_at(deps.as_mut(), 1), Uint128::new(10));
+
+    // New fees are distributed in full along with the released ROIDS
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(120));
+    assert_eq!(distribute_at(deps.as_mut(), 3), Uint128::new(50));
+
+    let rewards: Option<PreUpgradeRewards> =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::PreUpgradeRewards {}).unwrap())
+            .unwrap();
+    assert_eq!(
+        rewards,
+        Some(PreUpgradeRewards {
+            pre_upgrade_blocks: 10,
+            last_distribution_block: mock_env().block.height + 3,
+            rewards_amount: Uint128::new(70),
+            pre_upgrade_amount: Uint128::new(100),
+        })
+    );
+
+    // The rest is released once the blocks passed
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::new(70));
+    assert_eq!(distribute_at(deps.as_mut(), 20), Uint128::new(70));
+}
+
+#[test]
+fn transfer_tax() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee")]);
+    instantiate_maker(deps.as_mut());
+    deps.querier
+        .with_token_balance(ROIDS, MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let tax_token = token_asset_info(Addr::unchecked("tax-token"));
+    deps.querier
+        .with_pair(&[ufee.clone(), tax_token.clone()], "fee-tax");
+    deps.querier
+        .with_pair(&[tax_token.clone(), roids()], "tax-roids");
+    deps.querier
+        .with_token_balance("tax-token", MOCK_CONTRACT_ADDR, Uint128::zero());
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateBridges {
+            add: Some(vec![(ufee.clone(), tax_token.clone())]),
+            remove: None,
+        },
+    )
+    .unwrap();
+
+    let collect = |info: AssetInfo| ExecuteMsg::Collect {
+        assets: vec![AssetWithLimit {
+            info,
+            limit: None,
+            limit_semantics: None,
+        }],
+        asset_type_filter: None,
+        expected_routes: None,
+        deadline: None,
+        min_roids_out: None,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        collect(ufee),
+    )
+    .unwrap();
+
+    let swap_reply = |offer: &str, ask: &str, offer_amount: u128, return_amount: u128| Reply {
+        id: SWAP_REPLY_ID,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![Event::new("wasm").add_attributes([
+                attr("action", "swap"),
+                attr("offer_asset", offer),
+                attr("ask_asset", ask),
+                attr("offer_amount", offer_amount.to_string()),
+                attr("return_amount", return_amount.to_string()),
+                attr("spread_amount", "0"),
+            ])],
+            data: None,
+        }),
+    };
+
+    // The pair returned 100 tokens but only 90 arrived
+    deps.querier
+        .with_token_balance("tax-token", MOCK_CONTRACT_ADDR, Uint128::new(90));
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        swap_reply("ufee", "tax-token", 100, 100),
+    )
+    .unwrap();
+    assert_eq!(
+        res.events[2],
+        Event::new("transfer_tax").add_attributes([
+            attr("asset", "tax-token"),
+            attr("return_amount", "100"),
+            attr("received_amount", "90"),
+            attr("tax_rate", "0.1"),
+            attr("collect_id", "1"),
+        ])
+    );
+
+    // Only the received tokens are accounted for on the way to ROIDS
+    deps.querier
+        .with_token_balance("tax-token", MOCK_CONTRACT_ADDR, Uint128::zero());
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        swap_reply("tax-token", ROIDS, 90, 180),
+    )
+    .unwrap();
+    assert!(res.events[2]
+        .attributes
+        .contains(&attr("realized_roids", "180")));
+
+    // Swaps of the taxed token expect the pair to receive the balance net of the tax
+    deps.querier
+        .with_token_balance("tax-token", MOCK_CONTRACT_ADDR, Uint128::new(50));
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        collect(tax_token.clone()),
+    )
+    .unwrap();
+    let progress = ROUTE_PROGRESS.load(deps.as_ref().storage).unwrap();
+    assert_eq!(
+        progress,
+        vec![RouteProgress {
+            asset: tax_token.clone(),
+            input: Uint128::new(50),
+            holding: tax_token,
+            held: Uint128::new(45),
+        }]
+    );
+}
+
+#[test]
+fn simulate_swaps() {
+    let mut deps = mock_dependencies(&[coin(100, "ufee"), coin(200, "uatom"), coin(10, "ujunk")]);
+    instantiate_maker(deps.as_mut());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    let uluna = native_asset_info("uluna".to_string());
+    let ujunk = native_asset_info("ujunk".to_string());
+    deps.querier
+        .with_pair_spread(&[ufee.clone(), roids()], "fee-roids", Decimal::percent(10));
+    deps.querier.with_pair_spread(
+        &[uatom.clone(), uluna.clone()],
+        "atom-luna",
+        Decimal::percent(10),
+    );
+    deps.querier.with_pair_spread(
+        &[uluna.clone(), roids()],
+        "luna-roids",
+        Decimal::percent(20),
+    );
+
+    // The atom is swapped through the default bridge
+    let simulations: Vec<SwapSimulation> = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SimulateSwaps {
+                assets: vec![
+                    AssetWithLimit {
+                        info: ufee.clone(),
+                        limit: Some(Uint128::new(50)),
+                        limit_semantics: None,
+                    },
+                    AssetWithLimit {
+                        info: uatom.clone(),
+                        limit: None,
+                        limit_semantics: None,
+                    },
+                    AssetWithLimit {
+                        info: ujunk.clone(),
+                        limit: None,
+                        limit_semantics: None,
+                    },
+                ],
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        simulations[0],
+        SwapSimulation {
+            asset: Asset {
+                info: ufee,
+                amount: Uint128::new(50),
+            },
+            route: vec![roids()],
+            roids_amount: Uint128::new(45),
+            spread: Decimal::percent(10),
+            error: None,
+        }
+    );
+    assert_eq!(
+        simulations[1],
+        SwapSimulation {
+            asset: Asset {
+                info: uatom,
+                amount: Uint128::new(200),
+            },
+            route: vec![uluna, roids()],
+            roids_amount: Uint128::new(144),
+            spread: Decimal::percent(28),
+            error: None,
+        }
+    );
+
+    // Fee tokens without a route don't fail the query
+    assert_eq!(simulations[2].asset.info, ujunk);
+    assert_eq!(simulations[2].roids_amount, Uint128::zero());
+    assert!(simulations[2].error.is_some());
+}
+
+#[test]
+fn validate_routes() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let ufee = native_asset_info("ufee".to_string());
+    let uosmo = native_asset_info("uosmo".to_string());
+    let uatom = native_asset_info("uatom".to_string());
+    deps.querier
+        .with_pair(&[ufee.clone(), uatom.clone()], "fee-atom");
+    deps.querier
+        .with_pair(&[uosmo.clone(), uatom.clone()], "osmo-atom");
+    deps.querier
+        .with_pair(&[uatom.clone(), roids()], "atom-roids");
+    deps.querier.with_drained_pair("osmo-atom");
+
+    for asset in ["ufee", "uosmo", "ugone"] {
+        ROUTES
+            .save(
+                deps.as_mut().storage,
+                asset.to_string(),
+                &vec![uatom.clone()],
+            )
+            .unwrap();
+    }
+
+    let validate = |deps: Deps, assets: Option<Vec<AssetInfo>>| -> Vec<BrokenRoute> {
+        from_json(query(deps, mock_env(), QueryMsg::ValidateRoutes { assets }).unwrap()).unwrap()
+    };
+
+    // Delisted and drained pools are reported
+    assert_eq!(
+        validate(deps.as_ref(), None),
+        vec![
+            BrokenRoute {
+                asset: "ugone".to_string(),
+                bridge: uatom.clone(),
+                error: "Invalid bridge. Pool ugone to uatom not found".to_string(),
+            },
+            BrokenRoute {
+                asset: "uosmo".to_string(),
+                bridge: uatom.clone(),
+                error: "Pool osmo-atom has no liquidity".to_string(),
+            },
+        ]
+    );
+
+    // Only the specified fee tokens are checked
+    assert_eq!(
+        validate(
+            deps.as_ref(),
+            Some(vec![
+                ufee.clone(),
+                uosmo,
+                native_asset_info("ujunk".to_string())
+            ])
+        ),
+        vec![BrokenRoute {
+            asset: "uosmo".to_string(),
+            bridge: uatom.clone(),
+            error: "Pool osmo-atom has no liquidity".to_string(),
+        }]
+    );
+
+    // The pool of the bridge asset with ROIDS is checked as well
+    deps.querier.with_drained_pair("atom-roids");
+    assert_eq!(
+        validate(deps.as_ref(), Some(vec![ufee])),
+        vec![BrokenRoute {
+            asset: "ufee".to_string(),
+            bridge: uatom,
+            error: "Pool atom-roids has no liquidity".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn update_bridges_shared_chain() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_maker(deps.as_mut());
+
+    let uatom = native_asset_info("uatom".to_string());
+    let uosmo = native_asset_info("uosmo".to_string());
+    deps.querier
+        .with_pair(&[uosmo.clone(), uatom.clone()], "osmo-atom");
+    deps.querier
+        .with_pair(&[uatom.clone(), roids()], "atom-roids");
+
+    // Every fee token goes through the same bridges, one of them is listed twice
+    for i in 0..5 {
+        deps.querier.with_pair(
+            &[native_asset_info(format!("ufee{i}")), uosmo.clone()],
+            &format!("fee{i}-osmo"),
+        );
+    }
+    let mut add = vec![(uosmo.clone(), uatom.clone())];
+    for i in [0, 1, 2, 3, 4, 0] {
+        add.push((native_asset_info(format!("ufee{i}")), uosmo.clone()));
+    }
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateBridges {
+            add: Some(add),
+            remove: None,
+        },
+    )
+    .unwrap();
+
+    let broken: Vec<BrokenRoute> = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ValidateRoutes { assets: None },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(broken, vec![]);
+    assert_eq!(
+        ROUTES
+            .keys(deps.as_ref().storage, None, None, Order::Ascending)
+            .count(),
+        6
+    );
+}
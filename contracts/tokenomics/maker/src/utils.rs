@@ -1,62 +1,354 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
 use cosmwasm_std::{
-    coins, to_json_binary, wasm_execute, Addr, Binary, CosmosMsg, Decimal, Deps, Empty, Env,
-    QuerierWrapper, StdError, StdResult, SubMsg, Uint128, WasmMsg,
+    coin, coins, to_json_binary, wasm_execute, Addr, Api, Attribute, BankMsg, Binary, CosmosMsg,
+    Decimal, Deps, DepsMut, Empty, Env, Order, QuerierWrapper, Response, StdError, StdResult,
+    Storage, SubMsg, Uint128, Uint256, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
+use cw_storage_plus::Bound;
 
 use astroport::asset::{Asset, AssetInfo, PairInfo};
+use astroport::factory::PairType;
+use astroport::ibc_transfer::ibc_transfer_msg;
 use astroport::maker::{
-    Config, ExecuteMsg, COOLDOWN_LIMITS,
+    AssetWithLimit, AsteroidBridgeExecuteMsg, Auction, BeliefPriceSource, BurnDestinationSource,
+    Config, ConfigHistoryEntry, DevFundConfig, DistributionMode, ExecuteMsg, ExternalDex,
+    LimitSemantics, MinReceived, PriceOracle, Role, BRIDGES_EXECUTION_DEPTH_LIMITS,
+    COOLDOWN_LIMITS, MAX_KEEPER_FEE_BPS, MAX_MIN_RECEIVE_BUFFER_BPS,
+};
+use astroport::oracle::QueryMsg as OracleQueryMsg;
+use astroport::pair::{Cw20HookMsg, PoolResponse, QueryMsg as PairQueryMsg, MAX_ALLOWED_SLIPPAGE};
+use astroport::querier::{query_pair_info, simulate};
+use astroport::router::{
+    Cw20HookMsg as RouterCw20HookMsg, ExecuteMsg as RouterExecuteMsg, SwapOperation,
 };
-use astroport::pair::Cw20HookMsg;
-use astroport::querier::query_pair_info;
+use astroport::token_factory::tf_burn_msg;
 
 use crate::error::ContractError;
-use crate::state::BRIDGES;
+use crate::state::{
+    COLLECT_ID, CONFIG_HISTORY, DUST_BRIDGE_BALANCES, EXTERNAL_DEXES, PAIR_TYPES, PRICE_ORACLES,
+    ROLES, ROUTES, SPREADS, STATS, TOKEN_BALANCES, TOTAL_BURNED, TRANSFER_TAXES,
+};
 
 /// The default bridge depth for a fee token
 pub const BRIDGES_INITIAL_DEPTH: u64 = 0;
 /// Maximum amount of bridges to use in a multi-hop swap
 pub const BRIDGES_MAX_DEPTH: u64 = 2;
-/// Swap execution depth limit
+/// Default swap execution depth limit
 pub const BRIDGES_EXECUTION_MAX_DEPTH: u64 = 5;
+/// Share of the balance of a rebasing asset (in bps) left out of collects to absorb rebases
+pub const REBASE_DRIFT_BUFFER_BPS: u16 = 100;
 
-/// The function checks from<>to pool exists and creates swap message.
-///
-/// * **from** asset we want to swap.
+/// Bridges validated within a single execution along with their pool and the depth they were
+/// validated at, keyed by the fee token and its bridge
+pub type BridgeMemo = HashMap<(String, String), (u64, PairInfo)>;
+
+/// Returns the amount of a fee token to swap during a collect: the Maker's balance
+/// capped by the asset limit if one is set, or zero if the limit is a floor the balance doesn't
+/// reach. Only part of the balance of a rebasing asset is
+/// collected so a negative rebase before the swap doesn't make it fail.
 ///
-/// * **to** asset we want to swap to.
+/// * **contract_addr** maker contract address.
 ///
-/// * **amount_in** amount of tokens to swap.
-pub fn try_build_swap_msg(
+/// * **asset** fee token along with an optional limit.
+pub fn get_collect_amount(
+    deps: Deps,
+    cfg: &Config,
+    contract_addr: &Addr,
+    asset: &AssetWithLimit,
+) -> StdResult<Uint128> {
+    let mut balance = asset.info.query_pool(&deps.querier, contract_addr)?;
+    if cfg.rebasing_assets.contains(&asset.info) {
+        balance = balance.multiply_ratio(10000u16 - REBASE_DRIFT_BUFFER_BPS, 10000u16);
+    }
+    if let Some(limit) = asset.limit.filter(|limit| !limit.is_zero()) {
+        match asset.limit_semantics.clone().unwrap_or_default() {
+            LimitSemantics::Cap => balance = balance.min(limit),
+            // Assets below the floor are skipped
+            LimitSemantics::Floor if balance < limit => balance = Uint128::zero(),
+            LimitSemantics::Floor => {}
+        }
+    }
+
+    Ok(balance)
+}
+
+/// Builds the message paying the dev fund its share of a distribution. The share is swapped to
+/// the asset of the dev fund with the pair sending the result straight to the fund.
+pub fn build_dev_fund_msg(
     querier: &QuerierWrapper,
     cfg: &Config,
-    from: &AssetInfo,
-    to: &AssetInfo,
-    amount_in: Uint128,
+    dev_fund: &DevFundConfig,
+    amount: Uint128,
 ) -> Result<SubMsg, ContractError> {
-    let pool = get_pool(querier, &cfg.factory_contract, from, to)?;
-    let msg = build_swap_msg(cfg.max_spread, &pool, from, Some(to), amount_in)?;
-    Ok(msg)
+    let share = Asset {
+        info: cfg.roids_token.clone(),
+        amount,
+    };
+    if dev_fund.asset == cfg.roids_token {
+        return Ok(SubMsg::new(
+            share.into_msg::<Empty>(dev_fund.address.clone())?,
+        ));
+    }
+
+    let pool = get_pool(querier, &cfg.factory_contract, &share.info, &dev_fund.asset)?;
+    build_swap_msg(
+        get_max_spread(cfg, &pool.pair_type)?,
+        &pool.contract_addr,
+        &share.info,
+        Some(&dev_fund.asset),
+        amount,
+        None,
+        Some(dev_fund.address.clone()),
+    )
+}
+
+/// Validates the dev fund config. The asset of the dev fund must be ROIDS or have a direct pair
+/// with it.
+pub fn validate_dev_fund(
+    deps: Deps,
+    cfg: &Config,
+    dev_fund: &DevFundConfig,
+) -> Result<(), ContractError> {
+    if dev_fund.share > Decimal::one() {
+        return Err(ContractError::IncorrectDevFundShare {});
+    }
+    deps.api.addr_validate(&dev_fund.address)?;
+    dev_fund.asset.check(deps.api)?;
+    if dev_fund.asset != cfg.roids_token {
+        get_pool(
+            &deps.querier,
+            &cfg.factory_contract,
+            &cfg.roids_token,
+            &dev_fund.asset,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Builds the message applying the terminal action of the distribution mode to distributed ROIDS.
+pub fn build_distribution_msg(
+    env: &Env,
+    cfg: &Config,
+    amount: Uint128,
+) -> Result<CosmosMsg, ContractError> {
+    match (
+        burn_destination_source(cfg),
+        &cfg.distribution_mode,
+        &cfg.roids_token,
+    ) {
+        // The Maker administers ROIDS so it burns it directly, whatever the distribution mode
+        (BurnDestinationSource::TokenfactoryBurn, _, AssetInfo::NativeToken { denom }) => Ok(
+            tf_burn_msg(env.contract.address.as_str(), coin(amount.u128(), denom)),
+        ),
+        (BurnDestinationSource::Burn, _, AssetInfo::Token { contract_addr }) => {
+            Ok(CosmosMsg::Wasm(wasm_execute(
+                contract_addr,
+                &Cw20ExecuteMsg::Burn { amount },
+                vec![],
+            )?))
+        }
+        (BurnDestinationSource::Burn, _, AssetInfo::NativeToken { denom }) => {
+            Ok(CosmosMsg::Bank(BankMsg::Burn {
+                amount: coins(amount.u128(), denom),
+            }))
+        }
+        (
+            BurnDestinationSource::IbcReceiver,
+            DistributionMode::IbcTransfer {
+                channel,
+                receiver,
+                timeout_secs,
+                memo,
+            },
+            AssetInfo::NativeToken { denom },
+        ) => Ok(ibc_transfer_msg(
+            env.contract.address.as_str(),
+            channel,
+            receiver,
+            coin(amount.u128(), denom),
+            env.block.time.plus_seconds(*timeout_secs),
+            callback_memo(memo, &env.contract.address),
+        )),
+        (BurnDestinationSource::IbcReceiver, ..) => Err(ContractError::InvalidIbcTransfer {}),
+        _ => build_bridge_msg(cfg, amount),
+    }
+}
+
+/// Returns the setting distributed ROIDS is directed by. A TokenFactory burn takes precedence
+/// over the distribution mode.
+fn burn_destination_source(cfg: &Config) -> BurnDestinationSource {
+    if cfg.tokenfactory_burn && cfg.roids_token.is_native_token() {
+        return BurnDestinationSource::TokenfactoryBurn;
+    }
+
+    match cfg.distribution_mode {
+        DistributionMode::Burn => BurnDestinationSource::Burn,
+        DistributionMode::IbcTransfer { .. } => BurnDestinationSource::IbcReceiver,
+        DistributionMode::BridgeToHub | DistributionMode::Hold { .. } => {
+            BurnDestinationSource::AsteroidContract
+        }
+    }
+}
+
+/// Adds the IBC hooks callback of the Maker to the memo of an ICS20 transfer, so the Maker learns
+/// whether the Hub received ROIDS. The memo is validated to be empty or a JSON object.
+pub fn callback_memo(memo: &str, contract: &Addr) -> String {
+    let callback = format!(r#""ibc_callback":"{contract}""#);
+    match memo.trim().strip_prefix('{') {
+        Some(fields) if !fields.trim_start().starts_with('}') => format!("{{{callback},{fields}"),
+        _ => format!("{{{callback}}}"),
+    }
+}
+
+/// Accounts ROIDS which left circulation in the burn totals and distribution statistics.
+pub fn record_burn(storage: &mut dyn Storage, env: &Env, burned: Uint128) -> StdResult<()> {
+    let total = TOTAL_BURNED.may_load(storage)?.unwrap_or_default();
+    TOTAL_BURNED.save(storage, &(total + burned))?;
+
+    let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+    stats.total_roids += burned;
+    stats.distributions += 1;
+    stats.last_distribution_amount = burned;
+    stats.last_distribution_ts = Some(env.block.time.seconds());
+    STATS.save(storage, &stats)
+}
+
+/// Returns the execute message the Asteroid bridge contract is called with, if a bridge call is
+/// configured.
+pub fn bridge_payload(cfg: &Config) -> Option<AsteroidBridgeExecuteMsg> {
+    cfg.bridge_call
+        .as_ref()
+        .map(|call| AsteroidBridgeExecuteMsg::Bridge {
+            destination: call.destination.clone(),
+            memo: call.memo.clone(),
+        })
+}
+
+/// Builds the message sending ROIDS to the Asteroid bridge contract. ROIDS is wrapped in the
+/// configured bridge call, or transferred as is without one.
+fn build_bridge_msg(cfg: &Config, amount: Uint128) -> Result<CosmosMsg, ContractError> {
+    let payload = match bridge_payload(cfg) {
+        Some(payload) => payload,
+        None => {
+            return Ok(Asset {
+                info: cfg.roids_token.clone(),
+                amount,
+            }
+            .into_msg(cfg.asteroid_contract.clone())?)
+        }
+    };
+
+    let msg = match &cfg.roids_token {
+        AssetInfo::Token { contract_addr } => wasm_execute(
+            contract_addr,
+            &Cw20ExecuteMsg::Send {
+                contract: cfg.asteroid_contract.to_string(),
+                amount,
+                msg: to_json_binary(&payload)?,
+            },
+            vec![],
+        )?,
+        AssetInfo::NativeToken { denom } => wasm_execute(
+            &cfg.asteroid_contract,
+            &payload,
+            coins(amount.u128(), denom),
+        )?,
+    };
+
+    Ok(CosmosMsg::Wasm(msg))
+}
+
+/// Validates the distribution mode.
+pub fn validate_distribution_mode(
+    cfg: &Config,
+    mode: &DistributionMode,
+) -> Result<(), ContractError> {
+    if let DistributionMode::IbcTransfer {
+        channel,
+        receiver,
+        timeout_secs,
+        memo,
+    } = mode
+    {
+        // The callback of the Maker is added to the memo, which therefore must be a JSON object
+        let memo = memo.trim();
+        let json_memo = memo.is_empty() || (memo.starts_with('{') && memo.ends_with('}'));
+        if !cfg.roids_token.is_native_token()
+            || channel.is_empty()
+            || receiver.is_empty()
+            || *timeout_secs == 0
+            || !json_memo
+            || memo.contains("\"ibc_callback\"")
+        {
+            return Err(ContractError::InvalidIbcTransfer {});
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates the external DEX of a fee token. The fee token can't be ROIDS and the DEX must swap
+/// it to another asset.
+pub fn validate_external_dex(
+    deps: Deps,
+    cfg: &Config,
+    asset: &AssetInfo,
+    dex: &ExternalDex,
+) -> Result<(), ContractError> {
+    if *asset == cfg.roids_token || dex.ask_asset == *asset {
+        return Err(ContractError::InvalidExternalDex(asset.clone()));
+    }
+    dex.ask_asset.check(deps.api)?;
+    deps.api.addr_validate(&dex.adapter)?;
+
+    Ok(())
+}
+
+/// Checks that ROIDS is a TokenFactory denom created by the Maker, so the Maker is allowed to
+/// burn it with a TokenFactory MsgBurn.
+pub fn validate_tokenfactory_burn(env: &Env, roids_token: &AssetInfo) -> Result<(), ContractError> {
+    match roids_token {
+        AssetInfo::NativeToken { denom }
+            if denom.starts_with(&format!("factory/{}/", env.contract.address)) =>
+        {
+            Ok(())
+        }
+        _ => Err(ContractError::InvalidTokenFactoryBurn {}),
+    }
+}
+
+/// Checks whether an amount of a fee token is below the min collect amount.
+pub fn is_dust(cfg: &Config, amount: Uint128) -> bool {
+    cfg.min_collect_amount.map_or(false, |min| amount < min)
 }
 
 /// This function creates swap message.
 ///
 /// * **max_spread** max allowed spread.
 ///
-/// * **pool** pool's information.
+/// * **pool** address of the pair or the external DEX adapter to swap in.
 ///
 /// * **from**  asset we want to swap.
 ///
 /// * **to** asset we want to swap to.
 ///
 /// * **amount_in** amount of tokens to swap.
+///
+/// * **belief_price** expected price the spread is measured against.
+///
+/// * **receiver** recipient of the swapped tokens. The Maker itself if not set.
 pub fn build_swap_msg(
     max_spread: Decimal,
-    pool: &PairInfo,
+    pool: &Addr,
     from: &AssetInfo,
     to: Option<&AssetInfo>,
     amount_in: Uint128,
+    belief_price: Option<Decimal>,
+    receiver: Option<String>,
 ) -> Result<SubMsg, ContractError> {
     if from.is_native_token() {
         let offer_asset = Asset {
@@ -65,13 +357,13 @@ pub fn build_swap_msg(
         };
 
         Ok(SubMsg::new(WasmMsg::Execute {
-            contract_addr: pool.contract_addr.to_string(),
+            contract_addr: pool.to_string(),
             msg: to_json_binary(&astroport::pair::ExecuteMsg::Swap {
                 offer_asset: offer_asset.clone(),
                 ask_asset_info: to.cloned(),
-                belief_price: None,
+                belief_price,
                 max_spread: Some(max_spread),
-                to: None,
+                to: receiver,
             })?,
             funds: vec![offer_asset.as_coin()?],
         }))
@@ -79,13 +371,13 @@ pub fn build_swap_msg(
         Ok(SubMsg::new(WasmMsg::Execute {
             contract_addr: from.to_string(),
             msg: to_json_binary(&cw20::Cw20ExecuteMsg::Send {
-                contract: pool.contract_addr.to_string(),
+                contract: pool.to_string(),
                 amount: amount_in,
                 msg: to_json_binary(&Cw20HookMsg::Swap {
                     ask_asset_info: to.cloned(),
-                    belief_price: None,
+                    belief_price,
                     max_spread: Some(max_spread),
-                    to: None,
+                    to: receiver,
                 })?,
             })?,
             funds: vec![],
@@ -147,6 +439,38 @@ pub fn validate_bridge(
     astro_token: &AssetInfo,
     depth: u64,
 ) -> Result<PairInfo, ContractError> {
+    validate_bridge_memo(
+        deps,
+        factory_contract,
+        from_token,
+        bridge_token,
+        astro_token,
+        depth,
+        &mut BridgeMemo::new(),
+    )
+}
+
+/// Same as [`validate_bridge`] but skips the bridges already validated within the execution, so
+/// validating many bridges sharing the same chain queries each pool once. A bridge validated at
+/// some depth is also valid at lower depths as more hops are left.
+///
+/// * **memo** the bridges validated so far.
+pub fn validate_bridge_memo(
+    deps: Deps,
+    factory_contract: &Addr,
+    from_token: &AssetInfo,
+    bridge_token: &AssetInfo,
+    astro_token: &AssetInfo,
+    depth: u64,
+    memo: &mut BridgeMemo,
+) -> Result<PairInfo, ContractError> {
+    let key = (from_token.to_string(), bridge_token.to_string());
+    if let Some((validated_depth, pool)) = memo.get(&key) {
+        if *validated_depth >= depth {
+            return Ok(pool.clone());
+        }
+    }
+
     // Check if the bridge pool exists
     let bridge_pool = get_pool(&deps.querier, factory_contract, from_token, bridge_token)?;
 
@@ -159,28 +483,375 @@ pub fn validate_bridge(
                 return Err(ContractError::MaxBridgeDepth(depth));
             }
 
-            // Check if next level of bridge exists
-            let next_bridge_token = BRIDGES
+            // Check if next level of bridge exists. Full routes were checked hop by hop when set
+            let route = ROUTES
                 .load(deps.storage, bridge_token.to_string())
                 .map_err(|_| ContractError::InvalidBridgeDestination(from_token.to_string()))?;
 
-            validate_bridge(
+            if let [next_bridge_token] = &route[..] {
+                validate_bridge_memo(
+                    deps,
+                    factory_contract,
+                    bridge_token,
+                    next_bridge_token,
+                    astro_token,
+                    depth + 1,
+                    memo,
+                )?;
+            }
+        }
+    }
+
+    memo.insert(key, (depth, bridge_pool.clone()));
+
+    Ok(bridge_pool)
+}
+
+/// Follows the stored bridges of a fee token and makes sure they don't loop back to an asset
+/// already visited and that the swaps they take fit the execution depth.
+///
+/// * **from_token** fee token whose bridges are checked.
+pub fn check_bridge_chain(
+    deps: Deps,
+    cfg: &Config,
+    from_token: &AssetInfo,
+) -> Result<(), ContractError> {
+    let mut chain = vec![from_token.clone()];
+    let mut current = from_token.clone();
+
+    while current != cfg.roids_token {
+        let route = match ROUTES.may_load(deps.storage, current.to_string())? {
+            Some(route) => route,
+            None => break,
+        };
+
+        for next in route {
+            let looped = chain.contains(&next);
+            chain.push(next.clone());
+            if looped {
+                return Err(ContractError::BridgeCycle(
+                    chain
+                        .iter()
+                        .map(|asset| asset.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" -> "),
+                ));
+            }
+            current = next;
+        }
+    }
+
+    // The last bridge still needs a swap to ROIDS. The first swap is made by collect itself,
+    // the following ones by SwapBridgeAssets
+    let mut swaps = chain.len() as u64 - 1;
+    if current != cfg.roids_token {
+        swaps += 1;
+    }
+    if swaps > execution_max_depth(cfg) + 1 {
+        return Err(ContractError::MaxBridgeDepth(swaps));
+    }
+
+    Ok(())
+}
+
+/// Returns the fee token a [`ROUTES`] key belongs to. Routes are keyed by the asset string, CW20
+/// tokens being keyed by their address.
+pub fn route_key_to_asset(api: &dyn Api, key: String) -> AssetInfo {
+    match api.addr_validate(&key) {
+        Ok(contract_addr) => AssetInfo::Token { contract_addr },
+        Err(_) => AssetInfo::NativeToken { denom: key },
+    }
+}
+
+/// Returns every stored route, bridges included, along with its fee token.
+pub fn load_routes(deps: Deps) -> StdResult<Vec<(AssetInfo, Vec<AssetInfo>)>> {
+    ROUTES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (asset, route) = item?;
+            Ok((route_key_to_asset(deps.api, asset), route))
+        })
+        .collect()
+}
+
+/// Returns the stored bridges, i.e. the routes made of a single asset, along with their fee
+/// tokens.
+///
+/// * **start_after** fee token to start reading after.
+///
+/// * **limit** max number of bridges returned.
+pub fn load_bridges(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: usize,
+) -> StdResult<Vec<(AssetInfo, AssetInfo)>> {
+    ROUTES
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .filter_map(|item| match item {
+            Ok((asset, route)) => match &route[..] {
+                [bridge] => Some(Ok((route_key_to_asset(deps.api, asset), bridge.clone()))),
+                _ => None,
+            },
+            Err(err) => Some(Err(err)),
+        })
+        .take(limit)
+        .collect()
+}
+
+/// Returns the next asset in the route of a fee token to ROIDS along with the pool to swap through.
+/// The route is resolved in the following order: a stored route or bridge, the default bridge,
+/// a direct pair with ROIDS.
+///
+/// * **from_token** token to swap.
+pub fn get_next_hop(
+    deps: Deps,
+    cfg: &Config,
+    from_token: &AssetInfo,
+) -> Result<(AssetInfo, PairInfo), ContractError> {
+    // 1. Check if a route or a bridge is stored
+    match ROUTES
+        .may_load(deps.storage, from_token.to_string())?
+        .as_deref()
+    {
+        Some([bridge_token]) => {
+            let bridge_pool = validate_bridge(
                 deps,
-                factory_contract,
+                &cfg.factory_contract,
+                from_token,
                 bridge_token,
-                &next_bridge_token,
-                astro_token,
-                depth + 1,
+                &cfg.roids_token,
+                BRIDGES_INITIAL_DEPTH,
             )?;
+
+            return Ok((bridge_token.clone(), bridge_pool));
         }
+        Some([next, ..]) => {
+            let pool = get_pool(&deps.querier, &cfg.factory_contract, from_token, next)?;
+            return Ok((next.clone(), pool));
+        }
+        _ => {}
     }
 
-    Ok(bridge_pool)
+    // 2. Check for a pair with a default bridge
+    if let Some(default_bridge) = &cfg.default_bridge {
+        if from_token.ne(default_bridge) {
+            if let Ok(pool) = get_pool(
+                &deps.querier,
+                &cfg.factory_contract,
+                from_token,
+                default_bridge,
+            ) {
+                return Ok((default_bridge.clone(), pool));
+            }
+        }
+    }
+
+    // 3. Check for a direct pair with ROIDS
+    if let Ok(pool) = get_pool(
+        &deps.querier,
+        &cfg.factory_contract,
+        from_token,
+        &cfg.roids_token,
+    ) {
+        return Ok((cfg.roids_token.clone(), pool));
+    }
+
+    Err(ContractError::CannotSwap(from_token.clone()))
+}
+
+/// Custom pair type name of the concentrated liquidity pairs
+pub const CONCENTRATED_PAIR_TYPE: &str = "concentrated";
+/// The highest fee a concentrated liquidity pair may charge
+pub const CONCENTRATED_MAX_FEE: Decimal = Decimal::percent(1);
+
+/// Resolves the assets a fee token is swapped through on its way to ROIDS. The fee token itself
+/// isn't included and the last asset is ROIDS.
+///
+/// * **from_token** token to resolve the route for.
+pub fn resolve_route(
+    deps: Deps,
+    cfg: &Config,
+    from_token: &AssetInfo,
+) -> Result<Vec<AssetInfo>, ContractError> {
+    let mut route = vec![];
+    let mut current = from_token.clone();
+    while current != cfg.roids_token {
+        // The first hop is made by collect itself, the following ones by SwapBridgeAssets
+        if route.len() as u64 > execution_max_depth(cfg) {
+            return Err(ContractError::MaxBridgeDepth(route.len() as u64));
+        }
+
+        // A full route is taken as a whole
+        match EXTERNAL_DEXES.may_load(deps.storage, current.to_string())? {
+            Some(dex) => route.push(dex.ask_asset),
+            None => match ROUTES.may_load(deps.storage, current.to_string())? {
+                Some(stored) if stored.len() > 1 => route.extend(stored),
+                _ => route.push(get_next_hop(deps, cfg, &current)?.0),
+            },
+        }
+        current = route[route.len() - 1].clone();
+    }
+
+    Ok(route)
+}
+
+/// Simulates swapping a fee token to ROIDS. Bridge outputs are followed recursively
+/// hop by hop exactly like the `SwapBridgeAssets` recursion does, so the result accounts
+/// for the whole route.
+///
+/// * **from_token** token to swap.
+///
+/// * **amount** amount of tokens to swap.
+///
+/// * **depth** number of hops already simulated.
+///
+/// Fails when a hop's spread exceeds the max spread for its pair type like the swap itself would.
+pub fn simulate_swap_to_roids(
+    deps: Deps,
+    cfg: &Config,
+    from_token: &AssetInfo,
+    amount: Uint128,
+    depth: u64,
+) -> Result<Uint128, ContractError> {
+    if from_token == &cfg.roids_token || amount.is_zero() {
+        return Ok(amount);
+    }
+
+    // The first hop is made by collect itself, the following ones by SwapBridgeAssets
+    if depth > execution_max_depth(cfg) {
+        return Err(ContractError::MaxBridgeDepth(depth));
+    }
+
+    // A full route is swapped at once through the router, so it is simulated along its own hops
+    if !EXTERNAL_DEXES.has(deps.storage, from_token.to_string()) {
+        if let Some(route) = ROUTES
+            .may_load(deps.storage, from_token.to_string())?
+            .filter(|route| route.len() > 1)
+        {
+            let mut offer = Asset {
+                info: from_token.clone(),
+                amount,
+            };
+            for hop in route {
+                let pool = get_pool(&deps.querier, &cfg.factory_contract, &offer.info, &hop)?;
+                let max_spread = get_asset_max_spread(deps.storage, cfg, &offer.info, &pool)?;
+                offer = Asset {
+                    amount: simulate_hop(deps, &pool.contract_addr, &offer, max_spread)?,
+                    info: hop,
+                };
+            }
+
+            return Ok(offer.amount);
+        }
+    }
+
+    let (to_token, pool, max_spread) =
+        match EXTERNAL_DEXES.may_load(deps.storage, from_token.to_string())? {
+            Some(dex) => (
+                dex.ask_asset,
+                Addr::unchecked(dex.adapter),
+                SPREADS
+                    .may_load(deps.storage, from_token.to_string())?
+                    .unwrap_or(cfg.max_spread),
+            ),
+            None => {
+                let (to_token, pool) = get_next_hop(deps, cfg, from_token)?;
+                let max_spread = get_asset_max_spread(deps.storage, cfg, from_token, &pool)?;
+                (to_token, pool.contract_addr, max_spread)
+            }
+        };
+    let return_amount = simulate_hop(
+        deps,
+        &pool,
+        &Asset {
+            info: from_token.clone(),
+            amount,
+        },
+        max_spread,
+    )?;
+
+    simulate_swap_to_roids(deps, cfg, &to_token, return_amount, depth + 1)
+}
+
+/// Simulates a single swap in a pool. Fails when the spread exceeds the max spread.
+fn simulate_hop(
+    deps: Deps,
+    pool: &Addr,
+    offer: &Asset,
+    max_spread: Decimal,
+) -> Result<Uint128, ContractError> {
+    let simulation = simulate(&deps.querier, pool, offer)?;
+
+    let spread = Decimal::checked_from_ratio(
+        simulation.spread_amount,
+        simulation.return_amount + simulation.spread_amount,
+    )
+    .unwrap_or_default();
+    if spread > max_spread {
+        return Err(ContractError::MaxSpreadExceeded(offer.info.clone()));
+    }
+
+    Ok(simulation.return_amount)
+}
+
+/// Translates a max spread to a pair of the specified type. Max spreads are set for xyk pairs,
+/// which check the spread against their return before commission. Concentrated pairs check it
+/// against their return net of fees, so the highest fee they may charge is added to the spread.
+pub fn translate_max_spread(max_spread: Decimal, pair_type: &PairType) -> StdResult<Decimal> {
+    match pair_type {
+        PairType::Custom(pair_type) if pair_type == CONCENTRATED_PAIR_TYPE => {
+            // The net return may be lower by both the spread and the fee
+            let translated = max_spread + CONCENTRATED_MAX_FEE - max_spread * CONCENTRATED_MAX_FEE;
+            Ok(translated.min(Decimal::from_str(MAX_ALLOWED_SLIPPAGE)?))
+        }
+        _ => Ok(max_spread),
+    }
+}
+
+/// Returns the max spread for swaps in a pair of the specified type.
+pub fn get_max_spread(cfg: &Config, pair_type: &PairType) -> StdResult<Decimal> {
+    translate_max_spread(cfg.max_spread, pair_type)
+}
+
+/// Returns the max spread for swapping an asset in a pool. A max spread set for the asset takes
+/// precedence over the global one. The spread is translated from the pair type recorded when the
+/// bridge or route through the pool was validated.
+pub fn get_asset_max_spread(
+    storage: &dyn Storage,
+    cfg: &Config,
+    asset: &AssetInfo,
+    pool: &PairInfo,
+) -> StdResult<Decimal> {
+    let max_spread = SPREADS
+        .may_load(storage, asset.to_string())?
+        .unwrap_or(cfg.max_spread);
+    let pair_type = PAIR_TYPES
+        .may_load(storage, pool.contract_addr.to_string())?
+        .unwrap_or_else(|| pool.pair_type.clone());
+
+    translate_max_spread(max_spread, &pair_type)
+}
+
+/// Records the pair types of the pools of a validated bridge or route.
+pub fn record_pair_types(storage: &mut dyn Storage, pools: &[PairInfo]) -> StdResult<()> {
+    for pool in pools {
+        PAIR_TYPES.save(storage, pool.contract_addr.to_string(), &pool.pair_type)?;
+    }
+
+    Ok(())
 }
 
 /// This function checks that there is a pool to swap between `from` and `to`. In case of success
 /// returns [`PairInfo`] of selected pool.
 ///
+/// The factory registers a single pair per set of assets whatever its pair type, so there is
+/// no other pool to compare against.
+///
 /// * **factory_contract** address of the factory contract.
 ///
 /// * **from** source asset.
@@ -200,6 +871,22 @@ pub fn get_pool(
     .map_err(|_| ContractError::InvalidBridgeNoPool(from.to_string(), to.to_string()))
 }
 
+/// Checks that every asset of a pool has liquidity.
+///
+/// * **pool** the pool to check.
+pub fn check_pool_liquidity(
+    querier: &QuerierWrapper,
+    pool: &PairInfo,
+) -> Result<(), ContractError> {
+    let response: PoolResponse =
+        querier.query_wasm_smart(&pool.contract_addr, &PairQueryMsg::Pool {})?;
+    if response.assets.iter().any(|asset| asset.amount.is_zero()) {
+        return Err(ContractError::NoLiquidity(pool.contract_addr.to_string()));
+    }
+
+    Ok(())
+}
+
 /// For native tokens of type [`AssetInfo`] uses method [`astro_satellite_package::ExecuteMsg::TransferAstro`]
 /// to send a token amount to a recipient.
 ///
@@ -244,3 +931,713 @@ pub fn validate_cooldown(maybe_cooldown: Option<u64>) -> Result<(), ContractErro
 
     Ok(())
 }
+
+/// Validate min receive buffer is not above the allowed maximum
+pub fn validate_min_receive_buffer(min_receive_buffer_bps: u16) -> Result<(), ContractError> {
+    if min_receive_buffer_bps > MAX_MIN_RECEIVE_BUFFER_BPS {
+        Err(ContractError::IncorrectMinReceiveBuffer {
+            max: MAX_MIN_RECEIVE_BUFFER_BPS,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Builds a swap of a fee token along its whole route through the router.
+///
+/// * **router** address of the router contract.
+///
+/// * **from** fee token to swap.
+///
+/// * **route** assets the fee token is swapped through, ending with ROIDS.
+///
+/// * **minimum_receive** minimum amount of ROIDS the swap must return.
+pub fn build_router_swap_msg(
+    router: &Addr,
+    from: &AssetInfo,
+    route: &[AssetInfo],
+    amount_in: Uint128,
+    max_spread: Decimal,
+    minimum_receive: Option<Uint128>,
+) -> Result<SubMsg, ContractError> {
+    let operations = [from]
+        .into_iter()
+        .chain(route)
+        .zip(route)
+        .map(|(offer, ask)| SwapOperation::AstroSwap {
+            offer_asset_info: offer.clone(),
+            ask_asset_info: ask.clone(),
+        })
+        .collect();
+
+    if from.is_native_token() {
+        let offer_asset = Asset {
+            info: from.clone(),
+            amount: amount_in,
+        };
+
+        Ok(SubMsg::new(WasmMsg::Execute {
+            contract_addr: router.to_string(),
+            msg: to_json_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                operations,
+                minimum_receive,
+                to: None,
+                max_spread: Some(max_spread),
+            })?,
+            funds: vec![offer_asset.as_coin()?],
+        }))
+    } else {
+        Ok(SubMsg::new(WasmMsg::Execute {
+            contract_addr: from.to_string(),
+            msg: to_json_binary(&cw20::Cw20ExecuteMsg::Send {
+                contract: router.to_string(),
+                amount: amount_in,
+                msg: to_json_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                    operations,
+                    minimum_receive,
+                    to: None,
+                    max_spread: Some(max_spread),
+                })?,
+            })?,
+            funds: vec![],
+        }))
+    }
+}
+
+/// This enum describes the outcome of a swap built by a [`SwapExecutor`].
+pub enum SwapTarget {
+    Roids(SubMsg),
+    Bridge { asset: AssetInfo, msg: SubMsg },
+}
+
+impl SwapTarget {
+    fn new(cfg: &Config, to_token: AssetInfo, msg: SubMsg) -> Self {
+        if to_token == cfg.roids_token {
+            SwapTarget::Roids(msg)
+        } else {
+            SwapTarget::Bridge {
+                asset: to_token,
+                msg,
+            }
+        }
+    }
+}
+
+/// Builds the swaps of fee tokens on their way to ROIDS.
+pub trait SwapExecutor {
+    /// Builds the swap of a fee token. Fails with [`ContractError::OracleDeviation`] when the
+    /// price the fee token is swapped at deviates from its oracle.
+    ///
+    /// * **from_token** fee token to swap.
+    ///
+    /// * **amount_in** amount of tokens to swap.
+    fn build_swap(
+        &self,
+        deps: Deps,
+        cfg: &Config,
+        from_token: &AssetInfo,
+        amount_in: Uint128,
+    ) -> Result<SwapTarget, ContractError>;
+}
+
+/// Swaps a fee token to its next hop in a factory pair.
+pub struct PairExecutor;
+
+impl SwapExecutor for PairExecutor {
+    fn build_swap(
+        &self,
+        deps: Deps,
+        cfg: &Config,
+        from_token: &AssetInfo,
+        amount_in: Uint128,
+    ) -> Result<SwapTarget, ContractError> {
+        let (to_token, pool) = get_next_hop(deps, cfg, from_token)?;
+        let max_spread = get_asset_max_spread(deps.storage, cfg, from_token, &pool)?;
+        let msg = build_priced_swap_msg(
+            deps,
+            cfg,
+            &pool.contract_addr,
+            from_token,
+            &to_token,
+            amount_in,
+            max_spread,
+        )?;
+
+        Ok(SwapTarget::new(cfg, to_token, msg))
+    }
+}
+
+/// Swaps a fee token along its route through the router. The route ends at ROIDS or at the
+/// first asset swapped on an external DEX which is then swapped as a bridge asset.
+pub struct RouterExecutor {
+    pub router: Addr,
+}
+
+impl SwapExecutor for RouterExecutor {
+    fn build_swap(
+        &self,
+        deps: Deps,
+        cfg: &Config,
+        from_token: &AssetInfo,
+        amount_in: Uint128,
+    ) -> Result<SwapTarget, ContractError> {
+        let mut route = resolve_route(deps, cfg, from_token)?;
+        if let Some(pos) = route
+            .iter()
+            .position(|asset| EXTERNAL_DEXES.has(deps.storage, asset.to_string()))
+        {
+            route.truncate(pos + 1);
+        }
+        let to_token = match route.last() {
+            Some(to_token) => to_token.clone(),
+            None => return Err(ContractError::CannotSwap(from_token.clone())),
+        };
+
+        // The router applies one max spread to every hop, so the tightest one of the hops is
+        // used. Each hop is checked against the oracle of the asset it offers
+        let mut max_spread = Decimal::one();
+        let mut offer = Asset {
+            info: from_token.clone(),
+            amount: amount_in,
+        };
+        for (index, ask_asset) in route.iter().enumerate() {
+            let pool = get_pool(&deps.querier, &cfg.factory_contract, &offer.info, ask_asset)?;
+            check_oracle_price(
+                deps,
+                &pool.contract_addr,
+                &offer.info,
+                ask_asset,
+                offer.amount,
+            )?;
+            max_spread =
+                max_spread.min(get_asset_max_spread(deps.storage, cfg, &offer.info, &pool)?);
+
+            if index + 1 < route.len() {
+                offer = Asset {
+                    info: ask_asset.clone(),
+                    amount: simulate(&deps.querier, &pool.contract_addr, &offer)?.return_amount,
+                };
+            }
+        }
+
+        // The simulated output is in ROIDS so it only bounds routes reaching ROIDS
+        let minimum_receive = match cfg.min_receive_buffer_bps {
+            Some(buffer_bps) if to_token == cfg.roids_token => {
+                let simulated = simulate_swap_to_roids(deps, cfg, from_token, amount_in, 0)?;
+                Some(simulated.multiply_ratio(10000u16 - buffer_bps, 10000u16))
+            }
+            _ => None,
+        };
+
+        let msg = build_router_swap_msg(
+            &self.router,
+            from_token,
+            &route,
+            amount_in,
+            max_spread,
+            minimum_receive,
+        )?;
+
+        Ok(SwapTarget::new(cfg, to_token, msg))
+    }
+}
+
+/// Swaps a fee token on the external DEX set for it.
+pub struct ExternalDexExecutor {
+    pub dex: ExternalDex,
+}
+
+impl SwapExecutor for ExternalDexExecutor {
+    fn build_swap(
+        &self,
+        deps: Deps,
+        cfg: &Config,
+        from_token: &AssetInfo,
+        amount_in: Uint128,
+    ) -> Result<SwapTarget, ContractError> {
+        // The adapter is validated when the external DEX is set
+        let adapter = Addr::unchecked(&self.dex.adapter);
+        let max_spread = SPREADS
+            .may_load(deps.storage, from_token.to_string())?
+            .unwrap_or(cfg.max_spread);
+        let msg = build_priced_swap_msg(
+            deps,
+            cfg,
+            &adapter,
+            from_token,
+            &self.dex.ask_asset,
+            amount_in,
+            max_spread,
+        )?;
+
+        Ok(SwapTarget::new(cfg, self.dex.ask_asset.clone(), msg))
+    }
+}
+
+/// Returns the executor a fee token is swapped with. An external DEX set for the fee token takes
+/// precedence over the router, which takes precedence over the factory pairs.
+///
+/// * **from_token** fee token to swap.
+pub fn get_swap_executor(
+    deps: Deps,
+    cfg: &Config,
+    from_token: &AssetInfo,
+) -> StdResult<Box<dyn SwapExecutor>> {
+    if let Some(dex) = EXTERNAL_DEXES.may_load(deps.storage, from_token.to_string())? {
+        return Ok(Box::new(ExternalDexExecutor { dex }));
+    }
+
+    match &cfg.router_contract {
+        Some(router) => Ok(Box::new(RouterExecutor {
+            router: router.clone(),
+        })),
+        None => Ok(Box::new(PairExecutor)),
+    }
+}
+
+/// Builds a single hop swap after checking the pool price against the oracle of the fee token.
+/// The swap is bounded by the max spread around the configured belief price. The min receive
+/// buffer doesn't apply here: a belief price simulated in the same transaction would make the
+/// pair's spread check pass for any pool state.
+///
+/// * **pool** address of the pair or the external DEX adapter to swap in.
+fn build_priced_swap_msg(
+    deps: Deps,
+    cfg: &Config,
+    pool: &Addr,
+    from_token: &AssetInfo,
+    to_token: &AssetInfo,
+    amount_in: Uint128,
+    max_spread: Decimal,
+) -> Result<SubMsg, ContractError> {
+    check_oracle_price(deps, pool, from_token, to_token, amount_in)?;
+    let belief_price = get_belief_price(deps, cfg, from_token, to_token, amount_in)?;
+
+    build_swap_msg(
+        max_spread,
+        pool,
+        from_token,
+        Some(to_token),
+        amount_in,
+        belief_price,
+        None,
+    )
+}
+
+/// Returns the amount of the ask asset an amount of an asset is worth according to the TWAP
+/// oracle of the asset.
+fn query_twap(
+    querier: &QuerierWrapper,
+    oracle: &PriceOracle,
+    asset: &AssetInfo,
+    ask_asset: &AssetInfo,
+    amount: Uint128,
+) -> Result<Uint128, ContractError> {
+    let twap: Vec<(AssetInfo, Uint256)> = querier.query_wasm_smart(
+        &oracle.contract,
+        &OracleQueryMsg::Consult {
+            token: asset.clone(),
+            amount,
+        },
+    )?;
+    let (_, twap_amount) = twap
+        .into_iter()
+        .find(|(info, _)| info == ask_asset)
+        .ok_or_else(|| ContractError::InvalidPriceOracle(asset.clone()))?;
+    let twap_amount: Uint128 = twap_amount.try_into().map_err(StdError::from)?;
+    if twap_amount.is_zero() {
+        return Err(ContractError::InvalidPriceOracle(asset.clone()));
+    }
+
+    Ok(twap_amount)
+}
+
+/// Returns the belief price of a single hop swap taken from the configured source, i.e. the
+/// amount of the offered asset paid per unit of the asked one. None if the swap has no belief
+/// price. The belief price must come from outside the pool, otherwise the pair's spread check
+/// would pass whatever the pool state.
+pub fn get_belief_price(
+    deps: Deps,
+    cfg: &Config,
+    from_token: &AssetInfo,
+    to_token: &AssetInfo,
+    amount_in: Uint128,
+) -> Result<Option<Decimal>, ContractError> {
+    let expected_return = match cfg.belief_price_source {
+        BeliefPriceSource::Disabled => return Ok(None),
+        BeliefPriceSource::Oracle => {
+            match PRICE_ORACLES.may_load(deps.storage, from_token.to_string())? {
+                Some(oracle) => {
+                    query_twap(&deps.querier, &oracle, from_token, to_token, amount_in)?
+                }
+                None => return Ok(None),
+            }
+        }
+    };
+
+    Decimal::checked_from_ratio(amount_in, expected_return)
+        .map(Some)
+        .map_err(|_| ContractError::CannotSwap(from_token.clone()))
+}
+
+/// Returns the minimum ROIDS a collected amount of a fee token must realize.
+///
+/// * **asset** fee token being collected.
+///
+/// * **amount** amount of the fee token swept by the collect.
+///
+/// * **min_received** minimum set for the fee token.
+pub fn get_min_received(
+    querier: &QuerierWrapper,
+    cfg: &Config,
+    asset: &AssetInfo,
+    amount: Uint128,
+    min_received: &MinReceived,
+) -> Result<Uint128, ContractError> {
+    match min_received {
+        MinReceived::Price(price) => Ok(amount * *price),
+        MinReceived::Oracle {
+            contract,
+            tolerance_bps,
+        } => {
+            let twap: Vec<(AssetInfo, Uint256)> = querier.query_wasm_smart(
+                contract,
+                &OracleQueryMsg::Consult {
+                    token: asset.clone(),
+                    amount,
+                },
+            )?;
+            let (_, roids_amount) = twap
+                .into_iter()
+                .find(|(info, _)| info == &cfg.roids_token)
+                .ok_or_else(|| ContractError::InvalidMinReceived(asset.clone()))?;
+            let roids_amount: Uint128 = roids_amount.try_into().map_err(StdError::from)?;
+
+            Ok(roids_amount.multiply_ratio(10000u16 - tolerance_bps, 10000u16))
+        }
+    }
+}
+
+/// Checks that the pool price of an asset doesn't deviate from its TWAP oracle by more than the
+/// max deviation. Assets without an oracle aren't checked.
+///
+/// * **pool** pair the asset is swapped in.
+///
+/// * **ask_asset** asset the asset is swapped to.
+///
+/// * **amount** amount of the asset to swap.
+pub fn check_oracle_price(
+    deps: Deps,
+    pool: &Addr,
+    asset: &AssetInfo,
+    ask_asset: &AssetInfo,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let oracle = match PRICE_ORACLES.may_load(deps.storage, asset.to_string())? {
+        Some(oracle) => oracle,
+        None => return Ok(()),
+    };
+    let twap_amount = query_twap(&deps.querier, &oracle, asset, ask_asset, amount)?;
+
+    // The spread and the commission are added back so the price impact of the swap itself
+    // isn't mistaken for a deviation
+    let simulation = simulate(
+        &deps.querier,
+        pool,
+        &Asset {
+            info: asset.clone(),
+            amount,
+        },
+    )?;
+    let spot_amount =
+        simulation.return_amount + simulation.spread_amount + simulation.commission_amount;
+
+    let deviation_bps = spot_amount
+        .abs_diff(twap_amount)
+        .multiply_ratio(10000u16, twap_amount)
+        .min(Uint128::from(u16::MAX))
+        .u128() as u16;
+    if deviation_bps > oracle.max_deviation_bps {
+        return Err(ContractError::OracleDeviation {
+            asset: asset.clone(),
+            deviation_bps,
+            max_deviation_bps: oracle.max_deviation_bps,
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns the price of an auction at the specified time. The price declines linearly from the
+/// start price to the end price and stays there once the auction reached its end.
+pub fn auction_price(auction: &Auction, now: u64) -> Decimal {
+    if now >= auction.end_ts {
+        return auction.end_price;
+    }
+
+    let elapsed = now.saturating_sub(auction.start_ts);
+    let decline = (auction.start_price - auction.end_price)
+        * Decimal::from_ratio(elapsed, auction.end_ts - auction.start_ts);
+
+    auction.start_price - decline
+}
+
+/// Validates the prices of an auction. The price must decline to a non zero end price over a
+/// non zero duration.
+pub fn validate_auction_prices(
+    start_price: Decimal,
+    end_price: Decimal,
+    duration_secs: u64,
+) -> Result<(), ContractError> {
+    if end_price.is_zero() || start_price < end_price || duration_secs == 0 {
+        return Err(ContractError::InvalidAuction {});
+    }
+
+    Ok(())
+}
+
+/// Validates the keeper fee.
+///
+/// * **keeper_fee_bps** share of the realized ROIDS paid to the caller of a collect.
+pub fn validate_keeper_fee(keeper_fee_bps: u16) -> Result<(), ContractError> {
+    if keeper_fee_bps > MAX_KEEPER_FEE_BPS {
+        Err(ContractError::IncorrectKeeperFee {
+            max: MAX_KEEPER_FEE_BPS,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the swap execution depth limit, falling back to the default one if not configured.
+pub fn execution_max_depth(cfg: &Config) -> u64 {
+    cfg.bridges_execution_max_depth
+        .unwrap_or(BRIDGES_EXECUTION_MAX_DEPTH)
+}
+
+/// Validate bridges execution depth is within the allowed limits
+pub fn validate_bridges_execution_depth(depth: u64) -> Result<(), ContractError> {
+    if !BRIDGES_EXECUTION_DEPTH_LIMITS.contains(&depth) {
+        return Err(ContractError::IncorrectBridgesExecutionDepth {
+            min: *BRIDGES_EXECUTION_DEPTH_LIMITS.start(),
+            max: *BRIDGES_EXECUTION_DEPTH_LIMITS.end(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Validate swap deadline buffer is not zero
+pub fn validate_swap_deadline(maybe_deadline_secs: Option<u64>) -> Result<(), ContractError> {
+    if maybe_deadline_secs == Some(0) {
+        return Err(ContractError::IncorrectSwapDeadline {});
+    }
+
+    Ok(())
+}
+
+/// Checks that the bridge asset is allowed by the approved bridge assets whitelist.
+/// ROIDS is always allowed as it is the final destination of every route.
+pub fn check_bridge_approved(cfg: &Config, bridge: &AssetInfo) -> Result<(), ContractError> {
+    match &cfg.approved_bridge_assets {
+        Some(approved) if bridge != &cfg.roids_token && !approved.contains(bridge) => {
+            Err(ContractError::BridgeAssetNotApproved(bridge.clone()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that the address is a pair registered in the factory and returns its [`PairInfo`].
+///
+/// * **factory_contract** address of the factory contract.
+///
+/// * **pair_addr** address to check.
+pub fn validate_factory_pair(
+    querier: &QuerierWrapper,
+    factory_contract: &Addr,
+    pair_addr: &Addr,
+) -> Result<PairInfo, ContractError> {
+    let pair_info: PairInfo = querier
+        .query_wasm_smart(pair_addr, &astroport::pair::QueryMsg::Pair {})
+        .map_err(|_| ContractError::NotFactoryPair(pair_addr.to_string()))?;
+
+    let factory_pair = query_pair_info(querier, factory_contract, &pair_info.asset_infos)
+        .map_err(|_| ContractError::NotFactoryPair(pair_addr.to_string()))?;
+    if &factory_pair.contract_addr != pair_addr {
+        return Err(ContractError::NotFactoryPair(pair_addr.to_string()));
+    }
+
+    Ok(pair_info)
+}
+
+/// Resolves where distributed ROIDS ends up along with the setting it was resolved from, with
+/// the precedence [`build_distribution_msg`] applies.
+pub fn resolve_burn_destination(cfg: &Config) -> (String, BurnDestinationSource) {
+    let source = burn_destination_source(cfg);
+    let destination = match (&source, &cfg.distribution_mode) {
+        (BurnDestinationSource::TokenfactoryBurn | BurnDestinationSource::Burn, _) => {
+            cfg.roids_token.to_string()
+        }
+        (BurnDestinationSource::IbcReceiver, DistributionMode::IbcTransfer { receiver, .. }) => {
+            receiver.clone()
+        }
+        _ => cfg.asteroid_contract.to_string(),
+    };
+
+    (destination, source)
+}
+
+/// Returns the address holding a role. Roles which were never handed over are held by the owner.
+pub fn role_holder(deps: Deps, cfg: &Config, role: &Role) -> StdResult<Addr> {
+    Ok(ROLES
+        .may_load(deps.storage, role.as_str())?
+        .unwrap_or_else(|| cfg.owner.clone()))
+}
+
+/// Checks that the sender holds a role.
+pub fn assert_role(
+    deps: Deps,
+    cfg: &Config,
+    sender: &Addr,
+    role: &Role,
+) -> Result<(), ContractError> {
+    if *sender != role_holder(deps, cfg, role)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    Ok(())
+}
+
+/// Tags a response and each of its events with the ID of the latest collect so the swaps,
+/// distributions and replies of a collect can be correlated.
+pub fn tag_collect_id(deps: Deps, response: Response) -> StdResult<Response> {
+    let collect_id = COLLECT_ID
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .to_string();
+
+    let mut response = response.add_attribute("collect_id", &collect_id);
+    response.events = response
+        .events
+        .into_iter()
+        .map(|event| event.add_attribute("collect_id", &collect_id))
+        .collect();
+
+    Ok(response)
+}
+
+/// Appends a successful config or bridge update to the config history.
+///
+/// * **action** the executed action.
+///
+/// * **changes** the updated settings along with their new values.
+pub fn record_config_change(
+    storage: &mut dyn Storage,
+    env: &Env,
+    sender: &Addr,
+    action: &str,
+    changes: Vec<Attribute>,
+) -> StdResult<()> {
+    let id = CONFIG_HISTORY
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map_or(0, |id| id + 1);
+
+    CONFIG_HISTORY.save(
+        storage,
+        id,
+        &ConfigHistoryEntry {
+            id,
+            height: env.block.height,
+            sender: sender.clone(),
+            action: action.to_string(),
+            changes,
+        },
+    )
+}
+
+/// Returns the part of an amount of a fee token arriving at its recipient, net of the transfer
+/// tax observed for the token.
+pub fn net_of_transfer_tax(
+    storage: &dyn Storage,
+    asset: &AssetInfo,
+    amount: Uint128,
+) -> StdResult<Uint128> {
+    Ok(match TRANSFER_TAXES.may_load(storage, asset.to_string())? {
+        Some(tax) => amount - amount * tax,
+        None => amount,
+    })
+}
+
+/// Records the Maker's balance of each CW20 token among the assets so the swap replies can
+/// measure what actually arrived. Native assets aren't taxed on transfer.
+pub fn snapshot_token_balances<'a>(
+    deps: DepsMut,
+    contract_addr: &Addr,
+    assets: impl IntoIterator<Item = &'a AssetInfo>,
+) -> StdResult<()> {
+    for asset in assets {
+        if asset.is_native_token() {
+            continue;
+        }
+        let balance = asset.query_pool(&deps.querier, contract_addr)?;
+        TOKEN_BALANCES.save(deps.storage, asset.to_string(), &balance)?;
+    }
+
+    Ok(())
+}
+
+/// Records the Maker's balance of each bridge asset a dust consolidation swaps to, so the next
+/// hop only swaps what the consolidation added.
+///
+/// * **swapped** amounts of the bridge assets swapped away by the current hop.
+pub fn snapshot_dust_bridges(
+    deps: DepsMut,
+    contract_addr: &Addr,
+    bridge_assets: &[AssetInfo],
+    swapped: &HashMap<String, Uint128>,
+) -> StdResult<()> {
+    let balances = bridge_assets
+        .iter()
+        .map(|info| {
+            let balance = info.query_pool(&deps.querier, contract_addr)?;
+            let swapped = swapped.get(&info.to_string()).copied().unwrap_or_default();
+            Ok(Asset {
+                info: info.clone(),
+                amount: balance.saturating_sub(swapped),
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    DUST_BRIDGE_BALANCES.save(deps.storage, &balances)
+}
+
+/// Returns the amounts of the bridge assets a dust consolidation added since their balances were
+/// recorded. Bridge assets without a new amount are left out.
+pub fn dust_bridge_amounts(
+    deps: Deps,
+    contract_addr: &Addr,
+    balances: &[Asset],
+    assets: Vec<AssetInfo>,
+) -> StdResult<Vec<AssetWithLimit>> {
+    let mut amounts = vec![];
+    for info in assets {
+        let before = balances
+            .iter()
+            .find(|asset| asset.info == info)
+            .map(|asset| asset.amount)
+            .unwrap_or_default();
+        let added = info
+            .query_pool(&deps.querier, contract_addr)?
+            .saturating_sub(before);
+        if !added.is_zero() {
+            amounts.push(AssetWithLimit {
+                info,
+                limit: Some(added),
+                limit_semantics: Some(LimitSemantics::Cap),
+            });
+        }
+    }
+
+    Ok(amounts)
+}
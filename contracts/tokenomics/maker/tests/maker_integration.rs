@@ -15,8 +15,8 @@ use astroport::asset::{
 };
 use astroport::factory::{PairConfig, PairType, UpdateAddr};
 use astroport::maker::{
-    AssetWithLimit, BalancesResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg,
-    SecondReceiverConfig, SecondReceiverParams, COOLDOWN_LIMITS,
+    AssetWithLimit, BalancesResponse, BridgeInfo, BridgesResponse, ConfigResponse, ExecuteMsg,
+    InstantiateMsg, QueryMsg, SecondReceiverConfig, SecondReceiverParams, COOLDOWN_LIMITS,
 };
 use astroport_maker::error::ContractError;
 use cw20_base::msg::InstantiateMsg as TokenInstantiateMsg;
@@ -886,22 +886,27 @@ fn collect_all() {
         AssetWithLimit {
             info: token_asset(astro_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            limit_semantics: None,
         },
         AssetWithLimit {
             info: native_asset(uluna_asset.clone(), Uint128::zero()).info,
             limit: None,
+            limit_semantics: None,
         },
         AssetWithLimit {
             info: token_asset(usdc_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            limit_semantics: None,
         },
         AssetWithLimit {
             info: token_asset(test_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            limit_semantics: None,
         },
         AssetWithLimit {
             info: token_asset(bridge2_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            limit_semantics: None,
         },
     ];
 
@@ -1172,14 +1177,17 @@ fn collect_err_no_swap_pair() {
         AssetWithLimit {
             info: native_asset(ukrt_asset.clone(), Uint128::zero()).info,
             limit: None,
+            limit_semantics: None,
         },
         AssetWithLimit {
             info: token_asset(astro_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            limit_semantics: None,
         },
         AssetWithLimit {
             info: native_asset(uabc_asset.clone(), Uint128::zero()).info,
             limit: None,
+            limit_semantics: None,
         },
     ];
 
@@ -1336,19 +1344,36 @@ fn update_bridges() {
         .execute_contract(owner.clone(), maker_instance.clone(), &msg, &[])
         .unwrap();
 
-    let resp: Vec<(String, String)> = router
+    let resp: BridgesResponse = router
         .wrap()
         .query(&QueryRequest::Wasm(WasmQuery::Smart {
             contract_addr: maker_instance.to_string(),
-            msg: to_json_binary(&QueryMsg::Bridges {}).unwrap(),
+            msg: to_json_binary(&QueryMsg::Bridges {
+                start_after: None,
+                limit: None,
+            })
+            .unwrap(),
         }))
         .unwrap();
 
+    // Fee tokens are compared by key as the mock API accepts denoms as addresses
+    let by_key = |bridges: Vec<BridgeInfo>| {
+        bridges
+            .into_iter()
+            .map(|bridge| (bridge.asset.to_string(), bridge.route))
+            .collect::<Vec<_>>()
+    };
     assert_eq!(
-        resp,
+        by_key(resp.bridges),
         vec![
-            (String::from("ukrt"), String::from("uusd")),
-            (String::from("uluna"), String::from("uusd")),
+            (
+                String::from("ukrt"),
+                vec![native_asset_info(String::from("uusd"))]
+            ),
+            (
+                String::from("uluna"),
+                vec![native_asset_info(String::from("uusd"))]
+            ),
         ]
     );
 
@@ -1362,15 +1387,25 @@ fn update_bridges() {
         .execute_contract(owner.clone(), maker_instance.clone(), &msg, &[])
         .unwrap();
 
-    let resp: Vec<(String, String)> = router
+    let resp: BridgesResponse = router
         .wrap()
         .query(&QueryRequest::Wasm(WasmQuery::Smart {
             contract_addr: maker_instance.to_string(),
-            msg: to_json_binary(&QueryMsg::Bridges {}).unwrap(),
+            msg: to_json_binary(&QueryMsg::Bridges {
+                start_after: None,
+                limit: None,
+            })
+            .unwrap(),
         }))
         .unwrap();
 
-    assert_eq!(resp, vec![(String::from("uluna"), String::from("uusd"))]);
+    assert_eq!(
+        by_key(resp.bridges),
+        vec![(
+            String::from("uluna"),
+            vec![native_asset_info(String::from("uusd"))]
+        )]
+    );
 }
 
 #[test]
@@ -1467,10 +1502,12 @@ fn collect_with_asset_limit() {
         AssetWithLimit {
             info: token_asset(usdc_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            limit_semantics: None,
         },
         AssetWithLimit {
             info: token_asset(usdc_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            limit_semantics: None,
         },
     ];
 
@@ -1479,18 +1516,22 @@ fn collect_with_asset_limit() {
         AssetWithLimit {
             info: token_asset(astro_token_instance.clone(), Uint128::zero()).info,
             limit: Option::from(Uint128::new(5)),
+            limit_semantics: None,
         },
         AssetWithLimit {
             info: token_asset(usdc_token_instance.clone(), Uint128::zero()).info,
             limit: Option::from(Uint128::new(5)),
+            limit_semantics: None,
         },
         AssetWithLimit {
             info: token_asset(test_token_instance.clone(), Uint128::zero()).info,
             limit: Option::from(Uint128::new(5)),
+            limit_semantics: None,
         },
         AssetWithLimit {
             info: token_asset(bridge2_token_instance.clone(), Uint128::zero()).info,
             limit: Option::from(Uint128::new(5)),
+            limit_semantics: None,
         },
     ];
 
@@ -1783,18 +1824,22 @@ fn collect_with_second_receiver() {
         AssetWithLimit {
             info: token_asset(astro_token_instance.clone(), Uint128::zero()).info,
             limit: Option::from(Uint128::new(5)),
+            limit_semantics: None,
         },
         AssetWithLimit {
             info: token_asset(usdc_token_instance.clone(), Uint128::zero()).info,
             limit: Option::from(Uint128::new(5)),
+            limit_semantics: None,
         },
         AssetWithLimit {
             info: token_asset(test_token_instance.clone(), Uint128::zero()).info,
             limit: Option::from(Uint128::new(5)),
+            limit_semantics: None,
         },
         AssetWithLimit {
             info: token_asset(bridge2_token_instance.clone(), Uint128::zero()).info,
             limit: Option::from(Uint128::new(5)),
+            limit_semantics: None,
         },
     ];
 
@@ -2029,6 +2074,7 @@ fn test_collect_cooldown() {
     let assets = vec![AssetWithLimit {
         info: AssetInfo::native(asset0),
         limit: None,
+        limit_semantics: None,
     }];
 
     // First collect works
@@ -2204,22 +2250,27 @@ fn distribute_initially_accrued_fees() {
         AssetWithLimit {
             info: token_asset(astro_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            limit_semantics: None,
         },
         AssetWithLimit {
             info: native_asset(uluna_asset.clone(), Uint128::zero()).info,
             limit: None,
+            limit_semantics: None,
         },
         AssetWithLimit {
             info: token_asset(usdc_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            limit_semantics: None,
         },
         AssetWithLimit {
             info: token_asset(test_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            limit_semantics: None,
         },
         AssetWithLimit {
             info: token_asset(bridge2_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            limit_semantics: None,
         },
     ];
 
@@ -2356,6 +2407,7 @@ fn distribute_initially_accrued_fees() {
     let astro_asset = AssetWithLimit {
         info: token_asset_info(astro_token_instance.clone()),
         limit: None,
+        limit_semantics: None,
     };
     let assets = vec![astro_asset];
 
@@ -2617,14 +2669,17 @@ fn collect_3pools() {
         AssetWithLimit {
             info: token_asset(astro_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            limit_semantics: None,
         },
         AssetWithLimit {
             info: token_asset(usdc_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            limit_semantics: None,
         },
         AssetWithLimit {
             info: token_asset(test_token.clone(), Uint128::zero()).info,
             limit: None,
+            limit_semantics: None,
         },
     ];
 
@@ -0,0 +1,81 @@
+use cosmwasm_std::{Binary, Coin, CosmosMsg, CustomMsg, Timestamp};
+use prost::Message;
+
+use crate::token_factory::ProtoCoin;
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Height {
+    #[prost(uint64, tag = "1")]
+    pub revision_number: u64,
+    #[prost(uint64, tag = "2")]
+    pub revision_height: u64,
+}
+
+/// ICS20 transfer message. Unlike [`cosmwasm_std::IbcMsg::Transfer`] it carries a memo.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgTransfer {
+    #[prost(string, tag = "1")]
+    pub source_port: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub source_channel: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub token: ::core::option::Option<ProtoCoin>,
+    #[prost(string, tag = "4")]
+    pub sender: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub receiver: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "6")]
+    pub timeout_height: ::core::option::Option<Height>,
+    #[prost(uint64, tag = "7")]
+    pub timeout_timestamp: u64,
+    #[prost(string, tag = "8")]
+    pub memo: ::prost::alloc::string::String,
+}
+
+impl MsgTransfer {
+    pub const TYPE_URL: &'static str = "/ibc.applications.transfer.v1.MsgTransfer";
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgTransferResponse {
+    #[prost(uint64, tag = "1")]
+    pub sequence: u64,
+}
+
+/// Returns the packet sequence of an ICS20 transfer from the data of its submessage response.
+pub fn parse_transfer_sequence(data: &[u8]) -> Option<u64> {
+    MsgTransferResponse::decode(data)
+        .ok()
+        .map(|res| res.sequence)
+}
+
+pub fn ibc_transfer_msg<T>(
+    sender: impl Into<String>,
+    channel: impl Into<String>,
+    receiver: impl Into<String>,
+    coin: Coin,
+    timeout: Timestamp,
+    memo: impl Into<String>,
+) -> CosmosMsg<T>
+where
+    T: CustomMsg,
+{
+    let msg = MsgTransfer {
+        source_port: "transfer".to_string(),
+        source_channel: channel.into(),
+        token: Some(ProtoCoin {
+            denom: coin.denom,
+            amount: coin.amount.to_string(),
+        }),
+        sender: sender.into(),
+        receiver: receiver.into(),
+        timeout_height: None,
+        timeout_timestamp: timeout.nanos(),
+        memo: memo.into(),
+    };
+
+    CosmosMsg::Stargate {
+        type_url: MsgTransfer::TYPE_URL.to_string(),
+        value: Binary::from(msg.encode_to_vec()),
+    }
+}
@@ -8,6 +8,7 @@ pub mod common;
 pub mod cosmwasm_ext;
 pub mod factory;
 pub mod fee_granter;
+pub mod ibc_transfer;
 #[cfg(feature = "injective")]
 pub mod injective_ext;
 pub mod maker;
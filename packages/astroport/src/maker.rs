@@ -1,11 +1,24 @@
 use crate::asset::{Asset, AssetInfo};
-use crate::factory::UpdateAddr;
+use crate::factory::{PairType, UpdateAddr};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Decimal, Uint128, Uint64};
+use cosmwasm_std::{Addr, Attribute, Binary, Decimal, Uint128, Uint64};
+use cw20::Cw20ReceiveMsg;
 use std::ops::RangeInclusive;
 
 /// Validations limits for cooldown period. From 30 to 600 seconds.
 pub const COOLDOWN_LIMITS: RangeInclusive<u64> = 30..=600;
+/// The max buffer subtracted from simulated swap outputs, 1%
+pub const MAX_MIN_RECEIVE_BUFFER_BPS: u16 = 100;
+/// The max share of the realized ROIDS paid to the caller of a collect, 10%
+pub const MAX_KEEPER_FEE_BPS: u16 = 1000;
+/// The default delay between proposing and executing a seize, 3 days
+pub const DEFAULT_SEIZE_DELAY_SECS: u64 = 3 * 86400;
+/// The default delay between proposing and applying a change of the critical settings, 3 days
+pub const DEFAULT_CONFIG_CHANGE_DELAY_SECS: u64 = 3 * 86400;
+/// The max share of each distribution paid to the second receiver, 50%
+pub const MAX_SECOND_RECEIVER_CUT_BPS: u16 = 5000;
+/// Validation limits for the bridges execution depth. From 1 to 10 bridges.
+pub const BRIDGES_EXECUTION_DEPTH_LIMITS: RangeInclusive<u64> = 1..=10;
 
 /// This structure stores the main parameters for the Maker contract.
 #[cw_serde]
@@ -24,6 +37,110 @@ pub struct Config {
     pub max_spread: Decimal,
     /// If set defines the period when maker collect can be called
     pub collect_cooldown: Option<u64>,
+    /// If set, collects must carry a deadline at most this many seconds ahead of their execution
+    #[serde(default)]
+    pub swap_deadline_secs: Option<u64>,
+    /// If set, only these assets can be used as intermediate bridge assets
+    #[serde(default)]
+    pub approved_bridge_assets: Option<Vec<AssetInfo>>,
+    /// If set, the Maker pauses itself after this many consecutive failed collects
+    #[serde(default)]
+    pub auto_pause_threshold: Option<u32>,
+    /// If set, ROIDS is accumulated and burned once every this many collects
+    #[serde(default)]
+    pub burn_every_n_collects: Option<u32>,
+    /// If set, balances up to this amount can be swept with `ConsolidateDust`
+    #[serde(default)]
+    pub dust_threshold: Option<Uint128>,
+    /// If set, a keeper can commit to a collect and gets this many seconds to execute it exclusively
+    #[serde(default)]
+    pub keeper_commit_window_secs: Option<u64>,
+    /// If set, router swaps reaching ROIDS must return at least their simulated output minus
+    /// this buffer (in bps). The max spread of each hop still applies
+    #[serde(default)]
+    pub min_receive_buffer_bps: Option<u16>,
+    /// Whether a failed ROIDS burn is recorded as pending instead of reverting the collect
+    #[serde(default)]
+    pub lenient_burn: bool,
+    /// If set, the maximum number of stored routes, bridges included
+    #[serde(default)]
+    pub max_bridges: Option<u32>,
+    /// Assets with an elastic supply whose balances can change between queries
+    #[serde(default)]
+    pub rebasing_assets: Vec<AssetInfo>,
+    /// Whether any failed swap reverts the whole collect, even with the circuit breaker enabled
+    #[serde(default)]
+    pub strict_collect: bool,
+    /// If set, collects are rejected for this many seconds after unpausing
+    #[serde(default)]
+    pub post_unpause_grace_secs: Option<u64>,
+    /// If set, blocked burns are re-attempted on this many subsequent collects regardless of the burn frequency
+    #[serde(default)]
+    pub burn_retry_limit: Option<u32>,
+    /// Whether routes discovered during a collect are saved as bridges
+    #[serde(default)]
+    pub learn_routes: bool,
+    /// If set, the caller of a collect is paid this share of the ROIDS its swaps realized (in bps)
+    #[serde(default)]
+    pub keeper_fee_bps: Option<u16>,
+    /// If set, swaps are made along whole routes through the router instead of pair by pair
+    #[serde(default)]
+    pub router_contract: Option<Addr>,
+    /// If set, this address can pause and unpause the Maker alongside the pauser
+    #[serde(default)]
+    pub guardian: Option<Addr>,
+    /// If set, the delay (in seconds) between proposing and executing a seize. Defaults to [`DEFAULT_SEIZE_DELAY_SECS`]
+    #[serde(default)]
+    pub seize_delay_secs: Option<u64>,
+    /// If set, collects skip fee token balances below this amount
+    #[serde(default)]
+    pub min_collect_amount: Option<Uint128>,
+    /// Whether failed swaps of a collect quarantine their asset instead of reverting the collect
+    #[serde(default)]
+    pub quarantine_failed_swaps: bool,
+    /// The secondary receiver of a share of each distribution
+    #[serde(default)]
+    pub second_receiver_cfg: Option<SecondReceiverConfig>,
+    /// The dev fund receiving a share of each distribution in its chosen asset
+    #[serde(default)]
+    pub dev_fund_config: Option<DevFundConfig>,
+    /// Max value (in ROIDS) swapped by the collects of a cooldown window. Amounts above it are
+    /// deferred to the next window
+    #[serde(default)]
+    pub max_collect_value_per_window: Option<Uint128>,
+    /// The terminal action applied to distributed ROIDS
+    #[serde(default)]
+    pub distribution_mode: DistributionMode,
+    /// The payload ROIDS is sent to the Asteroid bridge contract with. A bare transfer if not set
+    #[serde(default)]
+    pub bridge_call: Option<BridgeCall>,
+    /// Maximum number of bridges between a fee token and ROIDS when swapping. The default depth applies if not set
+    #[serde(default)]
+    pub bridges_execution_max_depth: Option<u64>,
+    /// Whether distributed ROIDS is burned with a TokenFactory MsgBurn. Requires ROIDS to be a TokenFactory denom administered by the Maker
+    #[serde(default)]
+    pub tokenfactory_burn: bool,
+    /// Maximum number of assets swapped by a single collect transaction. The remaining assets are swapped by ContinueCollect
+    #[serde(default)]
+    pub max_assets_per_tx: Option<u32>,
+    /// Where the belief price of single hop swaps is taken from
+    #[serde(default)]
+    pub belief_price_source: BeliefPriceSource,
+    /// What a collect does with an asset whose swap exceeds the max spread
+    #[serde(default)]
+    pub on_spread_exceeded: FailOrSkip,
+    /// Amount of ROIDS the Maker always retains instead of distributing it
+    #[serde(default)]
+    pub reserve_amount: Option<Uint128>,
+    /// Auction started for fee tokens without a swap route instead of failing the collect
+    #[serde(default)]
+    pub auction_fallback: Option<AuctionFallback>,
+    /// If set, the delay (in seconds) between proposing and applying a change of the critical settings. Defaults to [`DEFAULT_CONFIG_CHANGE_DELAY_SECS`]
+    #[serde(default)]
+    pub config_change_delay_secs: Option<u64>,
+    /// Operator able to update the bridges and the max spreads
+    #[serde(default)]
+    pub operator: Option<Addr>,
 }
 
 /// This structure stores general parameters for the contract.
@@ -43,36 +160,284 @@ pub struct InstantiateMsg {
     pub max_spread: Option<Decimal>,
     /// If set defines the period when maker collect can be called
     pub collect_cooldown: Option<u64>,
+    /// If set, collects must carry a deadline at most this many seconds ahead of their execution
+    pub swap_deadline_secs: Option<u64>,
 }
 
 /// This structure describes the functions that can be executed in this contract.
 #[cw_serde]
 pub enum ExecuteMsg {
-    /// Collects and swaps fee tokens to ASTRO
+    /// Receives CW20 fee tokens sent with a [`Cw20HookMsg`]
+    Receive(Cw20ReceiveMsg),
+    /// Collects and swaps fee tokens to ASTRO. The response data holds the ROIDS realized per unit
+    /// of each asset swapped directly to ROIDS as a `Vec<(AssetInfo, Decimal)>`. Prices of
+    /// bridged assets are emitted as `effective_price` events once their last hop is executed
     Collect {
         /// The assets to swap to ASTRO
         assets: Vec<AssetWithLimit>,
+        /// Restricts the collect to native or CW20 assets. Both are processed by default
+        asset_type_filter: Option<AssetTypeFilter>,
+        /// Routes the keeper planned for the assets, excluding the asset itself and ending with ROIDS.
+        /// The collect fails if any of them resolves differently
+        expected_routes: Option<Vec<(AssetInfo, Vec<AssetInfo>)>>,
+        /// Timestamp (in seconds) after which the collect fails instead of swapping at stale prices
+        deadline: Option<u64>,
+        /// Minimum ROIDS the swaps of the collect must realize altogether. Checked once all swaps
+        /// settled; the whole collect reverts if not met
+        min_roids_out: Option<Uint128>,
     },
+    /// Collects every routable fee token held by the Maker. The assets are taken from the pairs
+    /// registered in the factory, so keepers don't have to track new pools
+    CollectAll {
+        /// The number of factory pairs to read. Capped by the factory's own page limit
+        limit: Option<u32>,
+    },
+    /// Swaps the assets a collect left over because of `max_assets_per_tx`. Continuations aren't
+    /// subject to the collect cooldown
+    ContinueCollect {},
+    /// Swaps fee tokens to ROIDS bypassing the collect cooldown and the collect value budget.
+    /// Meant for incident response, only the owner or the guardian can execute this
+    ForceCollect { assets: Vec<AssetWithLimit> },
+    /// Donates the attached native tokens to the burn pool. The donor and the amounts are
+    /// recorded on chain
+    Donate {},
     /// Updates general settings
     UpdateConfig {
-        /// The factory contract address
-        factory_contract: Option<String>,
         /// Basic chain asset (Terra1 - LUNC, Terra2 - LUNA, etc.)
         basic_asset: Option<AssetInfo>,
         /// The maximum spread used when swapping fee tokens to ASTRO
         max_spread: Option<Decimal>,
-        /// Defines the period when maker collect can be called
+        /// Defines the period when maker collect can be called. A change applies from the next
+        /// collect on, the current cooldown window keeps its original end
         collect_cooldown: Option<u64>,
+        /// Max number of seconds a collect deadline may lie ahead, collects must carry one if set.
+        /// 0 lifts the bound
+        swap_deadline_secs: Option<u64>,
+        /// Number of consecutive failed collects after which the Maker pauses itself. 0 disables
+        /// the circuit breaker
+        auto_pause_threshold: Option<u32>,
+        /// Number of collects to accumulate ROIDS over before burning. 1 burns every collect
+        burn_every_n_collects: Option<u32>,
+        /// Max balance of an asset that can be swept with `ConsolidateDust`
+        dust_threshold: Option<Uint128>,
+        /// Exclusive collect window granted to a committed keeper. 0 disables commitments
+        keeper_commit_window_secs: Option<u64>,
+        /// Buffer (in bps) subtracted from the simulated output of router swaps to get their
+        /// minimum receive. 0 disables minimum receive checks
+        min_receive_buffer_bps: Option<u16>,
+        /// Whether a failed ROIDS burn is recorded as pending instead of reverting the collect
+        lenient_burn: Option<bool>,
+        /// The maximum number of stored routes, bridges included. Can't be lower than the current
+        /// number of routes. 0 removes the limit
+        max_bridges: Option<u32>,
+        /// Whether any failed swap reverts the whole collect, even with the circuit breaker enabled
+        strict_collect: Option<bool>,
+        /// Seconds after unpausing during which collects are rejected. 0 disables the grace window
+        post_unpause_grace_secs: Option<u64>,
+        /// Number of collects re-attempting a blocked burn. 0 disables automatic retries
+        burn_retry_limit: Option<u32>,
+        /// Whether routes discovered during a collect are saved as bridges
+        learn_routes: Option<bool>,
+        /// Share of the ROIDS realized by a collect paid to its caller (in bps). 0 disables the keeper fee
+        keeper_fee_bps: Option<u16>,
+        /// Guardian able to pause and unpause the Maker. An empty string removes the guardian
+        guardian: Option<String>,
+        /// Delay (in seconds) between proposing and executing a seize
+        seize_delay_secs: Option<u64>,
+        /// Balance below which fee tokens are skipped by collects. 0 swaps any balance again
+        min_collect_amount: Option<Uint128>,
+        /// Whether failed swaps of a collect quarantine their asset instead of reverting the collect
+        quarantine_failed_swaps: Option<bool>,
+        /// Max value (in ROIDS) swapped by the collects of a cooldown window. 0 removes the limit
+        max_collect_value_per_window: Option<Uint128>,
+        /// Maximum number of bridges between a fee token and ROIDS when swapping
+        bridges_execution_max_depth: Option<u64>,
+        /// Whether distributed ROIDS is burned with a TokenFactory MsgBurn
+        tokenfactory_burn: Option<bool>,
+        /// Maximum number of assets swapped by a single collect transaction, 0 if unlimited
+        max_assets_per_tx: Option<u32>,
+        /// Where the belief price of single hop swaps is taken from
+        belief_price_source: Option<BeliefPriceSource>,
+        /// What a collect does with an asset whose swap exceeds the max spread
+        on_spread_exceeded: Option<FailOrSkip>,
+        /// Amount of ROIDS the Maker always retains, 0 to distribute the whole balance
+        reserve_amount: Option<Uint128>,
+        /// Auction started for fee tokens without a swap route. A zero duration removes it
+        auction_fallback: Option<AuctionFallback>,
+        /// Operator able to update the bridges and the max spreads. An empty string removes the operator
+        operator: Option<String>,
+    },
+    /// Proposes a change of the settings deciding where fees end up. The change can be applied
+    /// once the config change delay passed, giving time to react to a compromised owner
+    ProposeConfigChange {
+        /// The factory contract address
+        factory_contract: Option<String>,
         /// The ROIDS token asset info
         roids_token: Option<AssetInfo>,
         /// The Asteroid bridge contract
         asteroid_contract: Option<String>,
+        /// The delay (in seconds) between proposing and applying the following changes
+        config_change_delay_secs: Option<u64>,
+        /// Router contract used to swap along whole routes. An empty string swaps pair by pair again
+        router_contract: Option<String>,
+        /// The secondary receiver of a share of each distribution. A zero cut removes it
+        second_receiver_params: Option<SecondReceiverParams>,
+        /// The dev fund receiving a share of each distribution in its chosen asset. A zero share removes it
+        dev_fund_config: Option<DevFundConfig>,
+        /// The terminal action applied to distributed ROIDS
+        distribution_mode: Option<DistributionMode>,
+        /// The payload ROIDS is sent to the Asteroid bridge contract with. An empty destination removes it
+        bridge_call: Option<BridgeCall>,
+        /// External DEXes specific fee tokens are swapped on instead of the factory pairs
+        external_dexes: Option<Vec<(AssetInfo, ExternalDex)>>,
+    },
+    /// Applies the pending config change once its delay passed
+    ApplyConfigChange {},
+    /// Removes the pending config change. The guardian can drop it as well
+    DropConfigChange {},
+    /// Updates the max spread and the collect cooldown together. A tight max spread can't be
+    /// combined with a short cooldown
+    TuneRisk {
+        max_spread: Decimal,
+        collect_cooldown: u64,
     },
-    /// Add bridge tokens used to swap specific fee tokens to ASTRO (effectively declaring a swap route)
+    /// Distributes ROIDS standing in the Maker, e.g. received via a direct transfer
+    FlushRoids {},
+    /// Reserves the next collect for the sender during the keeper commit window
+    CommitCollect {},
+    /// Swaps many dust balances to ROIDS in one pass. Every balance must be at or below the dust
+    /// threshold and consolidations are subject to the collect cooldown, grace period and keeper
+    /// commitments
+    ConsolidateDust { assets: Vec<AssetInfo> },
+    /// Swaps the whole balance of an asset without a route to ROIDS to an intermediate asset.
+    /// The intermediate is left in the Maker for a later collect
+    SwapToIntermediate {
+        asset: AssetInfo,
+        intermediate: AssetInfo,
+        max_spread: Decimal,
+    },
+    /// Add bridge tokens used to swap specific fee tokens to ASTRO (effectively declaring a swap route).
+    /// Can be executed by the operator as well as the bridge manager
     UpdateBridges {
         add: Option<Vec<(AssetInfo, AssetInfo)>>,
         remove: Option<Vec<AssetInfo>>,
     },
+    /// Bulk loads routes exported with [`QueryMsg::ExportBridges`], e.g. into a new deployment.
+    /// The bridges are validated once all of them are stored, so their order doesn't matter
+    ImportBridges { entries: Vec<BridgeInfo> },
+    /// Sets full routes to ROIDS for fee tokens which need several hops. A route replaces the
+    /// bridge of its fee token and is swapped at once through the router, which must be set
+    UpdateRoutes {
+        add: Option<Vec<(AssetInfo, Vec<AssetInfo>)>>,
+        remove: Option<Vec<AssetInfo>>,
+    },
+    /// Proposes a full route of a fee token to ROIDS. Anyone can propose a route; it is set
+    /// as if by `UpdateRoutes` once the owner approves it
+    ProposeBridge {
+        /// The fee token to route
+        asset: AssetInfo,
+        /// The route of the fee token. Every hop must have a pair in the factory and the last
+        /// hop must be ROIDS
+        route: Vec<AssetInfo>,
+    },
+    /// Approves or rejects the pending route proposal of a fee token
+    ReviewBridgeProposal {
+        /// The fee token whose proposal is reviewed
+        asset: AssetInfo,
+        /// Whether the proposed route is set
+        approve: bool,
+    },
+    /// Sets or removes the minimum ROIDS swept fee tokens must realize. The minimum is checked
+    /// in the swap replies once an asset reaches ROIDS and reverts the collect if not met
+    UpdateMinReceived {
+        add: Option<Vec<(AssetInfo, MinReceived)>>,
+        remove: Option<Vec<AssetInfo>>,
+    },
+    /// Sets or removes max spreads of specific fee tokens which take precedence over the global one.
+    /// Can be executed by the operator as well as the owner
+    UpdateSpreads {
+        add: Option<Vec<(AssetInfo, Decimal)>>,
+        remove: Option<Vec<AssetInfo>>,
+    },
+    /// Sets or removes the TWAP oracles the pool prices of specific assets are checked against
+    /// before swapping them
+    UpdatePriceOracles {
+        add: Option<Vec<(AssetInfo, PriceOracle)>>,
+        remove: Option<Vec<AssetInfo>>,
+    },
+    /// Removes the external DEXes of specific fee tokens so they are swapped in the factory pairs
+    /// again. External DEXes are set with [`ExecuteMsg::ProposeConfigChange`]
+    UpdateExternalDexes { remove: Option<Vec<AssetInfo>> },
+    /// Adds or removes assets which are never swapped, even if passed to a collect
+    UpdateBlacklist {
+        add: Option<Vec<AssetInfo>>,
+        remove: Option<Vec<AssetInfo>>,
+    },
+    /// Adds or removes callers whose collects aren't subject to the collect cooldown
+    UpdateCooldownExempt {
+        add: Option<Vec<String>>,
+        remove: Option<Vec<String>>,
+    },
+    /// Adds or removes contracts called with [`DistributionHookMsg::AfterDistribute`] after each
+    /// distribution. A failing hook doesn't revert the distribution
+    UpdateDistributionHooks {
+        add: Option<Vec<String>>,
+        remove: Option<Vec<String>>,
+    },
+    /// Pauses or unpauses collects
+    SetPaused { paused: bool },
+    /// Pauses collects along with the bridge swaps and distributions of a collect in flight.
+    /// Can be executed by the guardian as well as the pauser
+    Pause {},
+    /// Unpauses the Maker. Can be executed by the guardian as well as the pauser
+    Unpause {},
+    /// Proposes to send assets without a route to ROIDS out of the Maker. The proposal can be
+    /// executed with `Seize {}` once the seize delay passed and replaces any pending one
+    ProposeSeize {
+        /// The assets to send. Each of them must have no route to ROIDS
+        assets: Vec<Asset>,
+        /// The address receiving the assets
+        recipient: String,
+    },
+    /// Executes the pending seize proposal
+    Seize {},
+    /// Removes the pending seize proposal
+    DropSeizeProposal {},
+    /// Starts a declining price auction selling a fee token for ROIDS. The price falls linearly
+    /// from `start_price` to `end_price` over `duration_secs` and stays at `end_price` afterwards.
+    /// Prices are in ROIDS per unit of the fee token
+    StartAuction {
+        /// The fee token and the amount to sell
+        asset: Asset,
+        start_price: Decimal,
+        end_price: Decimal,
+        duration_secs: u64,
+    },
+    /// Cancels the auction of a fee token. The unsold amount is collected normally again
+    CancelAuction { asset_info: AssetInfo },
+    /// Buys a fee token from its auction with the attached native ROIDS. ROIDS paid for more
+    /// than the unsold amount is refunded
+    BidAuction { asset_info: AssetInfo },
+    /// Collects the fee tokens whose swaps failed and were quarantined
+    RetryFailed {},
+    /// Pulls ROIDS mistakenly sent by the Maker to the factory or a pair using the Maker's CW20 allowance.
+    /// Can only be executed while collects are paused
+    ReclaimRoids {
+        /// The factory or pair address holding the ROIDS
+        from: String,
+        /// The amount of ROIDS to reclaim
+        amount: Uint128,
+    },
+    /// Sets the whitelist of assets allowed to be used as bridges. `None` allows any asset
+    UpdateApprovedBridgeAssets { assets: Option<Vec<AssetInfo>> },
+    /// Sets the assets with an elastic supply. Only part of their balance is swapped to absorb
+    /// rebases happening before the swap is executed
+    UpdateRebasingAssets { assets: Vec<AssetInfo> },
+    /// Caches the decimals of the bridge assets, ROIDS and the default bridge
+    RefreshDecimals {},
+    /// Hands a role over to another address. Only the current holder of the role can do this,
+    /// so an owner handing a role over renounces it
+    AssignRole { role: Role, address: String },
     /// Swap fee tokens via bridge assets
     SwapBridgeAssets { assets: Vec<AssetInfo>, depth: u64 },
     /// Distribute ASTRO to stakers and to governance
@@ -88,6 +453,35 @@ pub enum ExecuteMsg {
     DropOwnershipProposal {},
     /// Claims contract ownership
     ClaimOwnership {},
+    /// Releases the ROIDS held from before the Maker upgrade linearly over the specified number
+    /// of blocks instead of distributing it at once. Can only be executed once
+    EnableRewards { blocks: u64 },
+}
+
+/// This structure describes the sudo messages the chain can send to the contract.
+#[cw_serde]
+pub enum SudoMsg {
+    /// Collects every held fee token found in the factory pairs. Sent by a chain cron module;
+    /// does nothing while the Maker is paused or the collect cooldown is running
+    Collect {},
+    /// Reports the outcome of an ICS20 transfer of ROIDS. Sent by the IBC hooks middleware for
+    /// transfers carrying the callback of the Maker
+    IbcLifecycleComplete(IbcLifecycleComplete),
+}
+
+/// This structure describes the outcome of an ICS20 transfer as reported by the IBC hooks
+/// middleware.
+#[cw_serde]
+pub enum IbcLifecycleComplete {
+    /// The Hub acknowledged the transfer, with a successful or an error acknowledgement
+    IbcAck {
+        channel: String,
+        sequence: u64,
+        ack: String,
+        success: bool,
+    },
+    /// The transfer timed out
+    IbcTimeout { channel: String, sequence: u64 },
 }
 
 /// This structure describes the query functions available in the contract.
@@ -97,11 +491,159 @@ pub enum QueryMsg {
     /// Returns information about the maker configs that contains in the [`ConfigResponse`]
     #[returns(ConfigResponse)]
     Config {},
+    /// Returns the configuration with every unset option resolved to the value the contract uses
+    #[returns(EffectiveConfigResponse)]
+    EffectiveConfig {},
+    /// Returns the non-zero balances of every native coin held by the Maker and of every CW20
+    /// token traded in the factory pairs, ordered by asset
+    #[returns(BalancesResponse)]
+    AllBalances {
+        /// The asset to start reading after
+        start_after: Option<AssetInfo>,
+        /// The number of balances to return
+        limit: Option<u32>,
+    },
     /// Returns the balance for each asset in the specified input parameters
     #[returns(BalancesResponse)]
-    Balances { assets: Vec<AssetInfo> },
-    #[returns(Vec<(String, String)>)]
-    Bridges {},
+    Balances {
+        assets: Vec<AssetInfo>,
+        /// Whether zero balances are returned too. Defaults to false
+        include_zero: Option<bool>,
+    },
+    /// Returns the bridges of fee tokens ordered by fee token. Routes of several hops are
+    /// returned by [`QueryMsg::Routes`]
+    #[returns(BridgesResponse)]
+    Bridges {
+        /// The fee token to start reading after
+        start_after: Option<AssetInfo>,
+        /// The number of bridges to read
+        limit: Option<u32>,
+    },
+    /// Returns every route, bridges included, ordered by fee token, in the format
+    /// [`ExecuteMsg::ImportBridges`] takes
+    #[returns(Vec<BridgeInfo>)]
+    ExportBridges {},
+    /// Re-validates the routes of the specified fee tokens, or every route, and returns the
+    /// broken ones
+    #[returns(Vec<BrokenRoute>)]
+    ValidateRoutes { assets: Option<Vec<AssetInfo>> },
+    /// Returns the stored routes of fee tokens to ROIDS, bridges being routes of a single asset
+    #[returns(Vec<(String, Vec<AssetInfo>)>)]
+    Routes {},
+    /// Returns the minimum ROIDS fee tokens must realize when collected
+    #[returns(Vec<(String, MinReceived)>)]
+    MinReceived {},
+    /// Returns the max spreads of specific fee tokens
+    #[returns(Vec<(String, Decimal)>)]
+    Spreads {},
+    /// Returns the TWAP oracles of specific assets
+    #[returns(Vec<(String, PriceOracle)>)]
+    PriceOracles {},
+    /// Returns the external DEXes of specific fee tokens
+    #[returns(Vec<(String, ExternalDex)>)]
+    ExternalDexes {},
+    /// Returns the assets which are never swapped
+    #[returns(Vec<AssetInfo>)]
+    Blacklist {},
+    /// Returns the callers whose collects aren't subject to the collect cooldown
+    #[returns(Vec<Addr>)]
+    CooldownExempt {},
+    /// Returns the contracts called after each distribution
+    #[returns(Vec<Addr>)]
+    DistributionHooks {},
+    /// Returns the specified assets which have a direct pair with ROIDS
+    #[returns(DirectPairsResponse)]
+    DirectPairs { assets: Vec<AssetInfo> },
+    /// Returns where distributed ROIDS ends up and the setting it was resolved from
+    #[returns(BurnDestinationResponse)]
+    ResolveBurnDestination {},
+    /// Returns the call made to the Asteroid bridge contract when ROIDS is bridged
+    #[returns(BridgeCallResponse)]
+    BridgeCall {},
+    /// Simulates a collect of the specified assets including all bridge hops
+    #[returns(SimulateCollectResponse)]
+    SimulateCollect { assets: Vec<AssetWithLimit> },
+    /// Simulates the swap of each of the specified assets to ROIDS along its route
+    #[returns(Vec<SwapSimulation>)]
+    SimulateSwaps { assets: Vec<AssetWithLimit> },
+    /// Returns the ROIDS a single collect of every held routable asset would produce right now,
+    /// within the assets per transaction and the value budget of the current window
+    #[returns(MaxCollectNowResponse)]
+    MaxCollectNow {},
+    /// Returns the latest collect time and when the next collect is allowed
+    #[returns(CollectStatusResponse)]
+    CollectStatus {},
+    /// Returns the current keeper commitment, if any
+    #[returns(Option<KeeperCommitment>)]
+    KeeperCommitment {},
+    /// Returns the pending seize proposal, if any
+    #[returns(Option<SeizeProposal>)]
+    SeizeProposal {},
+    /// Returns the pending change of the critical settings, if any
+    #[returns(Option<ConfigChange>)]
+    PendingConfigChange {},
+    /// Returns the auction of a fee token along with its current price, if any
+    #[returns(Option<AuctionResponse>)]
+    Auction { asset_info: AssetInfo },
+    /// Returns the pending route proposals ordered by fee token
+    #[returns(Vec<BridgeProposal>)]
+    BridgeProposals {},
+    /// Returns the fee tokens whose swaps failed and await a retry
+    #[returns(Vec<FailedSwap>)]
+    FailedAssets {},
+    /// Simulates a collect of the specified assets with config overrides applied in memory
+    #[returns(SimulateCollectResponse)]
+    SimulateWithConfig {
+        overrides: ConfigOverrides,
+        assets: Vec<AssetWithLimit>,
+    },
+    /// Returns the latest collects starting from the most recent one
+    #[returns(Vec<CollectorRecord>)]
+    RecentCollectors { limit: Option<u32> },
+    /// Returns the successful config and bridge updates starting from the oldest one
+    #[returns(Vec<ConfigHistoryEntry>)]
+    ConfigHistory {
+        /// The ID of the entry to start reading after
+        start_after: Option<u64>,
+        /// The number of entries to read
+        limit: Option<u32>,
+    },
+    /// Returns the amount of ROIDS whose burn was blocked by the token, if any
+    #[returns(Option<Uint128>)]
+    PendingBurn {},
+    /// Returns the net amount of ROIDS removed from circulation by the Maker
+    #[returns(SupplyImpactResponse)]
+    SupplyImpact {},
+    /// Returns lifetime collect and distribution statistics
+    #[returns(StatisticsResponse)]
+    Statistics {},
+    /// Returns the release of the ROIDS held from before the Maker upgrade, if rewards were enabled
+    #[returns(Option<PreUpgradeRewards>)]
+    PreUpgradeRewards {},
+    /// Returns the swap with the highest slippage since the latest collect, if any
+    #[returns(Option<HopSlippage>)]
+    WorstHop {},
+    /// Checks whether a new fee token can be swapped to ROIDS or which bridges would allow it
+    #[returns(CanOnboardResponse)]
+    CanOnboard { asset: AssetInfo },
+    /// Returns the expected and the worst case amount of ROIDS a swap of a fee token returns
+    #[returns(SwapPreviewResponse)]
+    SwapPreview { asset: AssetInfo, amount: Uint128 },
+    /// Returns every hop a fee token takes to ROIDS along with the pair used for it
+    #[returns(RouteResponse)]
+    Route { asset_info: AssetInfo },
+    /// Returns the total amount of a fee token swapped by the Maker
+    #[returns(AssetStats)]
+    AssetStats { asset_info: AssetInfo },
+    /// Returns the total amounts donated by a donor
+    #[returns(Vec<Asset>)]
+    Donations { donor: String },
+    /// Returns the owner and the holder of each role
+    #[returns(PermissionsResponse)]
+    Permissions {},
+    /// Returns the assets whose route changed after the given block height
+    #[returns(RoutesChangedSinceResponse)]
+    RoutesChangedSince { height: u64 },
 }
 
 /// A custom struct that holds contract parameters and is used to retrieve them.
@@ -119,6 +661,186 @@ pub struct ConfigResponse {
     pub asteroid_contract: Addr,
     /// The maximum spread used when swapping fee tokens to ROIDS
     pub max_spread: Decimal,
+    /// Max number of seconds a collect deadline may lie ahead
+    pub swap_deadline_secs: Option<u64>,
+    /// Assets allowed to be used as bridges
+    pub approved_bridge_assets: Option<Vec<AssetInfo>>,
+    /// Number of consecutive failed collects after which the Maker pauses itself
+    pub auto_pause_threshold: Option<u32>,
+    /// Number of collects ROIDS is accumulated over before burning
+    pub burn_every_n_collects: Option<u32>,
+    /// Max balance of an asset that can be swept with `ConsolidateDust`
+    pub dust_threshold: Option<Uint128>,
+    /// Exclusive collect window granted to a committed keeper
+    pub keeper_commit_window_secs: Option<u64>,
+    /// Buffer (in bps) subtracted from the simulated output of router swaps to get their
+    /// minimum receive
+    pub min_receive_buffer_bps: Option<u16>,
+    /// Whether a failed ROIDS burn is recorded as pending instead of reverting the collect
+    pub lenient_burn: bool,
+    /// The maximum number of stored routes, bridges included
+    pub max_bridges: Option<u32>,
+    /// Assets with an elastic supply
+    pub rebasing_assets: Vec<AssetInfo>,
+    /// Whether any failed swap reverts the whole collect
+    pub strict_collect: bool,
+    /// Seconds after unpausing during which collects are rejected
+    pub post_unpause_grace_secs: Option<u64>,
+    /// Number of collects re-attempting a blocked burn
+    pub burn_retry_limit: Option<u32>,
+    /// Whether routes discovered during a collect are saved as bridges
+    pub learn_routes: bool,
+    /// Share of the ROIDS realized by a collect paid to its caller (in bps)
+    pub keeper_fee_bps: Option<u16>,
+    /// Router contract used to swap along whole routes
+    pub router_contract: Option<Addr>,
+    /// Guardian able to pause and unpause the Maker
+    pub guardian: Option<Addr>,
+    /// Delay (in seconds) between proposing and executing a seize
+    pub seize_delay_secs: Option<u64>,
+    /// Balance below which fee tokens are skipped by collects
+    pub min_collect_amount: Option<Uint128>,
+    /// Whether failed swaps of a collect quarantine their asset instead of reverting the collect
+    pub quarantine_failed_swaps: bool,
+    /// The secondary receiver of a share of each distribution
+    pub second_receiver_cfg: Option<SecondReceiverConfig>,
+    /// The dev fund receiving a share of each distribution in its chosen asset
+    pub dev_fund_config: Option<DevFundConfig>,
+    /// Max value (in ROIDS) swapped by the collects of a cooldown window
+    pub max_collect_value_per_window: Option<Uint128>,
+    /// The terminal action applied to distributed ROIDS
+    pub distribution_mode: DistributionMode,
+    /// The payload ROIDS is sent to the Asteroid bridge contract with
+    pub bridge_call: Option<BridgeCall>,
+    /// Maximum number of bridges between a fee token and ROIDS when swapping
+    pub bridges_execution_max_depth: Option<u64>,
+    /// Whether distributed ROIDS is burned with a TokenFactory MsgBurn
+    pub tokenfactory_burn: bool,
+    /// Maximum number of assets swapped by a single collect transaction
+    pub max_assets_per_tx: Option<u32>,
+    /// Where the belief price of single hop swaps is taken from
+    pub belief_price_source: BeliefPriceSource,
+    /// What a collect does with an asset whose swap exceeds the max spread
+    pub on_spread_exceeded: FailOrSkip,
+    /// Amount of ROIDS the Maker always retains
+    pub reserve_amount: Option<Uint128>,
+    /// Auction started for fee tokens without a swap route
+    pub auction_fallback: Option<AuctionFallback>,
+    /// Delay (in seconds) between proposing and applying a change of the critical settings
+    pub config_change_delay_secs: Option<u64>,
+    /// Operator able to update the bridges and the max spreads
+    pub operator: Option<Addr>,
+    /// Whether collects are paused
+    pub paused: bool,
+}
+
+/// The configuration the contract actually runs with. Unlike [`ConfigResponse`], unset options are
+/// resolved to their defaults; zero means a feature is disabled.
+#[cw_serde]
+pub struct EffectiveConfigResponse {
+    /// Address that is allowed to update contract parameters
+    pub owner: Addr,
+    /// The factory contract address
+    pub factory_contract: Addr,
+    /// The Asteroid bridge contract
+    pub asteroid_contract: Addr,
+    /// Where distributed ROIDS ends up, see [`BurnDestinationResponse`]
+    pub burn_destination: String,
+    /// The ROIDS token asset info
+    pub roids_token: AssetInfo,
+    /// Default bridge asset. None if fee tokens without a bridge must have a ROIDS pair
+    pub default_bridge: Option<AssetInfo>,
+    /// The max spread allowed when swapping fee tokens to ROIDS
+    pub max_spread: Decimal,
+    /// The max spread used for swaps in concentrated liquidity pairs. It isn't configurable on its
+    /// own but derived from `max_spread` by adding the max fee of these pairs
+    pub concentrated_max_spread: Decimal,
+    /// Seconds between collects
+    pub collect_cooldown: u64,
+    /// Max number of seconds a collect deadline may lie ahead, 0 if collects need no deadline
+    pub swap_deadline_secs: u64,
+    /// Assets usable as bridges. None if any asset can be used
+    pub approved_bridge_assets: Option<Vec<AssetInfo>>,
+    /// Consecutive failed collects after which the Maker pauses itself
+    pub auto_pause_threshold: u32,
+    /// Number of collects between ROIDS burns
+    pub burn_every_n_collects: u32,
+    /// Balances up to this amount can be swept with `ConsolidateDust`
+    pub dust_threshold: Uint128,
+    /// Seconds a keeper gets to execute a committed collect exclusively
+    pub keeper_commit_window_secs: u64,
+    /// Buffer subtracted from the simulated output of router swaps (in bps)
+    pub min_receive_buffer_bps: u16,
+    /// Whether a failed ROIDS burn is recorded as pending instead of reverting the collect
+    pub lenient_burn: bool,
+    /// Number of collects re-attempting a blocked burn
+    pub burn_retry_limit: u32,
+    /// Maximum number of stored routes, bridges included
+    pub max_bridges: u32,
+    /// Maximum number of bridges between a fee token and ROIDS when adding a bridge
+    pub bridges_max_depth: u64,
+    /// Maximum number of bridges between a fee token and ROIDS when swapping
+    pub bridges_execution_max_depth: u64,
+    /// Assets with an elastic supply
+    pub rebasing_assets: Vec<AssetInfo>,
+    /// Buffer subtracted from the balances of rebasing assets (in bps)
+    pub rebase_drift_buffer_bps: u16,
+    /// Whether any failed swap reverts the whole collect
+    pub strict_collect: bool,
+    /// Seconds after unpausing during which collects are rejected
+    pub post_unpause_grace_secs: u64,
+    /// Whether routes discovered during a collect are saved as bridges
+    pub learn_routes: bool,
+    /// Share of the ROIDS realized by a collect paid to its caller (in bps)
+    pub keeper_fee_bps: u16,
+    /// Router contract swaps are made through. None if swaps are made pair by pair
+    pub router_contract: Option<Addr>,
+    /// Guardian able to pause and unpause the Maker. None if only the pauser can
+    pub guardian: Option<Addr>,
+    /// Delay (in seconds) between proposing and executing a seize
+    pub seize_delay_secs: u64,
+    /// Balance below which fee tokens are skipped by collects. Zero if any balance is swapped
+    pub min_collect_amount: Uint128,
+    /// Whether failed swaps of a collect quarantine their asset instead of reverting the collect
+    pub quarantine_failed_swaps: bool,
+    /// The secondary receiver of a share of each distribution
+    pub second_receiver_cfg: Option<SecondReceiverConfig>,
+    /// The dev fund receiving a share of each distribution in its chosen asset
+    pub dev_fund_config: Option<DevFundConfig>,
+    /// Max value (in ROIDS) swapped by the collects of a cooldown window, 0 if unlimited
+    pub max_collect_value_per_window: Uint128,
+    /// The terminal action applied to distributed ROIDS
+    pub distribution_mode: DistributionMode,
+    /// The payload ROIDS is sent to the Asteroid bridge contract with
+    pub bridge_call: Option<BridgeCall>,
+    /// Whether distributed ROIDS is burned with a TokenFactory MsgBurn
+    pub tokenfactory_burn: bool,
+    /// Maximum number of assets swapped by a single collect transaction, 0 if unlimited
+    pub max_assets_per_tx: u32,
+    /// Where the belief price of single hop swaps is taken from
+    pub belief_price_source: BeliefPriceSource,
+    /// What a collect does with an asset whose swap exceeds the max spread
+    pub on_spread_exceeded: FailOrSkip,
+    /// Amount of ROIDS the Maker always retains, 0 if the whole balance is distributed
+    pub reserve_amount: Uint128,
+    /// Auction started for fee tokens without a swap route
+    pub auction_fallback: Option<AuctionFallback>,
+    /// Delay (in seconds) between proposing and applying a change of the critical settings
+    pub config_change_delay_secs: u64,
+    /// Operator able to update the bridges and the max spreads. None if only the bridge manager
+    /// and the owner can
+    pub operator: Option<Addr>,
+    /// Whether collects are paused
+    pub paused: bool,
+}
+
+/// This structure is set as the response data of [`ExecuteMsg::DistributeAstro`].
+#[cw_serde]
+pub struct DistributeResponseData {
+    /// The amount of ROIDS distributed. Zero if there was nothing to distribute
+    pub amount: Uint128,
+    /// Where ROIDS was distributed to, see [`BurnDestinationResponse`]
+    pub destination: String,
 }
 
 /// A custom struct used to return multiple asset balances.
@@ -127,10 +849,659 @@ pub struct BalancesResponse {
     pub balances: Vec<Asset>,
 }
 
+/// A custom struct used to return assets which can be swapped to ROIDS directly.
+#[cw_serde]
+pub struct DirectPairsResponse {
+    /// Assets along with the address of their pair with ROIDS
+    pub pairs: Vec<(AssetInfo, Addr)>,
+}
+
+/// This enum describes the settings a burn destination can be resolved from, by precedence.
+#[cw_serde]
+pub enum BurnDestinationSource {
+    /// ROIDS is a TokenFactory denom burned by the Maker with `tokenfactory_burn`
+    TokenfactoryBurn,
+    /// ROIDS is burned by the Maker in the [`DistributionMode::Burn`] mode
+    Burn,
+    /// ROIDS is sent over ICS20 to the receiver of the [`DistributionMode::IbcTransfer`] mode
+    IbcReceiver,
+    /// ROIDS is sent to the Asteroid bridge contract set in the config
+    AsteroidContract,
+}
+
+/// A custom struct used to return the resolved burn destination.
+#[cw_serde]
+pub struct BurnDestinationResponse {
+    /// Where distributed ROIDS ends up: the ROIDS denom or CW20 contract for burns, the receiver
+    /// on the counterparty chain for ICS20 transfers, the Asteroid bridge contract otherwise
+    pub destination: String,
+    /// The setting the destination was resolved from
+    pub source: BurnDestinationSource,
+}
+
+/// The payload ROIDS is sent to the Asteroid bridge contract with.
+#[cw_serde]
+pub struct BridgeCall {
+    /// The Hub address receiving the bridged ROIDS
+    pub destination: String,
+    /// The memo forwarded by the bridge
+    pub memo: Option<String>,
+}
+
+/// This structure describes the execute message of the Asteroid bridge contract.
+#[cw_serde]
+pub enum AsteroidBridgeExecuteMsg {
+    /// Bridges the attached ROIDS to the Hub
+    Bridge {
+        destination: String,
+        memo: Option<String>,
+    },
+}
+
+/// This structure describes the execute message sent to the distribution hooks.
+#[cw_serde]
+pub enum DistributionHookMsg {
+    /// Sent after ROIDS was distributed
+    AfterDistribute { amount: Uint128 },
+}
+
+/// A custom struct used to return the call made to the Asteroid bridge contract.
+#[cw_serde]
+pub struct BridgeCallResponse {
+    /// The Asteroid bridge contract address
+    pub contract: Addr,
+    /// The payload sent along with ROIDS. None if ROIDS is transferred without one
+    pub msg: Option<Binary>,
+}
+
+/// Config values replacing the stored ones in a `SimulateWithConfig` query.
+#[cw_serde]
+#[derive(Default)]
+pub struct ConfigOverrides {
+    /// Default bridge asset
+    pub default_bridge: Option<AssetInfo>,
+    /// The max spread allowed when swapping fee tokens to ROIDS
+    pub max_spread: Option<Decimal>,
+}
+
+/// The impact of the Maker on the ROIDS supply.
+///
+/// Accounting assumptions:
+/// * ROIDS counts as burned once the transfer to the burn destination succeeded. ROIDS held by
+/// the Maker, including pending burns, isn't counted.
+/// * ROIDS counts as returned to circulation once the Maker pays it out: keeper fees, second
+/// receiver cuts and dev fund shares. The Maker doesn't mint ROIDS.
+/// * `net_removed` is `total_burned` minus `returned_to_circulation`, floored at zero.
+#[cw_serde]
+pub struct SupplyImpactResponse {
+    /// The total amount of ROIDS burned by the Maker
+    pub total_burned: Uint128,
+    /// The total amount of ROIDS the Maker put back into circulation as keeper fees, second
+    /// receiver cuts and dev fund shares
+    pub returned_to_circulation: Uint128,
+    /// The net amount of ROIDS removed from circulation
+    pub net_removed: Uint128,
+}
+
+/// This structure describes the release of the ROIDS held from before the Maker upgrade.
+#[cw_serde]
+pub struct PreUpgradeRewards {
+    /// The number of blocks the pre-upgrade ROIDS is released over
+    pub pre_upgrade_blocks: u64,
+    /// Block height of the latest distribution releasing pre-upgrade ROIDS
+    pub last_distribution_block: u64,
+    /// The amount of pre-upgrade ROIDS which wasn't released yet
+    pub rewards_amount: Uint128,
+    /// The amount of ROIDS held when rewards were enabled
+    pub pre_upgrade_amount: Uint128,
+}
+
+/// A custom struct used to return the lifetime statistics of the Maker.
+#[cw_serde]
+pub struct StatisticsResponse {
+    /// The total amount of ROIDS distributed
+    pub total_roids: Uint128,
+    /// The number of collects made
+    pub collects: u64,
+    /// The number of distributions confirmed by the ROIDS token
+    pub distributions: u64,
+    /// The amount of ROIDS of the latest distribution
+    pub last_distribution_amount: Uint128,
+    /// Timestamp (in seconds) of the latest distribution. None if nothing was distributed yet
+    pub last_distribution_ts: Option<u64>,
+}
+
+/// The routes changed after a block height.
+#[cw_serde]
+pub struct RoutesChangedSinceResponse {
+    /// Assets whose bridge was added or removed, along with the assets bridged through them
+    pub assets: Vec<String>,
+    /// Whether a change of the factory, ROIDS token or default bridge could have changed
+    /// every route
+    pub all_routes_changed: bool,
+}
+
+/// Whether a fee token can be swapped to ROIDS.
+#[cw_serde]
+pub struct CanOnboardResponse {
+    /// The route of the asset to ROIDS, if it already has one
+    pub route: Option<Vec<AssetInfo>>,
+    /// Bridges any of which would give the asset a route once set with `UpdateBridges`.
+    /// Empty if the asset already has a route
+    pub candidate_bridges: Vec<AssetInfo>,
+}
+
+/// A custom struct used to return a page of bridges.
+#[cw_serde]
+pub struct BridgesResponse {
+    /// The bridges ordered by fee token
+    pub bridges: Vec<BridgeInfo>,
+}
+
+/// The route of a fee token. A bridge is a route of a single asset.
+#[cw_serde]
+pub struct BridgeInfo {
+    /// The fee token
+    pub asset: AssetInfo,
+    /// The assets the fee token is swapped through. Routes of several hops end with ROIDS
+    pub route: Vec<AssetInfo>,
+}
+
+/// A bridge which no longer leads to ROIDS.
+#[cw_serde]
+pub struct BrokenRoute {
+    /// The fee token
+    pub asset: String,
+    /// The asset the fee token is swapped to
+    pub bridge: AssetInfo,
+    /// The reason the bridge is broken
+    pub error: String,
+}
+
+/// The amount of a fee token swapped by the Maker over its lifetime.
+#[cw_serde]
+#[derive(Default)]
+pub struct AssetStats {
+    /// The total amount of the fee token offered in swaps
+    pub total_swapped: Uint128,
+    /// The number of swaps offering the fee token
+    pub swaps: u64,
+    /// The total amount of the fee token deposited with a CW20 `Send`
+    #[serde(default)]
+    pub deposited: Uint128,
+}
+
+/// This structure describes the CW20 hook messages of the Maker.
+#[cw_serde]
+pub enum Cw20HookMsg {
+    /// Deposits fee tokens in the Maker. If `swap` is set, the token is collected right away
+    /// which fails the deposit if a collect isn't possible yet
+    CollectDeposit { swap: Option<bool> },
+    /// Donates the tokens to the burn pool. The donor and the amount are recorded on chain
+    Donate {},
+    /// Buys a fee token from its auction with CW20 ROIDS. ROIDS paid for more than the unsold
+    /// amount is refunded
+    BidAuction { asset_info: AssetInfo },
+}
+
+/// The hops a fee token takes to ROIDS.
+#[cw_serde]
+pub struct RouteResponse {
+    /// The hops in the order they are swapped
+    pub hops: Vec<RouteHop>,
+}
+
+/// A single swap along the route of a fee token.
+#[cw_serde]
+pub struct RouteHop {
+    /// The asset offered in the swap
+    pub offer_asset: AssetInfo,
+    /// The asset received from the swap
+    pub ask_asset: AssetInfo,
+    /// The pair the swap is made in
+    pub pair: Addr,
+    /// The type of the pair
+    pub pair_type: PairType,
+}
+
+/// The outputs of swapping a fee token to ROIDS.
+#[cw_serde]
+pub struct SwapPreviewResponse {
+    /// The route of the fee token to ROIDS
+    pub route: Vec<AssetInfo>,
+    /// The amount of ROIDS returned at the current pool prices
+    pub expected_roids: Uint128,
+    /// The amount of ROIDS returned if every swap hits the max spread
+    pub conservative_roids: Uint128,
+}
+
+/// The slippage of a single swap along a route to ROIDS.
+#[cw_serde]
+pub struct HopSlippage {
+    /// Index of the hop in the route, starting at 0 for swaps executed by the collect itself
+    pub hop: u64,
+    /// The swapped asset
+    pub offer_asset: String,
+    /// The received asset
+    pub ask_asset: String,
+    /// The spread amount relative to the amount the swap would return without spread
+    pub slippage: Decimal,
+}
+
+/// A successful config or bridge update recorded in the config history.
+#[cw_serde]
+pub struct ConfigHistoryEntry {
+    /// The sequential ID of the entry
+    pub id: u64,
+    /// Block height of the update
+    pub height: u64,
+    /// The address that made the update
+    pub sender: Addr,
+    /// The executed action
+    pub action: String,
+    /// The updated settings along with their new values
+    pub changes: Vec<Attribute>,
+}
+
+/// A collect attributed to its caller.
+#[cw_serde]
+pub struct CollectorRecord {
+    /// The address that executed the collect
+    pub caller: Addr,
+    /// Timestamp (in seconds) of the collect
+    pub timestamp: u64,
+    /// The amount of ROIDS distributed by the collect
+    pub roids_realized: Uint128,
+}
+
+/// A custom struct used to return the result of a collect simulation.
+#[cw_serde]
+pub struct SimulateCollectResponse {
+    /// The amount of ROIDS the collect would produce
+    pub roids_amount: Uint128,
+    /// The swapped amount of each fee token along with the ROIDS it would produce
+    pub assets: Vec<AssetSimulation>,
+}
+
+/// This structure describes the simulated collect of a single fee token.
+#[cw_serde]
+pub struct AssetSimulation {
+    /// The fee token and the amount swapped
+    pub asset: Asset,
+    /// The amount of ROIDS the fee token would produce
+    pub roids_amount: Uint128,
+}
+
+/// This structure describes the simulated swap of a single fee token along its route.
+#[cw_serde]
+pub struct SwapSimulation {
+    /// The fee token and the amount a collect would swap
+    pub asset: Asset,
+    /// The route of the fee token to ROIDS
+    pub route: Vec<AssetInfo>,
+    /// The amount of ROIDS the swaps would return
+    pub roids_amount: Uint128,
+    /// The share of the output lost to the spread over the whole route
+    pub spread: Decimal,
+    /// The reason the fee token can't be swapped, if any
+    pub error: Option<String>,
+}
+
+/// A custom struct used to return the result of the MaxCollectNow query.
+#[cw_serde]
+pub struct MaxCollectNowResponse {
+    /// The amount of ROIDS a collect of `assets` would produce
+    pub roids_amount: Uint128,
+    /// The held assets that can be swapped to ROIDS within the max spread
+    pub assets: Vec<Asset>,
+}
+
+/// A custom struct used to return the result of the CollectStatus query.
+#[cw_serde]
+pub struct CollectStatusResponse {
+    /// Timestamp (in seconds) of the latest collect
+    pub last_collect_ts: u64,
+    /// Timestamp (in seconds) from which a collect passes the cooldown and the post unpause grace
+    /// period
+    pub next_allowed_ts: u64,
+    /// Whether a collect by a regular caller is possible right now. Cooldown exemptions and
+    /// keeper commitments aren't taken into account
+    pub can_collect: bool,
+}
+
+/// A keeper's reservation of the next collect.
+#[cw_serde]
+pub struct KeeperCommitment {
+    /// The keeper that is allowed to collect exclusively
+    pub keeper: Addr,
+    /// Timestamp (in seconds) after which anyone can collect again
+    pub expires_at: u64,
+}
+
+/// This structure describes a fee token whose swap failed and was quarantined.
+#[cw_serde]
+pub struct FailedSwap {
+    /// The asset whose swap failed
+    pub asset: AssetInfo,
+    /// The error returned by the swap
+    pub error: String,
+    /// The ID of the collect the swap failed in
+    pub collect_id: u64,
+}
+
+/// The parameters of the second receiver of distributions.
+#[cw_serde]
+pub struct SecondReceiverParams {
+    /// The address receiving a share of each distribution
+    pub address: String,
+    /// The share of each distribution sent to the address, in bps
+    pub cut_bps: u16,
+}
+
+/// The dev fund receiving a share of each distribution.
+#[cw_serde]
+pub struct DevFundConfig {
+    /// The address of the dev fund
+    pub address: String,
+    /// The share of each distribution sent to the dev fund
+    pub share: Decimal,
+    /// The asset the share is swapped to before being sent
+    pub asset: AssetInfo,
+}
+
+/// This enum describes the terminal action applied to distributed ROIDS.
+#[cw_serde]
+#[derive(Default)]
+pub enum DistributionMode {
+    /// ROIDS is burned with a CW20 burn, or a bank burn for native ROIDS. Superseded by a
+    /// TokenFactory burn when `tokenfactory_burn` is set
+    Burn,
+    /// ROIDS is sent to the Asteroid bridge contract
+    #[default]
+    BridgeToHub,
+    /// ROIDS is accumulated in the Maker until its balance reaches the threshold and then sent
+    /// to the Asteroid bridge contract
+    Hold { threshold: Uint128 },
+    /// Native ROIDS is sent to the Hub over ICS20, bypassing the Asteroid bridge contract.
+    /// Transfers which time out or are rejected are refunded to the Maker by the transfer module
+    /// and distributed again by a later collect or flush. Transferred ROIDS only counts as burned
+    /// once the Hub acknowledges it
+    IbcTransfer {
+        /// The transfer channel to the Hub
+        channel: String,
+        /// The address receiving ROIDS on the Hub
+        receiver: String,
+        /// Seconds after which the transfer times out
+        timeout_secs: u64,
+        /// The memo attached to the transfer, e.g. an inscription. Either empty or a JSON object,
+        /// the Maker adds its IBC hooks callback to it
+        memo: String,
+    },
+}
+
+impl DistributionMode {
+    /// Returns the name of the mode used in attributes
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DistributionMode::Burn => "burn",
+            DistributionMode::BridgeToHub => "bridge_to_hub",
+            DistributionMode::Hold { .. } => "hold",
+            DistributionMode::IbcTransfer { .. } => "ibc_transfer",
+        }
+    }
+}
+
+/// The second receiver of distributions.
+#[cw_serde]
+pub struct SecondReceiverConfig {
+    /// The address receiving a share of each distribution
+    pub address: Addr,
+    /// The share of each distribution sent to the address, in bps
+    pub cut_bps: u16,
+}
+
+/// This structure describes a proposal to send assets out of the Maker.
+#[cw_serde]
+pub struct SeizeProposal {
+    /// The assets to send
+    pub assets: Vec<Asset>,
+    /// The address receiving the assets
+    pub recipient: Addr,
+    /// Timestamp (in seconds) after which the proposal can be executed
+    pub executable_at: u64,
+}
+
+/// This structure describes a declining price auction selling a fee token for ROIDS.
+#[cw_serde]
+pub struct Auction {
+    /// The fee token and its unsold amount
+    pub asset: Asset,
+    /// The price (in ROIDS per unit of the fee token) when the auction starts
+    pub start_price: Decimal,
+    /// The price (in ROIDS per unit of the fee token) the auction declines to
+    pub end_price: Decimal,
+    /// Timestamp (in seconds) when the auction started
+    pub start_ts: u64,
+    /// Timestamp (in seconds) when the price reaches `end_price`
+    pub end_ts: u64,
+}
+
+/// A custom struct used to return the result of the Auction query.
+#[cw_serde]
+pub struct AuctionResponse {
+    pub auction: Auction,
+    /// The current price in ROIDS per unit of the fee token
+    pub price: Decimal,
+}
+
+/// This structure describes the auction a collect starts for a fee token without a swap route.
+/// The whole balance of the fee token is auctioned.
+#[cw_serde]
+pub struct AuctionFallback {
+    /// The price (in ROIDS per unit of the fee token) when the auction starts
+    pub start_price: Decimal,
+    /// The price (in ROIDS per unit of the fee token) the auction declines to
+    pub end_price: Decimal,
+    /// The number of seconds the price takes to decline to `end_price`
+    pub duration_secs: u64,
+}
+
+/// This structure describes a change of the critical settings proposed with
+/// [`ExecuteMsg::ProposeConfigChange`].
+#[cw_serde]
+pub struct ConfigChange {
+    /// The new factory contract
+    pub factory_contract: Option<Addr>,
+    /// The new ROIDS token
+    pub roids_token: Option<AssetInfo>,
+    /// The new Asteroid bridge contract
+    pub asteroid_contract: Option<Addr>,
+    /// The new delay (in seconds) between proposing and applying a config change
+    pub config_change_delay_secs: Option<u64>,
+    /// The new router contract, an empty string removes it
+    pub router_contract: Option<String>,
+    /// The new secondary receiver
+    pub second_receiver_params: Option<SecondReceiverParams>,
+    /// The new dev fund
+    pub dev_fund_config: Option<DevFundConfig>,
+    /// The new distribution mode
+    pub distribution_mode: Option<DistributionMode>,
+    /// The new payload of the Asteroid bridge call
+    pub bridge_call: Option<BridgeCall>,
+    /// The new external DEXes of specific fee tokens
+    pub external_dexes: Option<Vec<(AssetInfo, ExternalDex)>>,
+    /// Timestamp (in seconds) after which the change can be applied
+    pub executable_at: u64,
+}
+
+/// This structure describes a route proposed with [`ExecuteMsg::ProposeBridge`].
+#[cw_serde]
+pub struct BridgeProposal {
+    /// The fee token to route
+    pub asset: AssetInfo,
+    /// The proposed route to ROIDS
+    pub route: Vec<AssetInfo>,
+    /// The address which proposed the route
+    pub proposer: Addr,
+}
+
 /// This structure describes a migration message.
 #[cw_serde]
+#[derive(Default)]
 pub struct MigrateMsg {
-    
+    /// The Asteroid bridge contract. Required when migrating from the Astroport Maker
+    #[serde(default)]
+    pub asteroid_contract: Option<String>,
+    /// The ROIDS token asset info. Required when migrating from the Astroport Maker
+    #[serde(default)]
+    pub roids_token: Option<AssetInfo>,
+}
+
+/// Powers which can be handed over by the owner. Roles which were never assigned are held by
+/// the owner.
+#[cw_serde]
+pub enum Role {
+    /// Manages the bridges and the approved bridge assets
+    BridgeManager,
+    /// Pauses and unpauses collects
+    Pauser,
+}
+
+impl Role {
+    /// Returns every role.
+    pub fn all() -> [Role; 2] {
+        [Role::BridgeManager, Role::Pauser]
+    }
+
+    /// Returns the key the role is stored under.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::BridgeManager => "bridge_manager",
+            Role::Pauser => "pauser",
+        }
+    }
+}
+
+/// The owner and the holder of each role.
+#[cw_serde]
+pub struct PermissionsResponse {
+    /// Address that is allowed to update contract parameters
+    pub owner: Addr,
+    /// The holder of each role
+    pub roles: Vec<(Role, Addr)>,
+}
+
+/// This enum describes which asset types a collect processes.
+#[cw_serde]
+#[derive(Default)]
+pub enum AssetTypeFilter {
+    /// Only native tokens are swapped
+    NativeOnly,
+    /// Only CW20 tokens are swapped
+    Cw20Only,
+    /// All assets are swapped
+    #[default]
+    Both,
+}
+
+impl AssetTypeFilter {
+    /// Returns true if the asset should be processed under this filter.
+    pub fn matches(&self, asset_info: &AssetInfo) -> bool {
+        match self {
+            AssetTypeFilter::NativeOnly => asset_info.is_native_token(),
+            AssetTypeFilter::Cw20Only => !asset_info.is_native_token(),
+            AssetTypeFilter::Both => true,
+        }
+    }
+}
+
+/// This enum describes the minimum ROIDS a fee token must realize when collected.
+#[cw_serde]
+pub enum MinReceived {
+    /// The minimum amount of ROIDS per unit of the fee token
+    Price(Decimal),
+    /// The TWAP of an Astroport oracle pricing the fee token in ROIDS, less a tolerance
+    Oracle {
+        /// The oracle contract address
+        contract: String,
+        /// The share of the TWAP (in bps) a swap may fall short of
+        tolerance_bps: u16,
+    },
+}
+
+/// The TWAP oracle the pool price of an asset is checked against before swapping it.
+#[cw_serde]
+pub struct PriceOracle {
+    /// The Astroport oracle contract address of the pair the asset is swapped in
+    pub contract: String,
+    /// The max deviation (in bps) of the pool price from the TWAP
+    pub max_deviation_bps: u16,
+}
+
+/// The DEX outside the factory a fee token is swapped on. The adapter contract exposes the swap
+/// and simulation interface of an Astroport pair and forwards the swap to the DEX.
+#[cw_serde]
+pub struct ExternalDex {
+    /// The adapter contract address
+    pub adapter: String,
+    /// The asset the fee token is swapped to
+    pub ask_asset: AssetInfo,
+}
+
+/// This enum describes where the belief price of a single hop swap is taken from. The pair
+/// measures the spread against the belief price, so `max_spread` bounds the deviation from it.
+#[cw_serde]
+#[derive(Default)]
+pub enum BeliefPriceSource {
+    /// Swaps are made without a belief price
+    #[default]
+    Disabled,
+    /// The TWAP oracle of the offered asset. Assets without an oracle are swapped without a
+    /// belief price
+    Oracle,
+}
+
+impl BeliefPriceSource {
+    /// Returns the name of the source used in attributes
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BeliefPriceSource::Disabled => "disabled",
+            BeliefPriceSource::Oracle => "oracle",
+        }
+    }
+}
+
+/// This enum describes what a collect does with an asset whose swap exceeds the max spread.
+#[cw_serde]
+#[derive(Default)]
+pub enum FailOrSkip {
+    /// The whole collect reverts
+    #[default]
+    Fail,
+    /// The asset is left for a later collect and reported in the `skipped_spread_exceeded`
+    /// attribute
+    Skip,
+}
+
+impl FailOrSkip {
+    /// Returns the name of the behavior used in attributes
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FailOrSkip::Fail => "fail",
+            FailOrSkip::Skip => "skip",
+        }
+    }
+}
+
+/// This enum describes how the limit of an [`AssetWithLimit`] is applied.
+#[cw_serde]
+#[derive(Default)]
+pub enum LimitSemantics {
+    /// At most `limit` tokens are swapped. A limit above the balance swaps the whole balance
+    #[default]
+    Cap,
+    /// The whole balance is swapped if it is at least `limit`, otherwise the asset is skipped
+    Floor,
 }
 
 /// This struct holds parameters to help with swapping a specific amount of a fee token to ASTRO.
@@ -140,4 +1511,6 @@ pub struct AssetWithLimit {
     pub info: AssetInfo,
     /// The amount of tokens to swap
     pub limit: Option<Uint128>,
+    /// How the limit is applied. Defaults to [`LimitSemantics::Cap`]
+    pub limit_semantics: Option<LimitSemantics>,
 }